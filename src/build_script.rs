@@ -0,0 +1,611 @@
+//! Build metadata for `build.rs`, in the spirit of vergen.
+//!
+//! [`BuildMetadata::collect`](crate::build_script::BuildMetadata::collect)
+//! gathers the version together with git, compiler and build information, and
+//! [`BuildMetadata::emit`](crate::build_script::BuildMetadata::emit) prints it
+//! as `cargo:rustc-env` instructions so the crate can read it with `env!`:
+//!
+//! ```no_run
+//! use cargo_version_info::build_script::BuildMetadata;
+//!
+//! fn main() {
+//!     match BuildMetadata::collect(".") {
+//!         Ok(metadata) => metadata.emit(),
+//!         Err(e) => println!("cargo:warning=Build metadata unavailable: {}", e),
+//!     }
+//! }
+//! ```
+//!
+//! ```ignore
+//! const VERSION: &str = env!("VERSION_INFO_VERSION");
+//! const GIT_SHA: &str = env!("VERSION_INFO_GIT_SHA");
+//! ```
+//!
+//! # Generated Source
+//!
+//! Crates that prefer a typed constant over environment variables can write
+//! `version_info.rs` to `OUT_DIR` with
+//! [`BuildMetadata::write_version_info`](crate::build_script::BuildMetadata::write_version_info)
+//! and include it:
+//!
+//! ```no_run
+//! use cargo_version_info::build_script::BuildMetadata;
+//!
+//! fn main() -> anyhow::Result<()> {
+//!     let metadata = BuildMetadata::collect(".")?;
+//!     metadata.write_version_info()?;
+//!     metadata.emit();
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/version_info.rs"));
+//!
+//! fn main() {
+//!     println!("{} ({})", VERSION_INFO.version, VERSION_INFO.short_sha);
+//! }
+//! ```
+//!
+//! The file defines a `VersionInfo` struct (version, semver parts,
+//! pre-release, build metadata, SHA, dirty flag, commit and build date) and
+//! `pub const VERSION_INFO: VersionInfo`. Both outputs are derived from the
+//! same [`BuildMetadata`](crate::build_script::BuildMetadata), which uses
+//! [`compute_version_string`](crate::commands::compute_version_string) like
+//! the `build-version` command, so they cannot drift apart.
+//!
+//! # Variables
+//!
+//! Every variable is always emitted (empty if unknown), so `env!` never fails:
+//!
+//! - `VERSION_INFO_VERSION`: Version from
+//!   [`compute_version_string`](crate::commands::compute_version_string)
+//! - `VERSION_INFO_GIT_SHA` / `VERSION_INFO_GIT_SHORT_SHA`: Commit at HEAD
+//! - `VERSION_INFO_GIT_BRANCH`: Checked out branch, empty when detached
+//! - `VERSION_INFO_GIT_COMMIT_TIMESTAMP`: Committer time of HEAD (RFC 3339)
+//! - `VERSION_INFO_GIT_DESCRIBE`: `git describe`-style version (see
+//!   [`crate::describe`])
+//! - `VERSION_INFO_DIRTY`: `true` if the working tree has uncommitted changes
+//! - `VERSION_INFO_RUSTC_VERSION`: Output of `rustc --version`
+//! - `VERSION_INFO_TARGET`: Target triple
+//! - `VERSION_INFO_PROFILE`: Build profile (`debug` or `release`)
+//! - `VERSION_INFO_BUILD_TIMESTAMP`: Build time (RFC 3339), taken from
+//!   `SOURCE_DATE_EPOCH` if set for reproducible builds
+//!
+//! # Rebuild Triggers
+//!
+//! Instead of the fixed `.git/HEAD` and `.git/refs`, the `rerun-if-changed`
+//! paths are resolved from the repository: the `HEAD` and `index` of the
+//! current worktree (linked worktrees have their own under
+//! `.git/worktrees/<name>`), the loose ref of the checked out branch,
+//! `packed-refs` and the tags directory. Only existing paths are emitted,
+//! since Cargo reruns the build script on every build for missing ones.
+//!
+//! Unstaged edits to tracked files don't touch any of these paths, so
+//! `VERSION_INFO_DIRTY` is only refreshed when the build script reruns for
+//! another reason.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use semver::Version;
+
+use crate::commands::compute_version_string;
+use crate::describe::{
+    self,
+    DEFAULT_DESCRIBE_FORMAT,
+};
+use crate::{
+    priority,
+    worktree,
+};
+
+/// Name of the generated file written by [`BuildMetadata::write_version_info`].
+pub const VERSION_INFO_FILE: &str = "version_info.rs";
+
+/// Definition of `VersionInfo` at the top of the generated file.
+const VERSION_INFO_STRUCT: &str = "\
+/// Version information generated by cargo-version-info.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionInfo {
+    /// Computed version.
+    pub version: &'static str,
+    /// Major version, 0 if the version is not semver.
+    pub major: u64,
+    /// Minor version, 0 if the version is not semver.
+    pub minor: u64,
+    /// Patch version, 0 if the version is not semver.
+    pub patch: u64,
+    /// Pre-release identifiers, empty for a release.
+    pub pre: &'static str,
+    /// Build metadata identifiers.
+    pub build: &'static str,
+    /// Full SHA of HEAD.
+    pub sha: &'static str,
+    /// Short SHA of HEAD.
+    pub short_sha: &'static str,
+    /// Whether the working tree had uncommitted changes.
+    pub dirty: bool,
+    /// Committer time of HEAD (RFC 3339).
+    pub commit_date: &'static str,
+    /// Build time (RFC 3339).
+    pub build_date: &'static str,
+}
+";
+
+/// Environment variables that influence the emitted metadata.
+const RERUN_IF_ENV_CHANGED: &[&str] = &[
+    "BUILD_VERSION",
+    "CARGO_PKG_VERSION_OVERRIDE",
+    "SOURCE_DATE_EPOCH",
+    describe::DESCRIBE_FORMAT_ENV,
+    crate::commands::DIRTY_SUFFIX_ENV,
+    worktree::INCLUDE_UNTRACKED_ENV,
+];
+
+/// Version, git, compiler and build information for a `build.rs`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BuildMetadata {
+    /// Computed version, see [`compute_version_string`].
+    pub version: String,
+    /// Git metadata, `None` outside a git repository.
+    pub git: Option<GitMetadata>,
+    /// Output of `rustc --version`.
+    pub rustc_version: Option<String>,
+    /// Target triple (`TARGET`).
+    pub target: Option<String>,
+    /// Build profile (`PROFILE`).
+    pub profile: Option<String>,
+    /// Build time in seconds since the Unix epoch.
+    pub build_timestamp: i64,
+}
+
+/// Git information about HEAD of a repository.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct GitMetadata {
+    /// Full SHA of HEAD.
+    pub sha: String,
+    /// Short SHA of HEAD.
+    pub short_sha: String,
+    /// Checked out branch, `None` when HEAD is detached.
+    pub branch: Option<String>,
+    /// Committer time of HEAD in seconds since the Unix epoch.
+    pub commit_timestamp: i64,
+    /// `git describe`-style version.
+    pub describe: String,
+    /// Whether the working tree has uncommitted changes.
+    pub dirty: bool,
+    /// Paths whose changes affect the metadata.
+    pub rerun_if_changed: Vec<PathBuf>,
+}
+
+impl BuildMetadata {
+    /// Collect build metadata for the repository containing `repo_path`.
+    ///
+    /// Reads the environment Cargo provides to build scripts (`RUSTC`,
+    /// `TARGET`, `PROFILE`) as well as `SOURCE_DATE_EPOCH`,
+    /// `VERSION_INFO_DESCRIBE_FORMAT` and `VERSION_INFO_INCLUDE_UNTRACKED`.
+    /// Missing git information is not an error; see [`Self::git`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version cannot be computed.
+    #[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+    pub fn collect(repo_path: impl Into<PathBuf>) -> Result<Self> {
+        let repo_root: PathBuf = repo_path.into();
+        let version = compute_version_string(&repo_root)?;
+        let git = GitMetadata::collect(&repo_root).ok();
+
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let rustc_version = std::process::Command::new(rustc)
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+        let source_date_epoch = std::env::var("SOURCE_DATE_EPOCH").ok();
+
+        Ok(Self {
+            version,
+            git,
+            rustc_version,
+            target: std::env::var("TARGET").ok(),
+            profile: std::env::var("PROFILE").ok(),
+            build_timestamp: build_timestamp(source_date_epoch.as_deref()),
+        })
+    }
+
+    /// The `cargo:` instructions for this metadata, one per line.
+    pub fn instructions(&self) -> Vec<String> {
+        let git = self.git.as_ref();
+        let vars = [
+            ("VERSION_INFO_VERSION", self.version.clone()),
+            (
+                "VERSION_INFO_GIT_SHA",
+                git.map(|git| git.sha.clone()).unwrap_or_default(),
+            ),
+            (
+                "VERSION_INFO_GIT_SHORT_SHA",
+                git.map(|git| git.short_sha.clone()).unwrap_or_default(),
+            ),
+            (
+                "VERSION_INFO_GIT_BRANCH",
+                git.and_then(|git| git.branch.clone()).unwrap_or_default(),
+            ),
+            (
+                "VERSION_INFO_GIT_COMMIT_TIMESTAMP",
+                git.map(|git| rfc3339(git.commit_timestamp))
+                    .unwrap_or_default(),
+            ),
+            (
+                "VERSION_INFO_GIT_DESCRIBE",
+                git.map(|git| git.describe.clone()).unwrap_or_default(),
+            ),
+            (
+                "VERSION_INFO_DIRTY",
+                git.is_some_and(|git| git.dirty).to_string(),
+            ),
+            (
+                "VERSION_INFO_RUSTC_VERSION",
+                self.rustc_version.clone().unwrap_or_default(),
+            ),
+            (
+                "VERSION_INFO_TARGET",
+                self.target.clone().unwrap_or_default(),
+            ),
+            (
+                "VERSION_INFO_PROFILE",
+                self.profile.clone().unwrap_or_default(),
+            ),
+            (
+                "VERSION_INFO_BUILD_TIMESTAMP",
+                rfc3339(self.build_timestamp),
+            ),
+        ];
+
+        let mut instructions: Vec<String> = vars
+            .into_iter()
+            .map(|(key, value)| format!("cargo:rustc-env={}={}", key, value))
+            .collect();
+        instructions.extend(
+            git.into_iter()
+                .flat_map(|git| &git.rerun_if_changed)
+                .map(|path| format!("cargo:rerun-if-changed={}", path.display())),
+        );
+        instructions.extend(
+            RERUN_IF_ENV_CHANGED
+                .iter()
+                .map(|key| format!("cargo:rerun-if-env-changed={}", key)),
+        );
+        instructions
+    }
+
+    /// Print [`Self::instructions`] to stdout for Cargo.
+    pub fn emit(&self) {
+        for instruction in self.instructions() {
+            println!("{}", instruction);
+        }
+    }
+
+    /// Rust source defining `VersionInfo` and the `VERSION_INFO` constant.
+    ///
+    /// Semver parts are zero and empty if the version is not a semantic
+    /// version; git fields are empty outside a git repository.
+    pub fn version_info_source(&self) -> String {
+        let semver = Version::parse(&self.version).ok();
+        let git = self.git.as_ref();
+        let field = |value: Option<&str>| format!("{:?}", value.unwrap_or(""));
+
+        format!(
+            "{VERSION_INFO_STRUCT}
+/// Version information of this build.
+#[allow(dead_code)]
+pub const VERSION_INFO: VersionInfo = VersionInfo {{
+    version: {version},
+    major: {major},
+    minor: {minor},
+    patch: {patch},
+    pre: {pre},
+    build: {build},
+    sha: {sha},
+    short_sha: {short_sha},
+    dirty: {dirty},
+    commit_date: {commit_date},
+    build_date: {build_date},
+}};
+",
+            version = field(Some(&self.version)),
+            major = semver.as_ref().map_or(0, |version| version.major),
+            minor = semver.as_ref().map_or(0, |version| version.minor),
+            patch = semver.as_ref().map_or(0, |version| version.patch),
+            pre = field(semver.as_ref().map(|version| version.pre.as_str())),
+            build = field(semver.as_ref().map(|version| version.build.as_str())),
+            sha = field(git.map(|git| git.sha.as_str())),
+            short_sha = field(git.map(|git| git.short_sha.as_str())),
+            dirty = git.is_some_and(|git| git.dirty),
+            commit_date = field(git.map(|git| rfc3339(git.commit_timestamp)).as_deref()),
+            build_date = field(Some(&rfc3339(self.build_timestamp))),
+        )
+    }
+
+    /// Write [`Self::version_info_source`] to [`VERSION_INFO_FILE`] in
+    /// `OUT_DIR`.
+    ///
+    /// The file is only rewritten when its content changes, so an unchanged
+    /// version does not trigger a recompile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `OUT_DIR` is not set (outside a build script) or
+    /// the file cannot be written.
+    #[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+    pub fn write_version_info(&self) -> Result<PathBuf> {
+        let out_dir = std::env::var("OUT_DIR").context("OUT_DIR is not set")?;
+        self.write_version_info_to(Path::new(&out_dir))
+    }
+
+    /// Write [`Self::version_info_source`] to [`VERSION_INFO_FILE`] in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn write_version_info_to(&self, dir: &Path) -> Result<PathBuf> {
+        let path = dir.join(VERSION_INFO_FILE);
+        let source = self.version_info_source();
+        if std::fs::read_to_string(&path).ok().as_deref() != Some(source.as_str()) {
+            std::fs::write(&path, source)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(path)
+    }
+}
+
+impl GitMetadata {
+    /// Collect git metadata for the repository containing `repo_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository cannot be discovered or HEAD does
+    /// not point to a commit.
+    pub fn collect(repo_path: &Path) -> Result<Self> {
+        let repo = gix::discover(repo_path).with_context(|| {
+            format!(
+                "Failed to discover git repository at {}",
+                repo_path.display()
+            )
+        })?;
+
+        let commit = repo.head_commit().context("Failed to read HEAD commit")?;
+        let commit_timestamp = commit
+            .time()
+            .context("Failed to read commit time of HEAD")?
+            .seconds;
+        let head_name = repo.head_name().context("Failed to read HEAD")?;
+        let branch = head_name
+            .as_ref()
+            .filter(|name| name.category() == Some(gix::reference::Category::LocalBranch))
+            .map(|name| name.shorten().to_string());
+
+        let describe_format =
+            describe::describe_format_from_env().unwrap_or(DEFAULT_DESCRIBE_FORMAT.to_string());
+        let describe = describe::describe(repo_path, worktree::include_untracked_from_env())?;
+
+        Ok(Self {
+            sha: commit.id.to_string(),
+            short_sha: describe.sha.clone(),
+            branch,
+            commit_timestamp,
+            describe: describe.format(&describe_format),
+            dirty: describe.dirty,
+            rerun_if_changed: rerun_if_changed_paths(&repo),
+        })
+    }
+}
+
+/// Existing git paths whose changes affect HEAD, tags or the index.
+fn rerun_if_changed_paths(repo: &gix::Repository) -> Vec<PathBuf> {
+    let mut paths = priority::head_rerun_paths(repo);
+    paths.extend(
+        [
+            repo.git_dir().join("index"),
+            repo.common_dir().join("refs").join("tags"),
+        ]
+        .into_iter()
+        .filter_map(|path| path.canonicalize().ok()),
+    );
+    paths
+}
+
+/// Build time from `SOURCE_DATE_EPOCH`, or the current time.
+fn build_timestamp(source_date_epoch: Option<&str>) -> i64 {
+    source_date_epoch
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs() as i64)
+                .unwrap_or(0)
+        })
+}
+
+/// Format seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+fn rfc3339(seconds: i64) -> String {
+    gix::date::Time::new(seconds, 0).format_or_unix(gix::date::time::format::ISO8601_STRICT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    fn create_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q", "-b", "main"]);
+        git(dir.path(), &["config", "user.email", "test@example.com"]);
+        git(dir.path(), &["config", "user.name", "Test User"]);
+        git(dir.path(), &["config", "commit.gpgsign", "false"]);
+        git(dir.path(), &["config", "tag.gpgsign", "false"]);
+        std::fs::write(dir.path().join("file.txt"), "one").unwrap();
+        git(dir.path(), &["add", "file.txt"]);
+        git(dir.path(), &["commit", "-q", "-m", "one"]);
+        git(dir.path(), &["tag", "v1.0.0"]);
+        dir
+    }
+
+    #[test]
+    fn test_git_metadata() {
+        let dir = create_repo();
+
+        let git_metadata = GitMetadata::collect(dir.path()).unwrap();
+
+        assert_eq!(git_metadata.sha.len(), 40);
+        assert!(git_metadata.sha.starts_with(&git_metadata.short_sha));
+        assert_eq!(git_metadata.branch.as_deref(), Some("main"));
+        assert_eq!(git_metadata.describe, "1.0.0");
+        assert!(!git_metadata.dirty);
+        assert!(git_metadata.commit_timestamp > 0);
+    }
+
+    #[test]
+    fn test_rerun_if_changed_follows_packed_refs() {
+        let dir = create_repo();
+        let git_dir = dir.path().canonicalize().unwrap().join(".git");
+
+        let loose = GitMetadata::collect(dir.path()).unwrap();
+        assert!(
+            loose
+                .rerun_if_changed
+                .contains(&git_dir.join("refs/heads/main"))
+        );
+
+        git(dir.path(), &["pack-refs", "--all"]);
+        let packed = GitMetadata::collect(dir.path()).unwrap();
+        assert!(packed.rerun_if_changed.contains(&git_dir.join("HEAD")));
+        assert!(
+            packed
+                .rerun_if_changed
+                .contains(&git_dir.join("packed-refs"))
+        );
+        assert!(
+            !packed
+                .rerun_if_changed
+                .contains(&git_dir.join("refs/heads/main"))
+        );
+        assert!(
+            packed
+                .rerun_if_changed
+                .contains(&git_dir.join("refs/heads"))
+        );
+    }
+
+    #[test]
+    fn test_rerun_if_changed_in_linked_worktree() {
+        let dir = create_repo();
+        let linked = dir.path().join("linked");
+        git(
+            dir.path(),
+            &["worktree", "add", "-q", "-b", "feature", "linked"],
+        );
+
+        let git_metadata = GitMetadata::collect(&linked).unwrap();
+
+        let root = dir.path().canonicalize().unwrap();
+        let worktree_git_dir = root.join(".git/worktrees/linked");
+        assert_eq!(git_metadata.branch.as_deref(), Some("feature"));
+        assert!(
+            git_metadata
+                .rerun_if_changed
+                .contains(&worktree_git_dir.join("HEAD"))
+        );
+        assert!(
+            git_metadata
+                .rerun_if_changed
+                .contains(&root.join(".git/refs/heads/feature"))
+        );
+    }
+
+    #[test]
+    fn test_build_timestamp_respects_source_date_epoch() {
+        assert_eq!(build_timestamp(Some("1700000000")), 1_700_000_000);
+        assert!(build_timestamp(Some("not-a-number")) > 1_700_000_000);
+        assert_eq!(rfc3339(1_700_000_000), "2023-11-14T22:13:20+00:00");
+    }
+
+    #[test]
+    fn test_version_info_source_compiles() {
+        let dir = create_repo();
+        let metadata = BuildMetadata {
+            version: "1.2.3-rc.1+build.5".to_string(),
+            git: Some(GitMetadata::collect(dir.path()).unwrap()),
+            rustc_version: None,
+            target: None,
+            profile: None,
+            build_timestamp: 1_700_000_000,
+        };
+
+        let path = metadata.write_version_info_to(dir.path()).unwrap();
+        let source = std::fs::read_to_string(&path).unwrap();
+        assert!(source.contains("pre: \"rc.1\","));
+        assert!(source.contains("build: \"build.5\","));
+        assert!(source.contains("build_date: \"2023-11-14T22:13:20+00:00\","));
+
+        let output = std::process::Command::new("rustc")
+            .args([
+                "--edition",
+                "2024",
+                "--crate-type",
+                "lib",
+                "--emit",
+                "metadata",
+            ])
+            .arg("--out-dir")
+            .arg(dir.path())
+            .arg(&path)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    #[test]
+    fn test_instructions_without_git() {
+        let metadata = BuildMetadata {
+            version: "1.2.3".to_string(),
+            git: None,
+            rustc_version: Some("rustc 1.93.0".to_string()),
+            target: Some("x86_64-unknown-linux-gnu".to_string()),
+            profile: Some("release".to_string()),
+            build_timestamp: 0,
+        };
+
+        let instructions = metadata.instructions();
+
+        assert!(instructions.contains(&"cargo:rustc-env=VERSION_INFO_VERSION=1.2.3".to_string()));
+        assert!(instructions.contains(&"cargo:rustc-env=VERSION_INFO_GIT_SHA=".to_string()));
+        assert!(instructions.contains(&"cargo:rustc-env=VERSION_INFO_DIRTY=false".to_string()));
+        assert!(instructions.contains(
+            &"cargo:rustc-env=VERSION_INFO_BUILD_TIMESTAMP=1970-01-01T00:00:00+00:00".to_string()
+        ));
+        assert!(instructions.contains(&"cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH".to_string()));
+        assert!(
+            !instructions
+                .iter()
+                .any(|line| line.starts_with("cargo:rerun-if-changed="))
+        );
+    }
+}
@@ -9,11 +9,15 @@
 //! 1. **BUILD_VERSION** (environment variable) - Preferred for CI workflows
 //! 2. **CARGO_PKG_VERSION_OVERRIDE** (environment variable) - Legacy override
 //! 3. **GitHub API** - Query and calculate next version (only in GitHub
-//!    Actions)
+//!    Actions, and only with the `github` feature)
 //! 4. **CARGO_PKG_VERSION** (environment variable) - From Cargo.toml at build
 //!    time
 //! 5. **Git SHA** - Fallback: `0.0.0-dev-<short-sha>` for local development
 //!
+//! With `--describe` (or `VERSION_INFO_DESCRIBE_FORMAT` set), steps 4 and 5
+//! are replaced by a `git describe`-style version such as
+//! `1.2.3-dev.14+g1a2b3c4` (see [`crate::describe`]).
+//!
 //! # Examples
 //!
 //! ```bash
@@ -25,24 +29,47 @@
 //!
 //! # With BUILD_VERSION set (highest priority)
 //! BUILD_VERSION=1.2.3 cargo version-info build-version
+//!
+//! # Describe-style fallback instead of manifest version + SHA
+//! cargo version-info build-version --describe
 //! ```
 
+use std::cell::OnceCell;
+use std::env;
 use std::path::PathBuf;
-use std::{
-    env,
-    fs,
-};
 
 use anyhow::{
     Context,
     Result,
 };
+#[cfg(feature = "github")]
 use cargo_plugin_utils::common::get_owner_repo;
+#[cfg(feature = "cli")]
 use clap::Parser;
 
+#[cfg(feature = "github")]
+use super::output::CiProvider;
+#[cfg(feature = "cli")]
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+#[cfg(feature = "cli")]
+use crate::describe::DEFAULT_DESCRIBE_FORMAT;
+#[cfg(feature = "github")]
 use crate::github;
+use crate::{
+    describe,
+    priority,
+    worktree,
+};
+
+/// Environment variable with the suffix appended to dirty versions.
+pub const DIRTY_SUFFIX_ENV: &str = "VERSION_INFO_DIRTY_SUFFIX";
 
 /// Arguments for the `build-version` command.
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct BuildVersionArgs {
     /// GitHub repository owner.
@@ -68,6 +95,14 @@ pub struct BuildVersionArgs {
     #[arg(long, env = "GITHUB_TOKEN")]
     github_token: Option<String>,
 
+    /// GitHub REST API base URL.
+    ///
+    /// Only used when falling back to GitHub API (priority 3).
+    /// Defaults to the `GITHUB_API_URL` environment variable (set by GitHub
+    /// Actions) or `https://api.github.com`.
+    #[arg(long, env = "GITHUB_API_URL")]
+    github_api_url: Option<String>,
+
     /// Path to the Cargo.toml manifest file.
     ///
     /// Currently unused but reserved for future use. Defaults to
@@ -82,13 +117,46 @@ pub struct BuildVersionArgs {
     #[arg(long, default_value = ".")]
     repo_path: PathBuf,
 
+    /// Use a `git describe`-style version instead of the manifest version +
+    /// SHA and the `0.0.0-dev-<sha>` fallback (priorities 4 and 5).
+    #[arg(long)]
+    describe: bool,
+
+    /// Format string for `--describe` (implies `--describe`).
+    ///
+    /// Placeholders: `{version}`, `{tag}`, `{distance}`, `{sha}`, `{dirty}`.
+    /// Defaults to `{version}-dev.{distance}+g{sha}{dirty}`.
+    #[arg(long, env = "VERSION_INFO_DESCRIBE_FORMAT")]
+    describe_format: Option<String>,
+
+    /// Suffix appended to git-derived versions (priorities 4 and 5) when the
+    /// working tree has uncommitted changes, e.g. `.dirty`.
+    ///
+    /// In describe mode use the `{dirty}` placeholder instead.
+    #[arg(long, env = DIRTY_SUFFIX_ENV)]
+    dirty_suffix: Option<String>,
+
+    /// Count untracked files (not ignored by `.gitignore`) as dirty.
+    #[arg(long, env = worktree::INCLUDE_UNTRACKED_ENV)]
+    include_untracked: bool,
+
     /// Output format for the build version.
     ///
     /// - `version`: Print just the version number
-    /// - `json`: Print JSON with version and source fields indicating where the
-    ///   version came from (environment, github_api, cargo_toml, or git)
+    /// - `json`: Print JSON with version, dirty and source fields, the source
+    ///   indicating where the version came from (environment, github_api,
+    ///   cargo_toml, git, or git_describe)
+    /// - `github-actions`: Write the same fields to GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same fields as
+    ///   `KEY=value` lines (e.g. `VERSION_INFO_VERSION=0.1.3`)
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "version")]
     format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
 /// Determine the build version using a priority-based fallback system.
@@ -104,7 +172,8 @@ pub struct BuildVersionArgs {
 /// 2. **CARGO_PKG_VERSION_OVERRIDE** environment variable - Legacy script-based
 ///    override mechanism
 /// 3. **GitHub API** - Only checked if running in GitHub Actions (detected via
-///    `GITHUB_ACTIONS` env var). Queries the API to calculate the next version.
+///    `GITHUB_ACTIONS` env var) with the `github` feature enabled. Queries the
+///    API to calculate the next version.
 /// 4. **CARGO_PKG_VERSION** environment variable - Set by Cargo at build time
 ///    from Cargo.toml. Usually "0.0.0" for placeholder versions.
 /// 5. **Git SHA** - Final fallback for local development:
@@ -143,110 +212,257 @@ pub struct BuildVersionArgs {
 ///
 /// With `--format json` (from GitHub API):
 /// ```json
-/// {"version":"0.1.3","source":"github_api"}
+/// {"version":"0.1.3","dirty":false,"source":"github_api"}
 /// ```
 ///
 /// With `--format json` (from CARGO_PKG_VERSION):
 /// ```json
-/// {"version":"0.1.2","source":"cargo_toml"}
+/// {"version":"0.1.2-a1b2c3d","dirty":false,"source":"cargo_toml"}
 /// ```
 ///
 /// With `--format json` (from git SHA fallback):
 /// ```json
-/// {"version":"0.0.0-dev-a1b2c3d","sha":"a1b2c3d","source":"git"}
+/// {"version":"0.0.0-dev-a1b2c3d","sha":"a1b2c3d","dirty":false,"source":"git"}
 /// ```
-#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+///
+/// With `--describe --format json`:
+/// ```json
+/// {"version":"1.2.3-dev.14+g1a2b3c4","sha":"1a2b3c4","distance":14,"dirty":false,"source":"git_describe"}
+/// ```
+#[cfg(feature = "cli")]
 pub fn build_version(args: BuildVersionArgs) -> Result<()> {
-    // Try explicit overrides first (CI workflow should set BUILD_VERSION)
-    let env_version = ["BUILD_VERSION", "CARGO_PKG_VERSION_OVERRIDE"]
-        .into_iter()
-        .find_map(|key| env::var(key).ok())
-        .filter(|v| !v.trim().is_empty());
-
-    if let Some(version) = env_version {
-        match args.format.as_str() {
-            "version" => println!("{}", version),
-            "json" => println!("{{\"version\":\"{}\",\"source\":\"environment\"}}", version),
-            _ => anyhow::bail!("Invalid format: {}", args.format),
+    let format = OutputFormat::parse(&args.format, "version")?;
+    let describe_format = args
+        .describe_format
+        .or_else(|| args.describe.then(|| DEFAULT_DESCRIBE_FORMAT.to_string()));
+    let options = BuildVersionOptions {
+        owner: args.owner,
+        repo: args.repo,
+        github_token: args.github_token,
+        github_api_url: args.github_api_url,
+        manifest: args.manifest,
+        repo_path: args.repo_path,
+        describe_format,
+        dirty_suffix: args.dirty_suffix,
+        include_untracked: args.include_untracked,
+        // The plain version output has no use for the dirty state
+        report_dirty: format != OutputFormat::Text,
+    };
+    let build = compute_build_version(&options)?;
+
+    format.write(&build, &args.output)
+}
+
+#[cfg(feature = "cli")]
+impl CommandOutput for BuildVersion {
+    fn text(&self) -> String {
+        self.version.clone()
+    }
+}
+
+/// Where a [`BuildVersion`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildVersionSource {
+    /// `BUILD_VERSION` or `CARGO_PKG_VERSION_OVERRIDE`.
+    Environment,
+    /// Next version calculated from the GitHub API.
+    GithubApi,
+    /// Manifest version, with the short SHA appended if available.
+    CargoToml,
+    /// `0.0.0-dev-<sha>` fallback.
+    Git,
+    /// `git describe`-style version.
+    GitDescribe,
+}
+
+/// Build version determined by the priority logic of [`build_version`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BuildVersion {
+    /// The version string.
+    pub version: String,
+    /// Short SHA of HEAD, for the git-derived sources.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha: Option<String>,
+    /// Commits since the nearest tag, for [`BuildVersionSource::GitDescribe`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<usize>,
+    /// Whether the working tree has uncommitted changes.
+    ///
+    /// Only checked when needed for the version (describe mode or a dirty
+    /// suffix) or asked for with [`BuildVersionOptions::report_dirty`];
+    /// `false` otherwise.
+    pub dirty: bool,
+    /// Where the version came from.
+    pub source: BuildVersionSource,
+}
+
+/// Inputs for [`compute_build_version`].
+#[derive(Debug, Clone, Default)]
+pub struct BuildVersionOptions {
+    /// GitHub repository owner for the GitHub API step (auto-detected when
+    /// `None`).
+    pub owner: Option<String>,
+    /// GitHub repository name for the GitHub API step (auto-detected when
+    /// `None`).
+    pub repo: Option<String>,
+    /// GitHub token for the GitHub API step (falls back to `GITHUB_TOKEN`).
+    pub github_token: Option<String>,
+    /// GitHub REST API base URL for the GitHub API step.
+    pub github_api_url: Option<String>,
+    /// Path to the Cargo.toml manifest.
+    pub manifest: PathBuf,
+    /// Path to the git repository.
+    pub repo_path: PathBuf,
+    /// Describe format; enables describe mode when set.
+    pub describe_format: Option<String>,
+    /// Suffix appended to git-derived versions when the tree is dirty.
+    pub dirty_suffix: Option<String>,
+    /// Whether untracked files make the tree dirty.
+    pub include_untracked: bool,
+    /// Whether to check the working tree for [`BuildVersion::dirty`] even
+    /// when the version does not depend on it.
+    pub report_dirty: bool,
+}
+
+impl BuildVersionOptions {
+    /// Options for the repository at `repo_path` and its `Cargo.toml`.
+    pub fn new(repo_path: impl Into<PathBuf>) -> Self {
+        let repo_path: PathBuf = repo_path.into();
+        Self {
+            manifest: repo_path.join("Cargo.toml"),
+            repo_path,
+            ..Default::default()
+        }
+    }
+
+    /// Options configured from the `VERSION_INFO_*` environment variables.
+    ///
+    /// Reads `VERSION_INFO_DESCRIBE_FORMAT`, `VERSION_INFO_DIRTY_SUFFIX` and
+    /// `VERSION_INFO_INCLUDE_UNTRACKED`, as used by
+    /// [`compute_version_string`].
+    #[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+    pub fn from_env(repo_path: impl Into<PathBuf>) -> Self {
+        Self {
+            describe_format: describe::describe_format_from_env(),
+            dirty_suffix: env::var(DIRTY_SUFFIX_ENV).ok(),
+            include_untracked: worktree::include_untracked_from_env(),
+            ..Self::new(repo_path)
         }
-        return Ok(());
+    }
+}
+
+/// Determine the build version without printing it.
+///
+/// Implements the priority order of [`build_version`] and returns the
+/// version together with its source, so it can be used in-process:
+///
+/// ```no_run
+/// use cargo_version_info::commands::{
+///     BuildVersionOptions,
+///     compute_build_version,
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let build = compute_build_version(&BuildVersionOptions::new("."))?;
+/// println!("{} (from {:?})", build.version, build.source);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - GitHub API fallback is attempted but the repository cannot be determined
+/// - Git repository cannot be discovered (for SHA fallback)
+/// - HEAD does not point to a valid commit (for SHA fallback)
+#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+pub fn compute_build_version(options: &BuildVersionOptions) -> Result<BuildVersion> {
+    // Hashing the worktree is the slowest step, so only do it when needed
+    let dirty = OnceCell::new();
+    let is_dirty =
+        || *dirty.get_or_init(|| is_repo_dirty(&options.repo_path, options.include_untracked));
+    let build = |version: String, source: BuildVersionSource| BuildVersion {
+        version,
+        sha: None,
+        distance: None,
+        dirty: if options.report_dirty {
+            is_dirty()
+        } else {
+            dirty.get().copied().unwrap_or(false)
+        },
+        source,
+    };
+
+    // Try explicit overrides first (CI workflow should set BUILD_VERSION)
+    if let Some(version) = priority::env_override() {
+        return Ok(build(version, BuildVersionSource::Environment));
     }
 
     // Fallback: Try to query GitHub API via octocrab
-    let is_github_actions = env::var("GITHUB_ACTIONS").is_ok();
-    if is_github_actions {
-        let (owner, repo) = get_owner_repo(args.owner, args.repo)?;
-        let github_token = args.github_token.as_deref();
+    #[cfg(feature = "github")]
+    if CiProvider::detect() == Some(CiProvider::GithubActions) {
+        let (owner, repo) = get_owner_repo(options.owner.clone(), options.repo.clone())?;
+        let context = github::SourceContext {
+            owner: Some(owner),
+            repo: Some(repo),
+            github_token: options.github_token.clone(),
+            github_api_url: options.github_api_url.clone(),
+            ..Default::default()
+        };
 
         let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-        if let Ok((_, next)) =
-            rt.block_on(github::calculate_next_version(&owner, &repo, github_token))
-        {
-            match args.format.as_str() {
-                "version" => println!("{}", next),
-                "json" => println!("{{\"version\":\"{}\",\"source\":\"github_api\"}}", next),
-                _ => anyhow::bail!("Invalid format: {}", args.format),
-            }
-            return Ok(());
+        if let Ok((_, next)) = rt.block_on(github::calculate_next_version(
+            github::VersionSource::Tags,
+            &context,
+        )) {
+            return Ok(build(next, BuildVersionSource::GithubApi));
         }
     }
 
+    // Describe mode: nearest tag + distance + SHA replaces the fallbacks below
+    if let Some(format) = &options.describe_format {
+        let describe = describe::describe(&options.repo_path, options.include_untracked)?;
+        return Ok(BuildVersion {
+            version: describe.format(format),
+            sha: Some(describe.sha),
+            distance: Some(describe.distance),
+            dirty: describe.dirty,
+            source: BuildVersionSource::GitDescribe,
+        });
+    }
+
+    let dirty_suffix = match options.dirty_suffix.as_deref() {
+        Some(suffix) if is_dirty() => suffix,
+        _ => "",
+    };
+
     // Fall back to manifest version (from Cargo.toml), optionally append SHA if
     // available
-    if let Some(manifest_version) = read_manifest_version(&args.manifest) {
-        let trimmed = manifest_version.trim();
-        if !trimmed.is_empty() && trimmed != "0.0.0" {
-            let version_with_sha = short_sha(&args.repo_path)
-                .map(|sha| format!("{trimmed}-{sha}"))
-                .unwrap_or_else(|| trimmed.to_string());
-
-            match args.format.as_str() {
-                "version" => println!("{version_with_sha}"),
-                "json" => println!(
-                    "{{\"version\":\"{}\",\"source\":\"cargo_toml\"}}",
-                    version_with_sha
-                ),
-                _ => anyhow::bail!("Invalid format: {}", args.format),
-            }
-            return Ok(());
-        }
+    if let Some(version) =
+        priority::manifest_version_with_sha(&options.manifest, &options.repo_path, dirty_suffix)
+    {
+        return Ok(build(version, BuildVersionSource::CargoToml));
     }
 
     // Final fallback: git SHA for local dev
-    let repo = gix::discover(&args.repo_path).with_context(|| {
-        format!(
-            "Failed to discover git repository at {}",
-            args.repo_path.display()
-        )
-    })?;
-
-    let head = repo.head().context("Failed to read HEAD")?;
-    let commit_id = head.id().context("HEAD does not point to a commit")?;
-    let short_sha = commit_id
-        .shorten()
-        .context("Failed to shorten commit SHA")?;
-
-    let dev_version = format!("0.0.0-dev-{}", short_sha);
-
-    match args.format.as_str() {
-        "version" => println!("{}", dev_version),
-        "json" => println!(
-            "{{\"version\":\"{}\",\"sha\":\"{}\",\"source\":\"git\"}}",
-            dev_version, short_sha
-        ),
-        _ => anyhow::bail!("Invalid format: {}", args.format),
-    }
+    let (version, short_sha) = priority::dev_version(&options.repo_path, dirty_suffix)?;
 
-    Ok(())
+    Ok(BuildVersion {
+        sha: Some(short_sha),
+        ..build(version, BuildVersionSource::Git)
+    })
 }
 
 /// Compute the build version using default arguments (local repo, version
 /// output).
+#[cfg(feature = "cli")]
 pub fn build_version_default() -> Result<()> {
     build_version_for_repo(".")
 }
 
 /// Compute the build version for a specific repository path.
+#[cfg(feature = "cli")]
 pub fn build_version_for_repo(repo_path: impl Into<PathBuf>) -> Result<()> {
     let repo_root: PathBuf = repo_path.into();
     let manifest = repo_root.join("Cargo.toml");
@@ -255,9 +471,15 @@ pub fn build_version_for_repo(repo_path: impl Into<PathBuf>) -> Result<()> {
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
         manifest,
         repo_path: repo_root,
+        describe: false,
+        describe_format: None,
+        dirty_suffix: None,
+        include_untracked: false,
         format: "version".to_string(),
+        output: OutputArgs::default(),
     })
 }
 
@@ -279,91 +501,69 @@ pub fn build_version_for_repo(repo_path: impl Into<PathBuf>) -> Result<()> {
 ///
 /// 1. **BUILD_VERSION** environment variable
 /// 2. **CARGO_PKG_VERSION_OVERRIDE** environment variable
-/// 3. **GitHub API** (only in GitHub Actions)
+/// 3. **GitHub API** (only in GitHub Actions, with the `github` feature)
 /// 4. **Manifest version** (from Cargo.toml) + git SHA if available
 /// 5. **Git SHA** fallback: `0.0.0-dev-<short-sha>`
+///
+/// Steps 1, 2, 4 and 5 come from [`crate::priority`], which the crate's own
+/// `build.rs` shares.
+///
+/// If the `VERSION_INFO_DESCRIBE_FORMAT` environment variable is set, steps
+/// 4 and 5 are replaced by a `git describe`-style version rendered with that
+/// format (see [`crate::describe`]).
+///
+/// Otherwise, if `VERSION_INFO_DIRTY_SUFFIX` is set and the working tree has
+/// uncommitted changes (untracked files only with
+/// `VERSION_INFO_INCLUDE_UNTRACKED`), the suffix is appended to the versions
+/// of steps 4 and 5. See [`compute_dirty`] for exposing the dirty state
+/// itself.
 pub fn compute_version_string(repo_path: impl Into<PathBuf>) -> Result<String> {
-    let repo_root: PathBuf = repo_path.into();
-    let manifest = repo_root.join("Cargo.toml");
-
-    // Try explicit overrides first (CI workflow should set BUILD_VERSION)
-    let env_version = ["BUILD_VERSION", "CARGO_PKG_VERSION_OVERRIDE"]
-        .into_iter()
-        .find_map(|key| env::var(key).ok())
-        .filter(|v| !v.trim().is_empty());
-
-    if let Some(version) = env_version {
-        return Ok(version);
-    }
-
-    // Fallback: Try to query GitHub API via octocrab
-    let is_github_actions = env::var("GITHUB_ACTIONS").is_ok();
-    if is_github_actions {
-        let (owner, repo) = get_owner_repo(None, None)?;
-        let github_token = None::<String>;
-
-        let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-        if let Ok((_, next)) = rt.block_on(github::calculate_next_version(
-            &owner,
-            &repo,
-            github_token.as_deref(),
-        )) {
-            return Ok(next);
-        }
-    }
-
-    // Fall back to manifest version (from Cargo.toml), optionally append SHA if
-    // available
-    if let Some(manifest_version) = read_manifest_version(&manifest) {
-        let trimmed = manifest_version.trim();
-        if !trimmed.is_empty() && trimmed != "0.0.0" {
-            let version_with_sha = short_sha(&repo_root)
-                .map(|sha| format!("{trimmed}-{sha}"))
-                .unwrap_or_else(|| trimmed.to_string());
-            return Ok(version_with_sha);
-        }
-    }
+    Ok(compute_build_version(&BuildVersionOptions::from_env(repo_path))?.version)
+}
 
-    // Final fallback: git SHA for local dev
+/// Check whether the working tree has uncommitted changes, for `build.rs`.
+///
+/// Untracked files count only if `VERSION_INFO_INCLUDE_UNTRACKED` is set.
+/// Emit the result as an environment variable so the binary can report it:
+///
+/// ```no_run
+/// use cargo_version_info::commands::compute_dirty;
+///
+/// if let Ok(dirty) = compute_dirty(".") {
+///     println!("cargo:rustc-env=VERSION_INFO_DIRTY={}", dirty);
+///     println!("cargo:rerun-if-changed=.git/index");
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the repository cannot be discovered or its status
+/// cannot be read.
+pub fn compute_dirty(repo_path: impl Into<PathBuf>) -> Result<bool> {
+    let repo_root: PathBuf = repo_path.into();
     let repo = gix::discover(&repo_root).with_context(|| {
         format!(
             "Failed to discover git repository at {}",
             repo_root.display()
         )
     })?;
-
-    let head = repo.head().context("Failed to read HEAD")?;
-    let commit_id = head.id().context("HEAD does not point to a commit")?;
-    let short_sha = commit_id
-        .shorten()
-        .context("Failed to shorten commit SHA")?;
-
-    Ok(format!("0.0.0-dev-{}", short_sha))
-}
-
-fn short_sha(repo_path: &PathBuf) -> Option<String> {
-    let repo = gix::discover(repo_path).ok()?;
-    let head = repo.head().ok()?;
-    let commit_id = head.id()?;
-    let short = commit_id.shorten().ok()?;
-    Some(short.to_string())
+    worktree::is_dirty(&repo, worktree::include_untracked_from_env())
 }
 
-fn read_manifest_version(manifest: &PathBuf) -> Option<String> {
-    let contents = fs::read_to_string(manifest).ok()?;
-    let value: toml::Value = toml::from_str(&contents).ok()?;
-    value
-        .get("package")
-        .and_then(|pkg| pkg.get("version"))
-        .and_then(|v| v.as_str())
-        .map(ToString::to_string)
+/// Whether the repository at `repo_path` is dirty, `false` if unknown.
+fn is_repo_dirty(repo_path: &PathBuf, include_untracked: bool) -> bool {
+    gix::discover(repo_path)
+        .ok()
+        .and_then(|repo| worktree::is_dirty(&repo, include_untracked).ok())
+        .unwrap_or(false)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "cli"))]
 mod tests {
     use std::env;
 
     use super::*;
+    use crate::test_util::git;
 
     #[test]
     fn test_build_version_env_priority() {
@@ -375,9 +575,15 @@ mod tests {
             owner: None,
             repo: None,
             github_token: None,
+            github_api_url: None,
             manifest: "./Cargo.toml".into(),
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            dirty_suffix: None,
+            include_untracked: false,
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         let result = build_version(args);
         unsafe {
@@ -395,9 +601,15 @@ mod tests {
             owner: None,
             repo: None,
             github_token: None,
+            github_api_url: None,
             manifest: "./Cargo.toml".into(),
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            dirty_suffix: None,
+            include_untracked: false,
             format: "json".to_string(),
+            output: OutputArgs::default(),
         };
         let result = build_version(args);
         unsafe {
@@ -418,9 +630,15 @@ mod tests {
             owner: None,
             repo: None,
             github_token: None,
+            github_api_url: None,
             manifest: "./Cargo.toml".into(),
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            dirty_suffix: None,
+            include_untracked: false,
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         let result = build_version(args);
         unsafe {
@@ -439,9 +657,15 @@ mod tests {
             owner: None,
             repo: None,
             github_token: None,
+            github_api_url: None,
             manifest: "./Cargo.toml".into(),
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            dirty_suffix: None,
+            include_untracked: false,
             format: "invalid".to_string(),
+            output: OutputArgs::default(),
         };
         let result = build_version(args);
         unsafe {
@@ -459,9 +683,15 @@ mod tests {
             owner: None,
             repo: None,
             github_token: None,
+            github_api_url: None,
             manifest: "./Cargo.toml".into(),
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            dirty_suffix: None,
+            include_untracked: false,
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         let result = build_version(args);
         unsafe {
@@ -481,9 +711,15 @@ mod tests {
             owner: None,
             repo: None,
             github_token: None,
+            github_api_url: None,
             manifest: "./Cargo.toml".into(),
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            dirty_suffix: None,
+            include_untracked: false,
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         let result = build_version(args);
         unsafe {
@@ -493,4 +729,58 @@ mod tests {
         // BUILD_VERSION should take priority
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_build_version_json_shape() {
+        let cargo_toml = BuildVersion {
+            version: "0.1.2-a1b2c3d".to_string(),
+            sha: None,
+            distance: None,
+            dirty: false,
+            source: BuildVersionSource::CargoToml,
+        };
+        assert_eq!(
+            serde_json::to_string(&cargo_toml).unwrap(),
+            r#"{"version":"0.1.2-a1b2c3d","dirty":false,"source":"cargo_toml"}"#
+        );
+
+        let describe = BuildVersion {
+            version: "1.2.3-dev.14+g1a2b3c4".to_string(),
+            sha: Some("1a2b3c4".to_string()),
+            distance: Some(14),
+            dirty: true,
+            source: BuildVersionSource::GitDescribe,
+        };
+        assert_eq!(
+            serde_json::to_string(&describe).unwrap(),
+            r#"{"version":"1.2.3-dev.14+g1a2b3c4","sha":"1a2b3c4","distance":14,"dirty":true,"source":"git_describe"}"#
+        );
+    }
+
+    #[test]
+    fn test_compute_build_version_checks_dirty_only_when_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| git(dir.path(), args);
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test User"]);
+        git(&["config", "commit.gpgsign", "false"]);
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        git(&["add", "Cargo.toml"]);
+        git(&["commit", "-q", "-m", "initial"]);
+        std::fs::write(dir.path().join("Cargo.toml"), "# changed\n").unwrap();
+
+        let options = BuildVersionOptions::new(dir.path());
+        assert!(!compute_build_version(&options).unwrap().dirty);
+
+        let options = BuildVersionOptions {
+            report_dirty: true,
+            ..BuildVersionOptions::new(dir.path())
+        };
+        assert!(compute_build_version(&options).unwrap().dirty);
+    }
 }
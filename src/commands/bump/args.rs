@@ -9,7 +9,8 @@
 //! the target version:
 //!
 //! - **Manual**: `--version X.Y.Z` - Explicitly set the version
-//! - **Auto**: `--auto` - Automatically suggest from GitHub releases
+//! - **Auto**: `--auto` - Next patch version after the latest version from
+//!   `--source` (git tags by default, or GitHub releases, Cargo.toml, registry)
 //! - **Major**: `--major` - Increment major version (X.0.0)
 //! - **Minor**: `--minor` - Increment minor version (X.Y.0)
 //! - **Patch**: `--patch` - Increment patch version (X.Y.Z)
@@ -31,12 +32,20 @@
 //!
 //! # Auto-suggest from GitHub releases
 //! cargo version-info bump --auto --github-token $TOKEN
+//!
+//! # Preview the changes and the commit without writing anything
+//! cargo version-info bump --minor --dry-run
+//!
+//! # Bump the release branch while the worktree is on main
+//! cargo version-info bump --patch --branch release/1.x
 //! ```
 
 use std::path::PathBuf;
 
 use clap::Parser;
 
+use crate::github::VersionSource;
+
 /// Arguments for the `bump` command.
 ///
 /// This struct uses `clap`'s derive macros to automatically parse command-line
@@ -66,10 +75,11 @@ pub struct BumpArgs {
     #[arg(long, conflicts_with_all = ["auto", "major", "minor", "patch"])]
     pub version: Option<String>,
 
-    /// Automatically suggest the target version from GitHub releases.
+    /// Automatically suggest the target version from the latest release.
     ///
-    /// This queries the GitHub API to find the latest release and suggests
-    /// the next appropriate version. Requires `--owner` and `--repo` (or
+    /// Reads the latest released version from `--source` (local git tags by
+    /// default) and suggests the next patch version. With `--source
+    /// releases`, the GitHub API is queried using `--owner` and `--repo` (or
     /// the `GITHUB_REPOSITORY` environment variable).
     ///
     /// Optionally use `--github-token` or `GITHUB_TOKEN` env var for
@@ -138,6 +148,36 @@ pub struct BumpArgs {
     #[arg(long, env = "GITHUB_TOKEN")]
     pub github_token: Option<String>,
 
+    /// GitHub REST API base URL (for --auto).
+    ///
+    /// Defaults to the `GITHUB_API_URL` environment variable (set by GitHub
+    /// Actions) or `https://api.github.com`. Set this for GitHub Enterprise
+    /// Server, e.g. `https://ghe.example.com/api/v3`.
+    #[arg(long, env = "GITHUB_API_URL")]
+    pub github_api_url: Option<String>,
+
+    /// Where `--auto` reads the latest released version from.
+    ///
+    /// - `tags` (default): Highest semver git tag in the local repository
+    /// - `releases`: Latest GitHub release
+    /// - `cargo-toml`: Current version in Cargo.toml
+    /// - `registry`: Latest non-yanked version published on crates.io (or
+    ///   `--registry`/`--index`)
+    #[arg(long, value_enum, default_value_t = VersionSource::Tags)]
+    pub source: VersionSource,
+
+    /// Alternative registry declared in `.cargo/config.toml` (for --auto).
+    ///
+    /// Only used with `--source registry`.
+    #[arg(long, conflicts_with = "index")]
+    pub registry: Option<String>,
+
+    /// Sparse registry index URL or local index directory (for --auto).
+    ///
+    /// Only used with `--source registry`.
+    #[arg(long)]
+    pub index: Option<String>,
+
     /// Don't commit changes, just update files.
     ///
     /// When this flag is set, the version will be updated in Cargo.toml but
@@ -160,4 +200,35 @@ pub struct BumpArgs {
     /// version. Use this flag to skip this step.
     #[arg(long)]
     pub no_readme: bool,
+
+    /// Show what the bump would do without doing it.
+    ///
+    /// Prints the target version, the changes to every file (Cargo.toml,
+    /// Cargo.lock, README.md and configured files), the content the commit
+    /// would record for each file, and the commit message, as unified diffs.
+    /// No files are written, no git objects are created and no hooks are run.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Leave modified files in place if the bump fails.
+    ///
+    /// By default, a failure after the files were updated (in `cargo
+    /// update`, a pre-bump hook, commit signing or the commit itself)
+    /// restores Cargo.toml, Cargo.lock, README.md, the configured files and
+    /// the git index to their state before the bump. Use this flag to
+    /// inspect the modified files instead.
+    #[arg(long)]
+    pub keep_on_failure: bool,
+
+    /// Create the bump commit on this branch without checking it out.
+    ///
+    /// Reads the files from the branch, applies the version changes in
+    /// memory and moves only the branch to the new commit. The worktree,
+    /// the index and HEAD are not touched, and hooks and `additional_files`
+    /// are skipped. The branch must be a local branch that is not checked
+    /// out in any worktree. With --auto, only `--source tags` (tags
+    /// reachable from the branch) and `--source cargo-toml` (the branch's
+    /// Cargo.toml) are supported.
+    #[arg(long, value_name = "NAME", conflicts_with = "no_commit")]
+    pub branch: Option<String>,
 }
@@ -0,0 +1,534 @@
+//! Bumping a branch that is not checked out.
+//!
+//! `bump --branch release/1.x` creates the bump commit on `release/1.x`
+//! while the worktree stays on another branch. The files are read from the
+//! branch's tree, the version edits are applied in memory, and the commit
+//! is built from blobs with the same plumbing as a normal bump (see
+//! [`commit`]). Only the branch reference moves: the
+//! worktree, the index and HEAD are left alone.
+//!
+//! # Differences from a Normal Bump
+//!
+//! - The current version and `[package.metadata.version-info]` are read from
+//!   the Cargo.toml on the branch
+//! - Cargo.lock only gets the crate's own entry updated, as in `--dry-run`,
+//!   instead of running `cargo update --workspace`
+//! - Hooks and `additional_files` are skipped, since they work on the worktree
+//! - `--auto` looks at the branch: `--source tags` takes the highest tag
+//!   reachable from the branch, `--source cargo-toml` the branch's Cargo.toml,
+//!   and the other sources are rejected
+//! - The branch must be a local branch (`refs/heads/`) that is not checked out
+//!   in any worktree
+//!
+//! # Examples
+//!
+//! ```bash
+//! # Release 1.4.3 from release/1.x while working on main
+//! cargo version-info bump --patch --branch release/1.x
+//!
+//! # Preview the commit
+//! cargo version-info bump --patch --branch release/1.x --dry-run
+//! ```
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+
+use super::args::BumpArgs;
+use super::hooks::VersionInfoConfig;
+use super::{
+    commit,
+    doc_versions,
+    dry_run,
+    readme_update,
+    version_update,
+    workflow,
+};
+use crate::github::VersionSource;
+use crate::version::{
+    format_version,
+    increment_patch,
+    parse_version,
+};
+
+/// A file the bump changes on the branch.
+#[derive(Debug)]
+struct BranchFile {
+    /// Path relative to the repository root.
+    path: PathBuf,
+    /// Content on the branch.
+    original: String,
+    /// Content after the bump.
+    updated: String,
+}
+
+/// Create the version bump commit on `branch` without checking it out.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The branch doesn't exist, is not under `refs/heads/`, or is checked out in
+///   a worktree
+/// - Cargo.toml on the branch has no version
+/// - A version file or reference pattern is invalid
+/// - The commit cannot be created or signed, or the branch moved meanwhile
+pub fn bump_branch(
+    args: &BumpArgs,
+    branch: &str,
+    logger: &mut cargo_plugin_utils::logger::Logger,
+) -> Result<()> {
+    let manifest_path = args
+        .manifest_path
+        .as_deref()
+        .unwrap_or_else(|| Path::new("./Cargo.toml"));
+    let manifest_dir = match manifest_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let repo = gix::discover(manifest_dir).context("Not in a git repository")?;
+    let base_dir = repo_relative_dir(&repo, manifest_dir)?;
+    let manifest = base_dir.join(manifest_path.file_name().unwrap_or("Cargo.toml".as_ref()));
+
+    // Step 1: Read the version and configuration from the branch
+    logger.status("Reading", branch);
+    let reference_name = if branch.starts_with("refs/heads/") {
+        branch.to_string()
+    } else if branch.starts_with("refs/") {
+        anyhow::bail!("{} is not a branch", branch);
+    } else {
+        format!("refs/heads/{}", branch)
+    };
+    let mut reference = repo
+        .find_reference(reference_name.as_str())
+        .with_context(|| format!("Branch {} not found", branch))?;
+    if let Some(worktree) = checked_out_in(&repo, &reference_name)? {
+        anyhow::bail!(
+            "Branch {} is checked out in {}; run bump there without --branch",
+            branch,
+            worktree.display()
+        );
+    }
+    let tip = reference
+        .peel_to_id()
+        .with_context(|| format!("Branch {} does not point to a commit", branch))?
+        .detach();
+    let tree = repo
+        .find_commit(tip)
+        .context("Failed to find branch commit")?
+        .tree()
+        .context("Failed to get branch tree")?;
+    let manifest_content = read_file(&tree, &manifest)?
+        .with_context(|| format!("{} does not exist on {}", manifest.display(), branch))?;
+    let (package_name, current_version, config) =
+        read_manifest(&manifest_content, &manifest, branch)?;
+    logger.finish();
+
+    // Step 2: Calculate the target version
+    logger.status("Calculating", "target version");
+    let target_version = if args.auto && args.version.is_none() {
+        auto_target_version(args, &repo, tip, &current_version)?
+    } else {
+        super::calculate_target_version(args, &current_version)?
+    };
+    logger.finish();
+    if current_version == target_version {
+        anyhow::bail!(
+            "Current version ({}) is already the target version. Nothing to bump.",
+            current_version
+        );
+    }
+
+    // Step 3: Apply the version edits in memory
+    logger.status("Updating", "files");
+    let context = BranchContext {
+        args,
+        tree: &tree,
+        base_dir: &base_dir,
+        package_name: &package_name,
+        current_version: &current_version,
+        target_version: &target_version,
+        config: &config,
+    };
+    let files = updated_files(&context, &manifest, &manifest_content)?;
+    let message = commit::bump_commit_message(
+        &repo,
+        &config.commit,
+        &package_name,
+        &current_version,
+        &target_version,
+        tip,
+    )?;
+    logger.finish();
+
+    if args.dry_run {
+        let mut out = format!(
+            "Dry run: {} {} -> {} on {} (nothing written)\n\nCommit changes:\n",
+            package_name, current_version, target_version, branch
+        );
+        for file in &files {
+            let name = file.path.display();
+            out.push_str(&dry_run::unified_diff(
+                &file.original,
+                &file.updated,
+                &format!("a/{}", name),
+                &format!("b/{}", name),
+            ));
+        }
+        out.push_str("\nCommit message:\n");
+        out.push_str(&dry_run::indent_message(&message));
+        print!("{}", out);
+        return Ok(());
+    }
+
+    if !config.pre_bump_hooks.is_empty()
+        || !config.post_bump_hooks.is_empty()
+        || !config.additional_files.is_empty()
+    {
+        workflow::warning(
+            "Skipping hooks and additional_files with --branch, since they work on the worktree",
+        );
+    }
+
+    // Step 4: Commit and move the branch
+    logger.status("Committing", &format!("to {}", branch));
+    let updates: Vec<(PathBuf, String)> = files
+        .into_iter()
+        .map(|file| (file.path, file.updated))
+        .collect();
+    let commit_id =
+        commit::commit_files_to_branch(&repo, &reference_name, tip, &updates, &message)?;
+    logger.finish();
+
+    logger.print_message(&format!(
+        "✓ Committed version bump on {}: {} -> {} ({} file{})",
+        branch,
+        current_version,
+        target_version,
+        updates.len(),
+        if updates.len() == 1 { "" } else { "s" }
+    ));
+    workflow::append_step_summary(&super::bump_summary(
+        &package_name,
+        &current_version,
+        &target_version,
+        Some(&commit_id.to_string()),
+    ))?;
+
+    Ok(())
+}
+
+/// What a branch bump works on.
+struct BranchContext<'a> {
+    args: &'a BumpArgs,
+    tree: &'a gix::Tree<'a>,
+    base_dir: &'a Path,
+    package_name: &'a str,
+    current_version: &'a str,
+    target_version: &'a str,
+    config: &'a VersionInfoConfig,
+}
+
+/// The files the bump changes on the branch, starting with Cargo.toml.
+///
+/// Follows the steps of a normal bump: Cargo.toml, Cargo.lock, README.md,
+/// version files, doc versions and version references.
+fn updated_files(
+    context: &BranchContext,
+    manifest: &Path,
+    manifest_content: &str,
+) -> Result<Vec<BranchFile>> {
+    let BranchContext {
+        args,
+        tree,
+        base_dir,
+        package_name,
+        current_version,
+        target_version,
+        config,
+    } = *context;
+    let mut files = Vec::new();
+    let mut push = |path: PathBuf, original: String, updated: String| {
+        if updated != original {
+            files.push(BranchFile {
+                path,
+                original,
+                updated,
+            });
+        }
+    };
+
+    push(
+        manifest.to_path_buf(),
+        manifest_content.to_string(),
+        version_update::update_cargo_toml_content(manifest, manifest_content, target_version)?,
+    );
+    let mut handled = vec![manifest.to_path_buf()];
+
+    let cargo_lock = base_dir.join("Cargo.lock");
+    if !args.no_lock
+        && let Some(content) = read_file(tree, &cargo_lock)?
+    {
+        let updated =
+            version_update::update_cargo_lock_content(&content, package_name, target_version);
+        push(cargo_lock.clone(), content, updated);
+    }
+    handled.push(cargo_lock);
+
+    let readme = base_dir.join("README.md");
+    if !args.no_readme {
+        if let Some(content) = read_file(tree, &readme)? {
+            let updated = readme_update::update_readme_content(
+                &content,
+                package_name,
+                current_version,
+                target_version,
+            )?
+            .content;
+            push(readme.clone(), content, updated);
+        }
+        handled.push(readme);
+    }
+
+    for file in &config.version_files {
+        let path = base_dir.join(&file.path);
+        let content = read_file(tree, &path)?
+            .with_context(|| format!("{} does not exist on the branch", path.display()))?;
+        let updated = file
+            .apply(&content, target_version)
+            .with_context(|| format!("Failed to update version in {}", path.display()))?;
+        push(path.clone(), content, updated);
+        handled.push(path);
+    }
+
+    let lib_rs = base_dir.join(doc_versions::LIB_RS);
+    let mut doc_paths = Vec::new();
+    if read_file(tree, &lib_rs)?.is_some() {
+        doc_paths.push(lib_rs);
+    }
+    doc_paths.extend(config.doc_files.iter().map(|file| base_dir.join(file)));
+    for path in doc_paths {
+        if handled.contains(&path) {
+            continue;
+        }
+        let content = read_file(tree, &path)?
+            .with_context(|| format!("{} does not exist on the branch", path.display()))?;
+        let updated = doc_versions::update_doc_versions(
+            &content,
+            package_name,
+            current_version,
+            target_version,
+        )?;
+        push(path.clone(), content, updated);
+        handled.push(path);
+    }
+
+    if !config.version_references.is_empty() {
+        for relative in tree_files(tree, base_dir)? {
+            let path = base_dir.join(&relative);
+            let matching: Vec<_> = config
+                .version_references
+                .iter()
+                .filter(|reference| reference.matches_path(&relative))
+                .collect();
+            if matching.is_empty() || handled.contains(&path) {
+                continue;
+            }
+            // Like on the worktree, files that are not valid UTF-8 are skipped
+            let Some(Ok(content)) = read_blob(tree, &path)?.map(String::from_utf8) else {
+                continue;
+            };
+            let mut updated = content.clone();
+            for reference in matching {
+                updated = reference.apply(&updated, current_version, target_version)?;
+            }
+            push(path, content, updated);
+        }
+    }
+
+    Ok(files)
+}
+
+/// The package name, version and configuration in the Cargo.toml `content`
+/// on `branch`.
+///
+/// A version inherited from `[workspace.package]` is read from the same
+/// file.
+fn read_manifest(
+    content: &str,
+    manifest: &Path,
+    branch: &str,
+) -> Result<(String, String, VersionInfoConfig)> {
+    let document: toml::Table = toml::from_str(content)
+        .with_context(|| format!("Failed to parse {} on {}", manifest.display(), branch))?;
+    let package = document
+        .get("package")
+        .and_then(|package| package.as_table())
+        .with_context(|| format!("No [package] in {} on {}", manifest.display(), branch))?;
+    let name = package
+        .get("name")
+        .and_then(|name| name.as_str())
+        .with_context(|| format!("No package name in {} on {}", manifest.display(), branch))?;
+    let version = package
+        .get("version")
+        .and_then(|version| version.as_str())
+        .or_else(|| {
+            document
+                .get("workspace")?
+                .get("package")?
+                .get("version")?
+                .as_str()
+        })
+        .with_context(|| format!("No version in {} on {}", manifest.display(), branch))?;
+    let config = package
+        .get("metadata")
+        .and_then(|metadata| metadata.get("version-info"))
+        .and_then(|config| config.clone().try_into().ok())
+        .unwrap_or_default();
+    Ok((name.to_string(), version.to_string(), config))
+}
+
+/// Content of the file at the repository-relative `path` in `tree`, or
+/// `None` if there is no such file.
+fn read_file(tree: &gix::Tree, path: &Path) -> Result<Option<String>> {
+    read_blob(tree, path)?
+        .map(|data| {
+            String::from_utf8(data)
+                .with_context(|| format!("{} is not valid UTF-8", path.display()))
+        })
+        .transpose()
+}
+
+/// Raw content of the file at the repository-relative `path` in `tree`, or
+/// `None` if there is no such file.
+fn read_blob(tree: &gix::Tree, path: &Path) -> Result<Option<Vec<u8>>> {
+    let Some(entry) = tree
+        .lookup_entry_by_path(path)
+        .with_context(|| format!("Failed to look up {}", path.display()))?
+    else {
+        return Ok(None);
+    };
+    if !entry.mode().is_blob() {
+        return Ok(None);
+    }
+    let mut blob = entry
+        .object()
+        .context("Failed to get blob from tree entry")?
+        .try_into_blob()
+        .context("Tree entry is not a blob")?;
+    Ok(Some(blob.take_data()))
+}
+
+/// Files in `tree` under `base_dir`, relative to it with `/` separators.
+fn tree_files(tree: &gix::Tree, base_dir: &Path) -> Result<Vec<String>> {
+    let mut recorder = gix::traverse::tree::Recorder::default();
+    tree.traverse()
+        .breadthfirst(&mut recorder)
+        .context("Failed to list branch files")?;
+
+    let prefix = base_dir.to_string_lossy().replace('\\', "/");
+    let prefix = if prefix.is_empty() {
+        prefix
+    } else {
+        format!("{}/", prefix)
+    };
+    Ok(recorder
+        .records
+        .into_iter()
+        .filter(|entry| entry.mode.is_blob())
+        .filter_map(|entry| {
+            entry
+                .filepath
+                .to_string()
+                .strip_prefix(&prefix)
+                .map(str::to_string)
+        })
+        .collect())
+}
+
+/// The `--auto` target version on a branch: the next patch version after the
+/// highest semver tag reachable from `tip` (`--source tags`) or after the
+/// version in the branch's Cargo.toml (`--source cargo-toml`).
+///
+/// The other sources do not depend on the branch, so they are rejected.
+fn auto_target_version(
+    args: &BumpArgs,
+    repo: &gix::Repository,
+    tip: gix::ObjectId,
+    current_version: &str,
+) -> Result<String> {
+    let latest = match args.source {
+        VersionSource::Tags => crate::describe::highest_reachable_tag(repo, tip)?
+            .map(|(_, version)| version.to_string()),
+        VersionSource::CargoToml => Some(current_version.to_string()),
+        source => anyhow::bail!(
+            "--auto --source {} does not depend on the branch; use --source tags or --source \
+             cargo-toml with --branch",
+            source
+        ),
+    };
+    let Some(latest) = latest else {
+        // Nothing released on the branch yet, start at 0.0.1
+        return Ok("0.0.1".to_string());
+    };
+    let (major, minor, patch) = parse_version(&latest)
+        .with_context(|| format!("Failed to parse latest version: {}", latest))?;
+    let (major, minor, patch) = increment_patch(major, minor, patch);
+    Ok(format_version(major, minor, patch))
+}
+
+/// The worktree that has `reference_name` checked out, if any.
+///
+/// Checks the main worktree and every linked worktree of `repo`.
+fn checked_out_in(repo: &gix::Repository, reference_name: &str) -> Result<Option<PathBuf>> {
+    let is_checked_out = |repo: &gix::Repository| -> Result<bool> {
+        Ok(repo
+            .head_name()
+            .context("Failed to read HEAD")?
+            .is_some_and(|head| head.as_bstr() == reference_name.as_bytes()))
+    };
+    let worktree_dir = |repo: &gix::Repository| {
+        repo.workdir()
+            .unwrap_or_else(|| repo.git_dir())
+            .to_path_buf()
+    };
+
+    let main_repo = repo
+        .main_repo()
+        .context("Failed to open the main worktree")?;
+    if is_checked_out(&main_repo)? {
+        return Ok(Some(worktree_dir(&main_repo)));
+    }
+    for worktree in repo.worktrees().context("Failed to list worktrees")? {
+        let base = worktree.base().ok();
+        let worktree = worktree
+            .into_repo_with_possibly_inaccessible_worktree()
+            .context("Failed to open worktree")?;
+        if is_checked_out(&worktree)? {
+            return Ok(Some(base.unwrap_or_else(|| worktree_dir(&worktree))));
+        }
+    }
+    Ok(None)
+}
+
+/// `dir` relative to the worktree root of `repo`.
+fn repo_relative_dir(repo: &gix::Repository, dir: &Path) -> Result<PathBuf> {
+    let workdir = repo
+        .workdir()
+        .context("Cannot bump a branch in a bare repository")?;
+    let workdir = workdir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", workdir.display()))?;
+    let dir = dir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", dir.display()))?;
+    Ok(dir
+        .strip_prefix(&workdir)
+        .context("Cargo.toml is outside the git worktree")?
+        .to_path_buf())
+}
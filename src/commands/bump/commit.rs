@@ -110,14 +110,26 @@ use bstr::ByteSlice;
 use smallvec::SmallVec;
 
 use super::diff;
+use super::message::{
+    self,
+    CommitMessageConfig,
+    MessageContext,
+};
+use super::version_files::VersionFile;
 
 /// Type of additional file for selective staging.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub enum FileType {
     /// Cargo.lock file - filter to only our crate's version changes
     CargoLock,
-    /// README.md file - filter to only version reference changes
-    Readme,
+    /// README.md file - filter to only lines holding one of the reference
+    /// patterns (see [`super::readme_update::reference_patterns`])
+    Readme(Vec<regex::Regex>),
+    /// Configured version file - stage only the value its definition rewrites
+    VersionFile(VersionFile),
+    /// File changed by configured version references - filter to only lines
+    /// matching one of the patterns
+    VersionReference(Vec<regex::Regex>),
     /// Other files - commit full content
     Other,
 }
@@ -155,6 +167,10 @@ pub struct AdditionalFile {
 /// * `old_version` - The previous version (for verification and commit message)
 /// * `new_version` - The new version (for verification and commit message)
 ///
+/// # Returns
+///
+/// Returns the ID of the new commit.
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -224,7 +240,8 @@ pub struct AdditionalFile {
 /// - Tree: Built from the staged index
 /// - Parents: Current HEAD commit
 /// - Author/Committer: From git config or defaults
-/// - Message: Conventional commit format "chore(version): bump X -> Y"
+/// - Message: Conventional commit format "chore(version): bump X -> Y" with the
+///   [`BUMP_TRAILER`]
 ///
 /// ## HEAD Update
 ///
@@ -235,9 +252,16 @@ pub fn commit_version_changes(
     crate_name: &str,
     old_version: &str,
     new_version: &str,
-) -> Result<()> {
+) -> Result<gix::ObjectId> {
     // Call the multi-file version with no additional files
-    commit_version_changes_with_files(manifest_path, crate_name, old_version, new_version, &[])
+    commit_version_changes_with_files(
+        manifest_path,
+        crate_name,
+        old_version,
+        new_version,
+        &[],
+        &CommitMessageConfig::default(),
+    )
 }
 
 /// Commit version-related changes along with additional files.
@@ -254,6 +278,8 @@ pub fn commit_version_changes(
 /// * `new_version` - The new version
 /// * `additional_files` - List of additional files to include with their
 ///   content and metadata for selective staging
+/// * `commit_config` - The commit message template and trailers (see
+///   [`message`])
 ///
 /// # Selective Staging
 ///
@@ -262,17 +288,22 @@ pub fn commit_version_changes(
 /// - **Cargo.toml**: Only lines containing "version" or the version strings
 /// - **Cargo.lock**: Only our crate's package entry changes
 /// - **README.md**: Only lines with `crate-name = "version"` patterns
+/// - **Version files**: Only lines containing the old or new version
+/// - **Version references**: Only lines matching the reference's pattern
 /// - **Other files**: Full content (no filtering)
 ///
 /// This ensures that unrelated uncommitted changes (typo fixes, dependency
 /// updates, etc.) are not accidentally included in the version bump commit.
+///
+/// Returns the ID of the new commit.
 pub fn commit_version_changes_with_files(
     manifest_path: &Path,
     crate_name: &str,
     old_version: &str,
     new_version: &str,
     additional_files: &[AdditionalFile],
-) -> Result<()> {
+    commit_config: &CommitMessageConfig,
+) -> Result<gix::ObjectId> {
     // Discover git repository by walking up from the manifest's directory
     let repo = gix::discover(manifest_path.parent().unwrap_or_else(|| Path::new(".")))
         .context("Not in a git repository")?;
@@ -313,24 +344,15 @@ pub fn commit_version_changes_with_files(
     // Get HEAD content for comparison
     let head_content = get_head_content(&head_tree, relative_path)?;
 
-    // Check if there are non-version changes in the file
-    let has_other_changes =
-        diff::has_non_version_changes(&head_content, &current_content, old_version, new_version);
-
-    // Create the content to stage for Cargo.toml
-    let staged_content = if has_other_changes {
-        // File has non-version changes - apply only version hunks
+    // Stage only the version lines of Cargo.toml
+    let (manifest_content, hunk_level) =
+        staged_manifest_content(&head_content, &current_content, old_version, new_version)?;
+    if hunk_level {
         eprintln!("⚠️  Using hunk-level staging: only version lines will be committed.");
-
-        // Apply only version-related hunks
-        diff::apply_version_hunks(&head_content, &current_content, old_version, new_version)?
-    } else {
-        // File only has version changes - stage the whole file
-        current_content.clone()
-    };
+    }
 
     // Create blob for Cargo.toml
-    let cargo_toml_blob_id = write_blob(&repo, &staged_content)?;
+    let cargo_toml_blob_id = write_blob(&repo, &manifest_content)?;
 
     // Build list of file updates for the tree
     let mut file_updates: Vec<(std::path::PathBuf, gix::ObjectId)> = Vec::new();
@@ -344,60 +366,14 @@ pub fn commit_version_changes_with_files(
             .or_else(|_| file.path.strip_prefix("."))
             .unwrap_or(&file.path);
 
-        // Determine what content to commit based on file type and available HEAD
-        // content
-        let content_to_commit = match (&file.head_content, file.file_type) {
-            (Some(head_content), FileType::Readme) => {
-                // Check if there are non-version changes
-                if diff::has_non_readme_version_changes(
-                    head_content,
-                    &file.working_content,
-                    crate_name,
-                    old_version,
-                    new_version,
-                ) {
-                    eprintln!(
-                        "⚠️  Using hunk-level staging for README.md: only version lines will be committed."
-                    );
-                    diff::apply_readme_version_hunks(
-                        head_content,
-                        &file.working_content,
-                        crate_name,
-                        old_version,
-                        new_version,
-                    )?
-                } else {
-                    file.working_content.clone()
-                }
-            }
-            (Some(head_content), FileType::CargoLock) => {
-                // Check if there are non-version changes
-                if diff::has_non_cargo_lock_version_changes(
-                    head_content,
-                    &file.working_content,
-                    crate_name,
-                    old_version,
-                    new_version,
-                ) {
-                    eprintln!(
-                        "⚠️  Using hunk-level staging for Cargo.lock: only our crate's version will be committed."
-                    );
-                    diff::apply_cargo_lock_version_hunks(
-                        head_content,
-                        &file.working_content,
-                        crate_name,
-                        old_version,
-                        new_version,
-                    )?
-                } else {
-                    file.working_content.clone()
-                }
-            }
-            _ => {
-                // No HEAD content or Other file type - commit full content
-                file.working_content.clone()
-            }
-        };
+        let (content_to_commit, hunk_level) =
+            staged_content(file, crate_name, old_version, new_version)?;
+        if hunk_level {
+            eprintln!(
+                "⚠️  Using hunk-level staging for {}: only version changes will be committed.",
+                file_relative_path.display()
+            );
+        }
 
         let blob_id = write_blob(&repo, &content_to_commit)?;
         file_updates.push((file_relative_path.to_path_buf(), blob_id));
@@ -407,10 +383,18 @@ pub fn commit_version_changes_with_files(
     let tree_id = update_tree_with_files(&repo, &head_tree, &file_updates)?;
 
     // Create the commit
-    let commit_id = create_commit(&repo, &tree_id, head_commit_id, old_version, new_version)?;
+    let message = bump_commit_message(
+        &repo,
+        commit_config,
+        crate_name,
+        old_version,
+        new_version,
+        head_commit_id.detach(),
+    )?;
+    let commit_id = create_commit(&repo, &tree_id, head_commit_id, &message)?;
 
     // Update HEAD to point to the new commit
-    update_head(&repo, commit_id)?;
+    update_head(&repo, commit_id, "bump version")?;
 
     // Reset the index to match HEAD
     // This is necessary because we created the commit via direct tree manipulation,
@@ -418,7 +402,187 @@ pub fn commit_version_changes_with_files(
     // in the index, causing confusing `git status` output.
     reset_index_to_head(&repo)?;
 
-    Ok(())
+    Ok(commit_id)
+}
+
+/// Trailer that marks commits created by `bump`.
+pub const BUMP_TRAILER: &str = "Bumped-by: cargo-version-info";
+
+/// The default message of the version bump commit, in conventional commits
+/// format, ending with the [`BUMP_TRAILER`].
+pub fn commit_message(old_version: &str, new_version: &str) -> String {
+    format!(
+        "chore(version): bump {} -> {}\n\n{}\n",
+        old_version, new_version, BUMP_TRAILER
+    )
+}
+
+/// Whether `message` is a commit message created by `bump`, i.e. its last
+/// paragraph contains the [`BUMP_TRAILER`].
+///
+/// The subject is not checked, since it can be configured.
+///
+/// # Examples
+///
+/// ```rust
+/// use cargo_version_info::commands::bump::commit::{
+///     commit_message,
+///     is_bump_message,
+/// };
+///
+/// assert!(is_bump_message(&commit_message("0.1.0", "0.2.0")));
+/// assert!(!is_bump_message("chore(version): bump 0.1.0 -> 0.2.0"));
+/// ```
+pub fn is_bump_message(message: &str) -> bool {
+    let message = message.trim_end();
+    message.contains("\n\n")
+        && message
+            .rsplit("\n\n")
+            .next()
+            .is_some_and(|trailers| trailers.lines().any(|line| line.trim_end() == BUMP_TRAILER))
+}
+
+/// Render the bump commit message configured in `config` for a commit on top
+/// of `parent_id`.
+///
+/// Reads the history of `parent_id` for `{changelog}` and the git identity
+/// for the `Signed-off-by` trailer only when they are used.
+///
+/// # Errors
+///
+/// Returns an error if the history cannot be read, or if `signoff` is set
+/// and `user.name` or `user.email` is not.
+pub fn bump_commit_message(
+    repo: &gix::Repository,
+    config: &CommitMessageConfig,
+    crate_name: &str,
+    old_version: &str,
+    new_version: &str,
+    parent_id: gix::ObjectId,
+) -> Result<String> {
+    let changelog = if config.needs_changelog() {
+        let old_tag = config.tag_name(crate_name, old_version);
+        message::changelog_since_last_bump(repo, parent_id, &old_tag)?
+    } else {
+        String::new()
+    };
+    let signed_off_by = if config.signoff {
+        let signature = get_signature_from_config(repo)?;
+        Some(format!("{} <{}>", signature.name, signature.email))
+    } else {
+        None
+    };
+    let tag = config.tag_name(crate_name, new_version);
+    let context = MessageContext {
+        package: crate_name,
+        old_version,
+        new_version,
+        tag: &tag,
+        changelog: &changelog,
+    };
+    Ok(config.render(&context, signed_off_by.as_deref()))
+}
+
+/// Content of Cargo.toml to commit: the working content, or HEAD's content
+/// with only the version lines applied if the file has other changes.
+///
+/// Returns the content and whether hunk-level staging was needed.
+///
+/// # Errors
+///
+/// Returns an error if the version hunks cannot be applied.
+pub fn staged_manifest_content(
+    head_content: &str,
+    current_content: &str,
+    old_version: &str,
+    new_version: &str,
+) -> Result<(String, bool)> {
+    if diff::has_non_version_changes(head_content, current_content, old_version, new_version) {
+        let staged =
+            diff::apply_version_hunks(head_content, current_content, old_version, new_version)?;
+        Ok((staged, true))
+    } else {
+        Ok((current_content.to_string(), false))
+    }
+}
+
+/// Content of an additional file to commit, filtered according to its
+/// [`FileType`].
+///
+/// Files without HEAD content and [`FileType::Other`] files are committed as
+/// they are. Returns the content and whether hunk-level staging was needed.
+///
+/// # Errors
+///
+/// Returns an error if the version hunks cannot be applied.
+pub fn staged_content(
+    file: &AdditionalFile,
+    crate_name: &str,
+    old_version: &str,
+    new_version: &str,
+) -> Result<(String, bool)> {
+    let Some(head_content) = &file.head_content else {
+        return Ok((file.working_content.clone(), false));
+    };
+    let working = &file.working_content;
+
+    let staged = match &file.file_type {
+        FileType::Readme(patterns)
+            if diff::has_non_readme_version_changes(
+                head_content,
+                working,
+                patterns,
+                old_version,
+                new_version,
+            ) =>
+        {
+            diff::apply_readme_version_hunks(
+                head_content,
+                working,
+                patterns,
+                old_version,
+                new_version,
+            )?
+        }
+        FileType::CargoLock
+            if diff::has_non_cargo_lock_version_changes(
+                head_content,
+                working,
+                crate_name,
+                old_version,
+                new_version,
+            ) =>
+        {
+            diff::apply_cargo_lock_version_hunks(
+                head_content,
+                working,
+                crate_name,
+                old_version,
+                new_version,
+            )?
+        }
+        FileType::VersionFile(version_file) => {
+            // Rewrite the version in HEAD's content the same way the working
+            // file was rewritten, so only that value is staged even if HEAD's
+            // version was out of step with Cargo.toml
+            let staged = version_file
+                .apply(head_content, new_version)
+                .with_context(|| format!("Failed to update version in {}", version_file.path))?;
+            if staged == *working {
+                return Ok((staged, false));
+            }
+            staged
+        }
+        FileType::VersionReference(patterns) => {
+            let is_version_line = |line: &str| patterns.iter().any(|re| re.is_match(line));
+            if !diff::has_non_matching_changes(head_content, working, is_version_line) {
+                return Ok((working.clone(), false));
+            }
+            diff::apply_matching_hunks(head_content, working, is_version_line)
+        }
+        _ => return Ok((working.clone(), false)),
+    };
+    Ok((staged, true))
 }
 
 /// Get the content of a file from the HEAD tree.
@@ -679,24 +843,19 @@ fn update_tree_with_files(
 /// * `repo` - The git repository
 /// * `tree_id` - The tree object ID (root tree of the commit)
 /// * `parent_id` - The parent commit ID (current HEAD)
-/// * `old_version` - Previous version (for commit message)
-/// * `new_version` - New version (for commit message)
+/// * `commit_message` - The commit message
 ///
 /// # Returns
 ///
 /// Returns the object ID of the newly created commit.
-fn create_commit(
+pub(crate) fn create_commit(
     repo: &gix::Repository,
     tree_id: &gix::ObjectId,
     parent_id: gix::Id,
-    old_version: &str,
-    new_version: &str,
+    commit_message: &str,
 ) -> Result<gix::ObjectId> {
     use super::signing;
 
-    // Create commit message following conventional commits format
-    let commit_message = format!("chore(version): bump {} -> {}", old_version, new_version);
-
     // Get author and committer from git config
     let author = get_signature_from_config(repo)?;
     let committer = author.clone();
@@ -712,7 +871,7 @@ fn create_commit(
     let extra_headers = if signing_config.enabled {
         // Build the commit payload that will be signed
         let payload =
-            signing::build_commit_payload(tree_id, parent_id, &author, &committer, &commit_message);
+            signing::build_commit_payload(tree_id, parent_id, &author, &committer, commit_message);
 
         // Sign the payload
         match signing::sign_commit_payload(&signing_config, &payload) {
@@ -750,6 +909,48 @@ fn create_commit(
     Ok(commit_id)
 }
 
+/// Commit `files` on top of `parent_id` and move `reference_name` to the new
+/// commit, without touching the worktree, the index or HEAD.
+///
+/// `files` holds repository-relative paths and their full new content. The
+/// reference is only moved if it still points to `parent_id`.
+///
+/// # Errors
+///
+/// Returns an error if the objects cannot be written, signing fails, or the
+/// reference was moved by someone else.
+pub(crate) fn commit_files_to_branch(
+    repo: &gix::Repository,
+    reference_name: &str,
+    parent_id: gix::ObjectId,
+    files: &[(PathBuf, String)],
+    commit_message: &str,
+) -> Result<gix::ObjectId> {
+    let parent = repo
+        .find_commit(parent_id)
+        .context("Failed to find branch commit")?;
+    let parent_tree = parent.tree().context("Failed to get branch tree")?;
+
+    let mut file_updates = Vec::new();
+    for (path, content) in files {
+        file_updates.push((path.clone(), write_blob(repo, content)?));
+    }
+    let tree_id = update_tree_with_files(repo, &parent_tree, &file_updates)?;
+    let commit_id = create_commit(repo, &tree_id, parent.id(), commit_message)?;
+
+    repo.reference(
+        reference_name,
+        commit_id,
+        gix::refs::transaction::PreviousValue::MustExistAndMatch(gix::refs::Target::Object(
+            parent_id,
+        )),
+        "bump version",
+    )
+    .with_context(|| format!("Failed to update {}", reference_name))?;
+
+    Ok(commit_id)
+}
+
 /// Update HEAD to point to the new commit.
 ///
 /// This moves the current branch forward to include the new commit. This is
@@ -768,6 +969,7 @@ fn create_commit(
 ///
 /// * `repo` - The git repository
 /// * `commit_id` - The object ID of the commit to point HEAD to
+/// * `reflog_message` - The message of the reflog entry
 ///
 /// # Errors
 ///
@@ -775,7 +977,11 @@ fn create_commit(
 /// - HEAD doesn't exist or is invalid
 /// - HEAD is not a reference (detached HEAD state)
 /// - Reference update fails
-fn update_head(repo: &gix::Repository, commit_id: gix::ObjectId) -> Result<()> {
+pub(crate) fn update_head(
+    repo: &gix::Repository,
+    commit_id: gix::ObjectId,
+    reflog_message: &str,
+) -> Result<()> {
     // Read current HEAD
     let mut head_ref = repo
         .head()
@@ -786,7 +992,7 @@ fn update_head(repo: &gix::Repository, commit_id: gix::ObjectId) -> Result<()> {
     // Update the reference to point to the new commit
     // This is an atomic operation - either succeeds completely or fails
     head_ref
-        .set_target_id(commit_id, "bump version")
+        .set_target_id(commit_id, reflog_message)
         .context("Failed to update HEAD reference")?;
 
     Ok(())
@@ -814,7 +1020,7 @@ fn update_head(repo: &gix::Repository, commit_id: gix::ObjectId) -> Result<()> {
 /// - HEAD cannot be read
 /// - The tree cannot be accessed
 /// - The index file cannot be written
-fn reset_index_to_head(repo: &gix::Repository) -> Result<()> {
+pub(crate) fn reset_index_to_head(repo: &gix::Repository) -> Result<()> {
     // Get HEAD commit and its tree
     let mut head = repo.head().context("Failed to read HEAD")?;
     let head_commit = head
@@ -838,6 +1044,45 @@ fn reset_index_to_head(repo: &gix::Repository) -> Result<()> {
     Ok(())
 }
 
+/// Reset the index entries of `paths` to their state in `tree_id`.
+///
+/// Unlike [`reset_index_to_head`], staged changes to every other path are
+/// kept. Paths missing from the tree are removed from the index, as with
+/// `git reset <tree> -- <paths>`.
+///
+/// # Errors
+///
+/// Returns an error if the index cannot be read or written, or the tree
+/// cannot be read.
+pub(crate) fn reset_index_paths(
+    repo: &gix::Repository,
+    tree_id: gix::ObjectId,
+    paths: &[bstr::BString],
+) -> Result<()> {
+    let validate_opts = gix::validate::path::component::Options::default();
+    let tree_state = gix::index::State::from_tree(&tree_id, &repo.objects, validate_opts)
+        .context("Failed to create index state from tree")?;
+    let mut index = repo.open_index().context("Failed to read index")?;
+
+    let is_reset = |path: &bstr::BStr| paths.iter().any(|reset| reset == path);
+    index.remove_entries(|_, path, _| is_reset(path));
+    for entry in tree_state.entries() {
+        let path = entry.path(&tree_state);
+        if is_reset(path) {
+            index.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path);
+        }
+    }
+    index.sort_entries();
+    // The cached trees no longer match the entries
+    index.remove_tree();
+
+    index
+        .write(gix::index::write::Options::default())
+        .context("Failed to write index")?;
+
+    Ok(())
+}
+
 /// Get git signature (author/committer) from repository config.
 ///
 /// Reads the `user.name` and `user.email` from git config and creates a
@@ -73,9 +73,12 @@ use anyhow::{
 use regex::Regex;
 use similar::{
     ChangeTag,
+    DiffOp,
     TextDiff,
 };
 
+use super::readme_update;
+
 /// Apply only version-related hunks to create partially-staged content.
 ///
 /// This is the core function that implements selective hunk staging. It:
@@ -222,42 +225,25 @@ pub fn has_non_version_changes(
 
 /// Check if a line is version-related for README.md.
 ///
-/// A line is README-version-related if it matches the pattern
-/// `crate-name = "version"` (typical TOML dependency format).
+/// A line is README-version-related if it holds one of the references that
+/// [`readme_update`] rewrites (`crate-name = "version"`, inline tables,
+/// `cargo add crate-name@version`, `cargo install crate-name --version
+/// version`) and its requirement refers to the old or new version.
 ///
 /// # Arguments
 ///
 /// * `line` - The line to check
-/// * `crate_name` - The crate name (hyphenated or underscored)
+/// * `patterns` - The README reference patterns of the crate (see
+///   [`readme_update::reference_patterns`])
 /// * `old_version` - The version being replaced
 /// * `new_version` - The version being added
 fn is_readme_version_line(
     line: &str,
-    crate_name: &str,
+    patterns: &[Regex],
     old_version: &str,
     new_version: &str,
 ) -> bool {
-    // Handle both hyphenated and underscored package names
-    let hyphenated = crate_name.replace('_', "-");
-    let underscored = crate_name.replace('-', "_");
-
-    for name in [&hyphenated, &underscored] {
-        // Pattern: name = "version" (with optional whitespace)
-        for version in [old_version, new_version] {
-            let pattern = format!(
-                r#"{}(\s|[-_])?\s*=\s*"{}""#,
-                regex::escape(name),
-                regex::escape(version)
-            );
-            if let Ok(re) = Regex::new(&pattern)
-                && re.is_match(line)
-            {
-                return true;
-            }
-        }
-    }
-
-    false
+    readme_update::is_version_reference_line(line, patterns, old_version, new_version)
 }
 
 /// Apply only README version-related hunks to create partially-staged content.
@@ -269,7 +255,7 @@ fn is_readme_version_line(
 ///
 /// * `head_content` - Content of README.md in HEAD commit
 /// * `working_content` - Content of README.md in working directory
-/// * `crate_name` - The crate/package name
+/// * `patterns` - The README reference patterns of the crate
 /// * `old_version` - The version string being replaced
 /// * `new_version` - The version string being added
 ///
@@ -279,42 +265,15 @@ fn is_readme_version_line(
 pub fn apply_readme_version_hunks(
     head_content: &str,
     working_content: &str,
-    crate_name: &str,
+    patterns: &[Regex],
     old_version: &str,
     new_version: &str,
 ) -> Result<String> {
-    let diff = TextDiff::from_lines(head_content, working_content);
-
-    let mut result = Vec::new();
-
-    for change in diff.iter_all_changes() {
-        let line = change.value();
-        let is_version_related = is_readme_version_line(line, crate_name, old_version, new_version);
-
-        match change.tag() {
-            ChangeTag::Equal => {
-                result.push(line);
-            }
-            ChangeTag::Delete => {
-                if is_version_related {
-                    // Version line being removed - apply the change (skip it)
-                } else {
-                    // Non-version line - keep original
-                    result.push(line);
-                }
-            }
-            ChangeTag::Insert => {
-                if is_version_related {
-                    // Version line being added - apply the change (include it)
-                    result.push(line);
-                } else {
-                    // Non-version line - don't apply the change (skip it)
-                }
-            }
-        }
-    }
-
-    Ok(result.join(""))
+    Ok(apply_matching_hunks(
+        head_content,
+        working_content,
+        |line| is_readme_version_line(line, patterns, old_version, new_version),
+    ))
 }
 
 /// Check if README.md has changes beyond version modifications.
@@ -323,7 +282,7 @@ pub fn apply_readme_version_hunks(
 ///
 /// * `head_content` - Content from HEAD
 /// * `working_content` - Content from working directory
-/// * `crate_name` - The crate/package name
+/// * `patterns` - The README reference patterns of the crate
 /// * `old_version` - Old version string
 /// * `new_version` - New version string
 ///
@@ -333,25 +292,90 @@ pub fn apply_readme_version_hunks(
 pub fn has_non_readme_version_changes(
     head_content: &str,
     working_content: &str,
-    crate_name: &str,
+    patterns: &[Regex],
     old_version: &str,
     new_version: &str,
 ) -> bool {
+    has_non_matching_changes(head_content, working_content, |line| {
+        is_readme_version_line(line, patterns, old_version, new_version)
+    })
+}
+
+/// Apply only the changes to lines accepted by `is_version_line`.
+///
+/// This is the general form of [`apply_readme_version_hunks`]: changed lines
+/// are grouped into blocks between unchanged lines; within a block, removed
+/// lines are kept unless they are version lines, which are replaced in place
+/// by the added version lines. Added lines that are not version lines are
+/// left out.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cargo_version_info::commands::bump::diff::apply_matching_hunks;
+/// let head = "FROM rust\nLABEL version=\"0.1.0\"\nRUN make\n";
+/// let working = "FROM rust:slim\nLABEL version=\"0.2.0\"\nRUN make\n";
+///
+/// let staged = apply_matching_hunks(head, working, |line| line.starts_with("LABEL version="));
+/// assert_eq!(staged, "FROM rust\nLABEL version=\"0.2.0\"\nRUN make\n");
+/// ```
+pub fn apply_matching_hunks(
+    head_content: &str,
+    working_content: &str,
+    is_version_line: impl Fn(&str) -> bool,
+) -> String {
     let diff = TextDiff::from_lines(head_content, working_content);
 
-    for change in diff.iter_all_changes() {
-        if matches!(change.tag(), ChangeTag::Delete | ChangeTag::Insert) {
-            let line = change.value();
-            let is_version_related =
-                is_readme_version_line(line, crate_name, old_version, new_version);
+    let mut result = Vec::new();
+    // Lines removed and added since the last unchanged line
+    let mut deleted = Vec::new();
+    let mut inserted = Vec::new();
 
-            if !is_version_related {
-                return true;
+    for change in diff.iter_all_changes() {
+        let line = change.value();
+        match change.tag() {
+            ChangeTag::Delete => deleted.push(line),
+            ChangeTag::Insert if is_version_line(line) => inserted.push(line),
+            ChangeTag::Insert => {}
+            ChangeTag::Equal => {
+                merge_version_lines(&mut result, &mut deleted, &mut inserted, &is_version_line);
+                result.push(line);
             }
         }
     }
+    merge_version_lines(&mut result, &mut deleted, &mut inserted, &is_version_line);
 
-    false
+    result.join("")
+}
+
+/// Resolve one block of changes: keep removed lines unless they are version
+/// lines, which are replaced in place by the added version lines.
+fn merge_version_lines<'a>(
+    result: &mut Vec<&'a str>,
+    deleted: &mut Vec<&'a str>,
+    inserted: &mut Vec<&'a str>,
+    is_version_line: impl Fn(&str) -> bool,
+) {
+    let mut replacements = inserted.drain(..);
+    for line in deleted.drain(..) {
+        if !is_version_line(line) {
+            result.push(line);
+        } else if let Some(replacement) = replacements.next() {
+            result.push(replacement);
+        }
+    }
+    result.extend(replacements);
+}
+
+/// Check if any changed line is not accepted by `is_version_line`.
+pub fn has_non_matching_changes(
+    head_content: &str,
+    working_content: &str,
+    is_version_line: impl Fn(&str) -> bool,
+) -> bool {
+    TextDiff::from_lines(head_content, working_content)
+        .iter_all_changes()
+        .any(|change| change.tag() != ChangeTag::Equal && !is_version_line(change.value()))
 }
 
 /// Locate our crate's `[[package]]` block in a Cargo.lock by structure.
@@ -363,7 +387,7 @@ pub fn has_non_readme_version_changes(
 /// preserves Cargo.lock formatting.
 ///
 /// Returns `None` if no `[[package]]` block names our crate.
-fn find_package_block(content: &str, crate_name: &str) -> Option<(usize, usize)> {
+pub(super) fn find_package_block(content: &str, crate_name: &str) -> Option<(usize, usize)> {
     let target_name = format!(r#"name = "{crate_name}""#);
     let mut cursor = 0usize;
     let mut current_block_start: Option<usize> = None;
@@ -453,6 +477,84 @@ pub fn has_non_cargo_lock_version_changes(
     }
 }
 
+/// Undo the changes from `parent` to `committed` in `working`, keeping the
+/// other changes in `working`.
+///
+/// This is the reverse of applying selected hunks: `committed` is a commit's
+/// version of a file, `parent` its parent's version and `working` the
+/// worktree content, which may have uncommitted changes on top. Each line
+/// the commit changed is put back to the parent's version.
+///
+/// Returns `None` if the worktree changed one of the lines the commit
+/// changed (a conflict, as `git revert` would report it).
+///
+/// # Examples
+///
+/// ```rust
+/// use cargo_version_info::commands::bump::diff::revert_changes;
+///
+/// let parent = "version = \"0.1.0\"\nedition = \"2021\"\n";
+/// let committed = "version = \"0.2.0\"\nedition = \"2021\"\n";
+/// let working = "version = \"0.2.0\"\nedition = \"2024\"\n";
+///
+/// assert_eq!(
+///     revert_changes(working, committed, parent).unwrap(),
+///     "version = \"0.1.0\"\nedition = \"2024\"\n"
+/// );
+/// ```
+pub fn revert_changes(working: &str, committed: &str, parent: &str) -> Option<String> {
+    let committed_lines: Vec<&str> = committed.split_inclusive('\n').collect();
+    let working_lines: Vec<&str> = working.split_inclusive('\n').collect();
+    let parent_lines: Vec<&str> = parent.split_inclusive('\n').collect();
+
+    // Position in `working` of each line of `committed` that is unchanged
+    let mut positions = vec![None; committed_lines.len()];
+    for op in TextDiff::from_slices(&committed_lines, &working_lines).ops() {
+        if let DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = *op
+        {
+            for offset in 0..len {
+                positions[old_index + offset] = Some(new_index + offset);
+            }
+        }
+    }
+
+    let mut result = String::with_capacity(working.len());
+    let mut last = 0;
+    for op in TextDiff::from_slices(&committed_lines, &parent_lines).ops() {
+        if matches!(op, DiffOp::Equal { .. }) {
+            continue;
+        }
+        let (old, new) = (op.old_range(), op.new_range());
+
+        // The range of `working` that holds the lines the commit changed
+        let start = if old.start == 0 {
+            0
+        } else if old.is_empty() {
+            positions[old.start - 1]? + 1
+        } else {
+            positions[old.start]?
+        };
+        for (offset, line) in old.clone().enumerate() {
+            if positions[line]? != start + offset {
+                return None;
+            }
+        }
+        if start < last {
+            return None;
+        }
+
+        result.extend(working_lines[last..start].iter().copied());
+        result.extend(parent_lines[new].iter().copied());
+        last = start + old.len();
+    }
+    result.extend(working_lines[last..].iter().copied());
+    Some(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -515,6 +617,10 @@ mod tests {
 
     // README.md selective staging tests
 
+    fn readme_patterns() -> Vec<Regex> {
+        readme_update::reference_patterns("my-crate").unwrap()
+    }
+
     #[test]
     fn test_apply_readme_version_hunks_only_version_change() {
         let head = r#"# My Crate
@@ -535,7 +641,8 @@ my-crate = "0.2.0"
 "#;
 
         let staged =
-            apply_readme_version_hunks(head, working, "my-crate", "0.1.0", "0.2.0").unwrap();
+            apply_readme_version_hunks(head, working, &readme_patterns(), "0.1.0", "0.2.0")
+                .unwrap();
 
         assert!(staged.contains(r#"my-crate = "0.2.0""#));
         assert!(!staged.contains(r#"my-crate = "0.1.0""#));
@@ -561,7 +668,8 @@ my-crate = "0.2.0"
 "#;
 
         let staged =
-            apply_readme_version_hunks(head, working, "my-crate", "0.1.0", "0.2.0").unwrap();
+            apply_readme_version_hunks(head, working, &readme_patterns(), "0.1.0", "0.2.0")
+                .unwrap();
 
         // Should have version change
         assert!(staged.contains(r#"my-crate = "0.2.0""#));
@@ -576,7 +684,8 @@ my-crate = "0.2.0"
         let working = r#"my_crate = "1.1.0""#;
 
         let staged =
-            apply_readme_version_hunks(head, working, "my-crate", "1.0.0", "1.1.0").unwrap();
+            apply_readme_version_hunks(head, working, &readme_patterns(), "1.0.0", "1.1.0")
+                .unwrap();
 
         assert!(staged.contains(r#"my_crate = "1.1.0""#));
     }
@@ -587,7 +696,11 @@ my-crate = "0.2.0"
         let working = "# Updated Readme\nmy-crate = \"0.2.0\"\n";
 
         assert!(has_non_readme_version_changes(
-            head, working, "my-crate", "0.1.0", "0.2.0"
+            head,
+            working,
+            &readme_patterns(),
+            "0.1.0",
+            "0.2.0"
         ));
     }
 
@@ -597,7 +710,11 @@ my-crate = "0.2.0"
         let working = "# Readme\nmy-crate = \"0.2.0\"\n";
 
         assert!(!has_non_readme_version_changes(
-            head, working, "my-crate", "0.1.0", "0.2.0"
+            head,
+            working,
+            &readme_patterns(),
+            "0.1.0",
+            "0.2.0"
         ));
     }
 
@@ -747,4 +864,26 @@ version = "0.2.0"
             head, working, "my-crate", "0.1.0", "0.2.0"
         ));
     }
+
+    #[test]
+    fn test_revert_changes_keeps_other_work() {
+        let parent = "[package]\nname = \"x\"\nversion = \"0.1.0\"\n\n[dependencies]\n";
+        let committed = "[package]\nname = \"x\"\nversion = \"0.2.0\"\n\n[dependencies]\n";
+        let working =
+            "[package]\nname = \"x\"\nversion = \"0.2.0\"\n\n[dependencies]\nserde = \"1\"\n";
+
+        assert_eq!(
+            revert_changes(working, committed, parent).unwrap(),
+            "[package]\nname = \"x\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1\"\n"
+        );
+    }
+
+    #[test]
+    fn test_revert_changes_conflict() {
+        let parent = "version = \"0.1.0\"\n";
+        let committed = "version = \"0.2.0\"\n";
+        let working = "version = \"0.3.0\"\n";
+
+        assert_eq!(revert_changes(working, committed, parent), None);
+    }
 }
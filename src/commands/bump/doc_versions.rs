@@ -0,0 +1,366 @@
+//! Version references in crate sources.
+//!
+//! Library crates often pin their documentation root to the released
+//! version, and docs-heavy crates show how to depend on them in doc
+//! comments:
+//!
+//! ```text
+//! #![doc(html_root_url = "https://docs.rs/my-crate/0.3.1")]
+//!
+//! //! [dependencies]
+//! //! my-crate = "0.3"
+//! ```
+//!
+//! This module finds and updates both kinds of references. `bump` applies
+//! it to `src/lib.rs` and the files listed in `doc_files` under
+//! `[package.metadata.version-info]`; `check-docs` reports references that
+//! are out of date with Cargo.toml.
+//!
+//! # Precision
+//!
+//! `html_root_url` always gets the full version. Dependency snippets in doc
+//! comments follow the same rules as the README (see
+//! [`rewrite_requirement`]): a partial requirement that still matches the new
+//! version is kept, so `my-crate = "1.2"` stays on a bump to 1.3.0, while a
+//! bump from 0.3.1 to 0.4.2 turns `my-crate = "0.3"` into `my-crate = "0.4"`
+//! and `my-crate = "0.3.1"` into `my-crate = "0.4.2"`. Requirement operators
+//! (`^`, `~`, `=`) are kept.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use regex::Regex;
+
+use super::readme_update::{
+    requirement_matches,
+    rewrite_requirement,
+};
+use super::version_references::ReferenceUpdate;
+
+/// Source file scanned even without configuration.
+pub const LIB_RS: &str = "src/lib.rs";
+
+/// A version reference found in a source file.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DocVersion {
+    /// 1-based line number.
+    pub line: usize,
+    /// The version as written (without a requirement operator).
+    pub found: String,
+    /// The version it should be for the current Cargo.toml version.
+    pub expected: String,
+}
+
+impl DocVersion {
+    /// Whether the reference matches the Cargo.toml version.
+    pub fn is_current(&self) -> bool {
+        self.found == self.expected
+    }
+}
+
+/// The `html_root_url` pattern (the version is the last path segment).
+fn html_root_url_regex() -> Result<Regex> {
+    Regex::new(r#"html_root_url\s*=\s*"[^"]*/(?P<version>\d+\.\d+\.\d+[^"/]*)/?""#)
+        .context("Invalid html_root_url pattern")
+}
+
+/// The pattern of a dependency on `crate_name` in a doc comment line.
+fn doc_dependency_regex(crate_name: &str) -> Result<Regex> {
+    let hyphenated = regex::escape(&crate_name.replace('_', "-"));
+    let underscored = regex::escape(&crate_name.replace('-', "_"));
+    let pattern = format!(
+        r#"^\s*//[/!].*[^\w-](?:{}|{})\s*=\s*(?:\{{[^}}]*?\bversion\s*=\s*)?"(?P<operator>[\^~=]?)(?P<version>\d+(?:\.\d+){{0,2}}(?:-[0-9A-Za-z.-]+)?)""#,
+        hyphenated, underscored
+    );
+    Regex::new(&pattern).context("Invalid doc dependency pattern")
+}
+
+/// Patterns of the lines that hold version references, for selective
+/// staging.
+///
+/// # Errors
+///
+/// Returns an error if a pattern cannot be compiled.
+pub fn line_patterns(crate_name: &str) -> Result<Vec<Regex>> {
+    Ok(vec![
+        html_root_url_regex()?,
+        doc_dependency_regex(crate_name)?,
+    ])
+}
+
+/// A version reference located in a source file.
+struct VersionSpan<'a> {
+    /// Byte range of the version.
+    start: usize,
+    end: usize,
+    /// Requirement operator in front of the version (`^`, `~`, `=` or
+    /// empty).
+    operator: &'a str,
+    /// Whether the reference takes the full version (`html_root_url`).
+    full: bool,
+}
+
+/// Every version reference in `content`.
+fn version_spans<'a>(content: &'a str, crate_name: &str) -> Result<Vec<VersionSpan<'a>>> {
+    let html_root_url = html_root_url_regex()?;
+    let doc_dependency = doc_dependency_regex(crate_name)?;
+
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if let Some(version) = html_root_url
+            .captures(line)
+            .and_then(|captures| captures.name("version"))
+        {
+            spans.push(VersionSpan {
+                start: offset + version.start(),
+                end: offset + version.end(),
+                operator: "",
+                full: true,
+            });
+        } else if let Some(captures) = doc_dependency.captures(line)
+            && let (Some(operator), Some(version)) =
+                (captures.name("operator"), captures.name("version"))
+        {
+            spans.push(VersionSpan {
+                start: offset + version.start(),
+                end: offset + version.end(),
+                operator: operator.as_str(),
+                full: false,
+            });
+        }
+        offset += line.len();
+    }
+    Ok(spans)
+}
+
+/// The version the reference `found` should have for `version`.
+///
+/// A dependency requirement that already refers to `version` (see
+/// [`requirement_matches`]) is kept; otherwise `version` is cut to as many
+/// components as `found`. Pre-release versions and references with three
+/// components get the full version.
+fn expected_version(found: &str, operator: &str, version: &str, full: bool) -> String {
+    if !full && requirement_matches(&format!("{}{}", operator, found), version) {
+        return found.to_string();
+    }
+    let components = found.split('.').count();
+    if full || found.contains('-') || components >= 3 || version.contains('-') {
+        return version.to_string();
+    }
+    version
+        .split('.')
+        .take(components)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Find the version references to `crate_name` in `content`.
+///
+/// # Errors
+///
+/// Returns an error if a pattern cannot be compiled.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cargo_version_info::commands::bump::doc_versions::find_doc_versions;
+/// let source =
+///     "//! my-crate = \"0.3\"\n#![doc(html_root_url = \"https://docs.rs/my-crate/0.3.1\")]\n";
+///
+/// let refs = find_doc_versions(source, "my-crate", "0.4.0").unwrap();
+/// assert_eq!(refs[0].expected, "0.4");
+/// assert_eq!(refs[1].expected, "0.4.0");
+/// ```
+pub fn find_doc_versions(
+    content: &str,
+    crate_name: &str,
+    version: &str,
+) -> Result<Vec<DocVersion>> {
+    Ok(version_spans(content, crate_name)?
+        .into_iter()
+        .map(|span| {
+            let found = &content[span.start..span.end];
+            DocVersion {
+                line: content[..span.start].matches('\n').count() + 1,
+                found: found.to_string(),
+                expected: expected_version(found, span.operator, version, span.full),
+            }
+        })
+        .collect())
+}
+
+/// Return `content` with the version references to `crate_name` updated for
+/// a bump from `old_version` to `new_version`.
+///
+/// `html_root_url` is set to `new_version`; dependency requirements are
+/// rewritten with [`rewrite_requirement`], like in the README.
+///
+/// # Errors
+///
+/// Returns an error if a pattern cannot be compiled.
+pub fn update_doc_versions(
+    content: &str,
+    crate_name: &str,
+    old_version: &str,
+    new_version: &str,
+) -> Result<String> {
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    for span in version_spans(content, crate_name)? {
+        let found = &content[span.start..span.end];
+        let replacement = if span.full {
+            Some(new_version.to_string())
+        } else {
+            rewrite_requirement(
+                &format!("{}{}", span.operator, found),
+                old_version,
+                new_version,
+            )
+            .map(|requirement| requirement[span.operator.len()..].to_string())
+        };
+        if let Some(replacement) = replacement {
+            result.push_str(&content[last..span.start]);
+            result.push_str(&replacement);
+            last = span.end;
+        }
+    }
+    result.push_str(&content[last..]);
+    Ok(result)
+}
+
+/// The source files to scan: `src/lib.rs` (if it exists) and `doc_files`,
+/// relative to `base_dir`.
+pub fn doc_file_paths(base_dir: &Path, doc_files: &[String]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let lib_rs = base_dir.join(LIB_RS);
+    if lib_rs.exists() {
+        paths.push(lib_rs);
+    }
+    for file in doc_files {
+        let path = base_dir.join(file);
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Update the version references in `paths`, writing the files that change.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read or written.
+pub fn update_doc_files(
+    paths: &[PathBuf],
+    crate_name: &str,
+    old_version: &str,
+    new_version: &str,
+) -> Result<Vec<ReferenceUpdate>> {
+    let updates = find_doc_updates(paths, crate_name, old_version, new_version)?;
+    for update in &updates {
+        update.write()?;
+    }
+    Ok(updates)
+}
+
+/// Compute the files [`update_doc_files`] would change, without writing
+/// them.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read.
+pub fn find_doc_updates(
+    paths: &[PathBuf],
+    crate_name: &str,
+    old_version: &str,
+    new_version: &str,
+) -> Result<Vec<ReferenceUpdate>> {
+    let mut updates = Vec::new();
+    for path in paths {
+        let original = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let content = update_doc_versions(&original, crate_name, old_version, new_version)?;
+        if content != original {
+            updates.push(ReferenceUpdate {
+                path: path.clone(),
+                content,
+                line_patterns: line_patterns(crate_name)?,
+            });
+        }
+    }
+    Ok(updates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = r#"//! # My Crate
+//!
+//! ```toml
+//! [dependencies]
+//! my-crate = "0.3"
+//! my_crate = { version = "^0.3.1", features = ["full"] }
+//! other-crate = "0.3.1"
+//! ```
+#![doc(html_root_url = "https://docs.rs/my-crate/0.3.1")]
+
+/// Same as the dependency in `my-crate = "0.3.1"`
+pub const VERSION: &str = "0.3.1";
+"#;
+
+    #[test]
+    fn test_find_doc_versions() {
+        let refs = find_doc_versions(LIB, "my-crate", "0.3.1").unwrap();
+
+        assert_eq!(
+            refs.iter()
+                .map(|r| (r.line, r.found.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(5, "0.3"), (6, "0.3.1"), (9, "0.3.1"), (11, "0.3.1")]
+        );
+        assert!(refs.iter().all(DocVersion::is_current));
+    }
+
+    #[test]
+    fn test_update_doc_versions_keeps_precision() {
+        let updated = update_doc_versions(LIB, "my-crate", "0.3.1", "0.4.0").unwrap();
+
+        assert!(updated.contains("//! my-crate = \"0.4\"\n"));
+        assert!(updated.contains("my_crate = { version = \"^0.4.0\", features"));
+        assert!(updated.contains("other-crate = \"0.3.1\""));
+        assert!(updated.contains("html_root_url = \"https://docs.rs/my-crate/0.4.0\""));
+        assert!(updated.contains("pub const VERSION: &str = \"0.3.1\";"));
+    }
+
+    #[test]
+    fn test_stale_references() {
+        let refs = find_doc_versions(LIB, "my-crate", "0.3.2").unwrap();
+        let stale: Vec<_> = refs.iter().filter(|r| !r.is_current()).collect();
+
+        // "0.3" still covers 0.3.2
+        assert_eq!(stale.len(), 3);
+        assert_eq!(stale[0].expected, "0.3.2");
+    }
+
+    #[test]
+    fn test_matching_partial_requirement_is_kept() {
+        let lib = "//! my-crate = \"1.2\"\n//! my-crate = \"~1.2\"\n";
+
+        // Same rule as the README: "1.2" still covers 1.3.0, "~1.2" does not
+        assert_eq!(
+            update_doc_versions(lib, "my-crate", "1.2.3", "1.3.0").unwrap(),
+            "//! my-crate = \"1.2\"\n//! my-crate = \"~1.3\"\n"
+        );
+        let refs = find_doc_versions(lib, "my-crate", "1.3.0").unwrap();
+        assert!(refs[0].is_current());
+        assert_eq!(refs[1].expected, "1.3");
+    }
+}
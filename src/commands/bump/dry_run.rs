@@ -0,0 +1,518 @@
+//! Preview of a version bump (`bump --dry-run`).
+//!
+//! The preview computes everything `bump` would do: the target version, the
+//! new content of Cargo.toml, Cargo.lock, README.md, version files, doc
+//! versions and version references, the blobs the selective staging would
+//! commit, and the commit message. It is printed as unified diffs:
+//!
+//! ```text
+//! Dry run: my-crate 0.1.0 -> 0.2.0 (no files written)
+//!
+//! File changes:
+//! --- a/Cargo.toml
+//! +++ b/Cargo.toml
+//! @@ -1,4 +1,4 @@
+//!  [package]
+//!  name = "my-crate"
+//! -version = "0.1.0"
+//! +version = "0.2.0"
+//!  edition = "2021"
+//!
+//! Staged for commit (against HEAD):
+//! --- a/Cargo.toml
+//! ...
+//!
+//! Commit message:
+//!     chore(version): bump 0.1.0 -> 0.2.0
+//!
+//!     Bumped-by: cargo-version-info
+//! ```
+//!
+//! Neither the worktree nor the git object database is touched, and hooks
+//! are listed but not run. Instead of running `cargo update --workspace`,
+//! the preview only changes the crate's own entry in Cargo.lock, which is
+//! the part of the lockfile a bump commits.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use similar::TextDiff;
+
+use super::commit::{
+    self,
+    AdditionalFile,
+    FileType,
+};
+use super::hooks::VersionInfoConfig;
+use super::message::MessageContext;
+use super::{
+    BumpArgs,
+    doc_versions,
+    readme_update,
+    version_references,
+    version_update,
+    workflow,
+};
+
+/// A file the bump would change in the worktree.
+#[derive(Debug)]
+pub struct FileChange {
+    /// Path of the file.
+    pub path: PathBuf,
+    /// Current worktree content.
+    pub original: String,
+    /// Content after the bump.
+    pub updated: String,
+    /// Content in HEAD, if the file is tracked.
+    pub head_content: Option<String>,
+    /// Selective staging strategy for the file.
+    pub file_type: FileType,
+}
+
+/// The content the bump commit would record for a file.
+#[derive(Debug)]
+pub struct StagedBlob {
+    /// Path of the file.
+    pub path: PathBuf,
+    /// Content in HEAD, if the file is tracked.
+    pub head_content: Option<String>,
+    /// Content of the blob.
+    pub content: String,
+}
+
+/// Everything a bump would do, computed without side effects.
+#[derive(Debug)]
+pub struct BumpPreview {
+    /// Name of the package.
+    pub package_name: String,
+    /// Version in Cargo.toml.
+    pub current_version: String,
+    /// Version after the bump.
+    pub target_version: String,
+    /// Directory containing Cargo.toml; paths are shown relative to it.
+    pub base_dir: PathBuf,
+    /// Worktree changes, starting with Cargo.toml.
+    pub changes: Vec<FileChange>,
+    /// Blobs of the commit (empty with `--no-commit`).
+    pub staged: Vec<StagedBlob>,
+    /// Message of the commit (`None` with `--no-commit`).
+    pub commit_message: Option<String>,
+    /// Pre-bump hooks that would run.
+    pub pre_bump_hooks: Vec<String>,
+    /// Post-bump hooks that would run.
+    pub post_bump_hooks: Vec<String>,
+}
+
+impl BumpPreview {
+    /// Render the preview as unified diffs.
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Dry run: {} {} -> {} (no files written)\n\nFile changes:\n",
+            self.package_name, self.current_version, self.target_version
+        );
+        for change in &self.changes {
+            let name = self.display_path(&change.path);
+            out.push_str(&unified_diff(
+                &change.original,
+                &change.updated,
+                &format!("a/{}", name),
+                &format!("b/{}", name),
+            ));
+        }
+
+        match &self.commit_message {
+            Some(message) => {
+                out.push_str("\nStaged for commit (against HEAD):\n");
+                for blob in &self.staged {
+                    let name = self.display_path(&blob.path);
+                    let (head, head_name) = match &blob.head_content {
+                        Some(head) => (head.as_str(), format!("a/{}", name)),
+                        None => ("", "/dev/null".to_string()),
+                    };
+                    if head != blob.content {
+                        out.push_str(&unified_diff(
+                            head,
+                            &blob.content,
+                            &head_name,
+                            &format!("b/{}", name),
+                        ));
+                    }
+                }
+                out.push_str("\nCommit message:\n");
+                out.push_str(&indent_message(message));
+            }
+            None => out.push_str("\nNot committing (--no-commit)\n"),
+        }
+
+        if !self.pre_bump_hooks.is_empty() || !self.post_bump_hooks.is_empty() {
+            out.push_str("\nHooks (not run):\n");
+            for hook in &self.pre_bump_hooks {
+                out.push_str(&format!("  pre-bump: {}\n", hook));
+            }
+            for hook in &self.post_bump_hooks {
+                out.push_str(&format!("  post-bump: {}\n", hook));
+            }
+        }
+        out
+    }
+
+    /// `path` relative to the directory containing Cargo.toml.
+    fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.base_dir)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+/// Compute what bumping `current_version` to `target_version` would change.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read, Cargo.toml has no version, a
+/// version file or reference pattern is invalid, or selective staging
+/// fails.
+pub fn preview(
+    args: &BumpArgs,
+    manifest_path: &Path,
+    package_name: &str,
+    current_version: &str,
+    target_version: &str,
+    config: &VersionInfoConfig,
+) -> Result<BumpPreview> {
+    let base_dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    let changes = file_changes(
+        args,
+        manifest_path,
+        package_name,
+        current_version,
+        target_version,
+        config,
+    )?;
+
+    let (staged, commit_message, post_bump_hooks) = if args.no_commit {
+        (Vec::new(), None, Vec::new())
+    } else {
+        let staged = staged_blobs(
+            manifest_path,
+            package_name,
+            current_version,
+            target_version,
+            &changes,
+            &config.additional_files,
+        )?;
+        let message = preview_commit_message(
+            manifest_path,
+            config,
+            package_name,
+            current_version,
+            target_version,
+        )?;
+        (staged, Some(message), config.post_bump_hooks.clone())
+    };
+
+    Ok(BumpPreview {
+        package_name: package_name.to_string(),
+        current_version: current_version.to_string(),
+        target_version: target_version.to_string(),
+        base_dir: base_dir.to_path_buf(),
+        changes,
+        staged,
+        commit_message,
+        pre_bump_hooks: config.pre_bump_hooks.clone(),
+        post_bump_hooks,
+    })
+}
+
+/// The worktree changes of the bump, in the order `bump` makes them.
+fn file_changes(
+    args: &BumpArgs,
+    manifest_path: &Path,
+    package_name: &str,
+    current_version: &str,
+    target_version: &str,
+    config: &VersionInfoConfig,
+) -> Result<Vec<FileChange>> {
+    let base_dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut changes = Vec::new();
+    let mut push = |path: PathBuf, original: String, updated: String, file_type: FileType| {
+        if updated != original {
+            let head_content = super::get_file_head_content(manifest_path, &path).ok();
+            changes.push(FileChange {
+                path,
+                original,
+                updated,
+                head_content,
+                file_type,
+            });
+        }
+    };
+
+    let original = read(manifest_path)?;
+    let updated =
+        version_update::update_cargo_toml_content(manifest_path, &original, target_version)?;
+    push(
+        manifest_path.to_path_buf(),
+        original,
+        updated,
+        FileType::Other,
+    );
+
+    let cargo_lock_path = base_dir.join("Cargo.lock");
+    if !args.no_lock && cargo_lock_path.exists() {
+        let original = read(&cargo_lock_path)?;
+        let updated =
+            version_update::update_cargo_lock_content(&original, package_name, target_version);
+        push(
+            cargo_lock_path.clone(),
+            original,
+            updated,
+            FileType::CargoLock,
+        );
+    }
+
+    let readme_path = base_dir.join("README.md");
+    if !args.no_readme && readme_path.exists() {
+        let original = read(&readme_path)?;
+        let update = readme_update::update_readme_content(
+            &original,
+            package_name,
+            current_version,
+            target_version,
+        )?;
+        push(
+            readme_path.clone(),
+            original,
+            update.content,
+            FileType::Readme(readme_update::reference_patterns(package_name)?),
+        );
+    }
+
+    let mut skip = vec![manifest_path.to_path_buf(), cargo_lock_path];
+    if !args.no_readme {
+        skip.push(readme_path);
+    }
+    for file in &config.version_files {
+        let path = base_dir.join(&file.path);
+        let original = read(&path)?;
+        let updated = file
+            .apply(&original, target_version)
+            .with_context(|| format!("Failed to update version in {}", path.display()))?;
+        if updated != original {
+            skip.push(path.clone());
+        }
+        push(path, original, updated, FileType::VersionFile(file.clone()));
+    }
+
+    let doc_paths = doc_versions::doc_file_paths(base_dir, &config.doc_files);
+    let mut updates =
+        doc_versions::find_doc_updates(&doc_paths, package_name, current_version, target_version)?;
+    skip.extend(doc_paths);
+    updates.extend(version_references::find_reference_updates(
+        base_dir,
+        &config.version_references,
+        current_version,
+        target_version,
+        &skip,
+    )?);
+    for update in updates {
+        let original = read(&update.path)?;
+        push(
+            update.path,
+            original,
+            update.content,
+            FileType::VersionReference(update.line_patterns),
+        );
+    }
+
+    Ok(changes)
+}
+
+/// The commit message `bump` would use.
+///
+/// Outside a git repository (or without a commit), `{changelog}` is empty
+/// and there is no `Signed-off-by` trailer.
+fn preview_commit_message(
+    manifest_path: &Path,
+    config: &VersionInfoConfig,
+    package_name: &str,
+    current_version: &str,
+    target_version: &str,
+) -> Result<String> {
+    let dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    let repo = gix::discover(dir).ok();
+    match repo
+        .as_ref()
+        .and_then(|repo| Some((repo, repo.head_id().ok()?)))
+    {
+        Some((repo, head_id)) => commit::bump_commit_message(
+            repo,
+            &config.commit,
+            package_name,
+            current_version,
+            target_version,
+            head_id.detach(),
+        ),
+        None => {
+            let tag = config.commit.tag_name(package_name, target_version);
+            let context = MessageContext {
+                package: package_name,
+                old_version: current_version,
+                new_version: target_version,
+                tag: &tag,
+                changelog: "",
+            };
+            Ok(config.commit.render(&context, None))
+        }
+    }
+}
+
+/// The blobs the selective staging would commit for `changes`, plus the
+/// configured additional files.
+fn staged_blobs(
+    manifest_path: &Path,
+    package_name: &str,
+    current_version: &str,
+    target_version: &str,
+    changes: &[FileChange],
+    additional_files: &[String],
+) -> Result<Vec<StagedBlob>> {
+    let mut staged = Vec::new();
+    for change in changes {
+        let content = if change.path == manifest_path {
+            match &change.head_content {
+                Some(head) => {
+                    commit::staged_manifest_content(
+                        head,
+                        &change.updated,
+                        current_version,
+                        target_version,
+                    )?
+                    .0
+                }
+                None => change.updated.clone(),
+            }
+        } else {
+            let file = AdditionalFile {
+                path: change.path.clone(),
+                working_content: change.updated.clone(),
+                head_content: change.head_content.clone(),
+                file_type: change.file_type.clone(),
+            };
+            commit::staged_content(&file, package_name, current_version, target_version)?.0
+        };
+        staged.push(StagedBlob {
+            path: change.path.clone(),
+            head_content: change.head_content.clone(),
+            content,
+        });
+    }
+
+    // Additional files are committed as they are (after the pre-bump hooks,
+    // which the preview does not run)
+    let base_dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    for file_path in additional_files {
+        let path = base_dir.join(file_path);
+        if staged.iter().any(|blob| blob.path == path) {
+            continue;
+        }
+        if !path.exists() {
+            workflow::warning(&format!("Additional file not found: {}", path.display()));
+            continue;
+        }
+        staged.push(StagedBlob {
+            content: read(&path)?,
+            head_content: super::get_file_head_content(manifest_path, &path).ok(),
+            path,
+        });
+    }
+
+    Ok(staged)
+}
+
+/// Read a file to a string.
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// `message` indented by four spaces, keeping blank lines empty.
+pub(super) fn indent_message(message: &str) -> String {
+    message
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("    {}\n", line)
+            }
+        })
+        .collect()
+}
+
+/// Unified diff of `old` and `new` with 3 lines of context.
+pub(super) fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_name, new_name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let diff = unified_diff(
+            "name = \"x\"\nversion = \"0.1.0\"\n",
+            "name = \"x\"\nversion = \"0.2.0\"\n",
+            "a/Cargo.toml",
+            "b/Cargo.toml",
+        );
+
+        assert_eq!(
+            diff,
+            "--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1,2 +1,2 @@\n name = \"x\"\n-version = \"0.1.0\"\n+version = \"0.2.0\"\n"
+        );
+    }
+
+    #[test]
+    fn test_render_new_file_and_no_commit() {
+        let mut preview = BumpPreview {
+            package_name: "my-crate".to_string(),
+            current_version: "0.1.0".to_string(),
+            target_version: "0.2.0".to_string(),
+            base_dir: PathBuf::from("/work"),
+            changes: Vec::new(),
+            staged: vec![StagedBlob {
+                path: PathBuf::from("/work/VERSION"),
+                head_content: None,
+                content: "0.2.0\n".to_string(),
+            }],
+            commit_message: Some("chore(version): bump 0.1.0 -> 0.2.0".to_string()),
+            pre_bump_hooks: vec!["make docs".to_string()],
+            post_bump_hooks: Vec::new(),
+        };
+
+        let rendered = preview.render();
+        assert!(
+            rendered.contains("--- /dev/null\n+++ b/VERSION\n"),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains("    chore(version): bump 0.1.0 -> 0.2.0\n"));
+        assert!(rendered.contains("  pre-bump: make docs\n"));
+
+        preview.commit_message = None;
+        let rendered = preview.render();
+        assert!(rendered.contains("Not committing (--no-commit)"));
+        assert!(!rendered.contains("/dev/null"));
+    }
+}
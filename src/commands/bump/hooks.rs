@@ -12,10 +12,13 @@
 //! ```toml
 //! [package.metadata.version-info]
 //! pre_bump_hooks = [
-//!     "./scripts/sync-npm-version.sh {{version}}"
+//!     "./scripts/generate-docs.sh {{version}}"
 //! ]
 //! additional_files = [
-//!     "npm/package.json"
+//!     "docs/VERSION"
+//! ]
+//! version_files = [
+//!     { path = "pyproject.toml", kind = "toml", key = "project.version" }
 //! ]
 //! post_bump_hooks = [
 //!     "echo 'Version {{version}} committed'"
@@ -27,6 +30,15 @@
 //! - **pre_bump_hooks**: Run after Cargo.toml is updated but before commit
 //! - **post_bump_hooks**: Run after the commit is created
 //! - **additional_files**: Files to include in the version bump commit
+//! - **version_files**: Files whose version `bump` rewrites itself (see
+//!   [`version_files`](super::version_files))
+//! - **version_references**: Search/replace patterns for version references in
+//!   other files (see [`version_references`](super::version_references))
+//! - **doc_files**: Source files besides `src/lib.rs` whose `html_root_url` and
+//!   doc comment versions are updated (see
+//!   [`doc_versions`](super::doc_versions))
+//! - **commit**: Template and trailers of the bump commit message (see
+//!   [`message`](super::message))
 //!
 //! # Template Variables
 //!
@@ -41,6 +53,10 @@ use anyhow::{
 };
 use serde::Deserialize;
 
+use super::message::CommitMessageConfig;
+use super::version_files::VersionFile;
+use super::version_references::VersionReference;
+
 /// Configuration for version-info hooks.
 ///
 /// This struct is deserialized from `[package.metadata.version-info]` in
@@ -75,6 +91,28 @@ pub struct VersionInfoConfig {
     /// Useful for files modified by pre_bump_hooks.
     #[serde(default)]
     pub additional_files: Vec<String>,
+
+    /// Files whose version is rewritten along with Cargo.toml.
+    ///
+    /// Unlike `additional_files`, these need no hook: `bump` updates the
+    /// version itself and commits only the changed version lines.
+    #[serde(default)]
+    pub version_files: Vec<VersionFile>,
+
+    /// Search/replace patterns for version references in files matching a
+    /// glob (docs, Dockerfiles, `CITATION.cff`, ...).
+    #[serde(default)]
+    pub version_references: Vec<VersionReference>,
+
+    /// Source files scanned for `html_root_url` and doc comment versions,
+    /// in addition to `src/lib.rs`.
+    #[serde(default)]
+    pub doc_files: Vec<String>,
+
+    /// Template and trailers of the bump commit message, from
+    /// `[package.metadata.version-info.commit]`.
+    #[serde(default)]
+    pub commit: CommitMessageConfig,
 }
 
 impl VersionInfoConfig {
@@ -209,6 +247,9 @@ mod tests {
         assert!(config.pre_bump_hooks.is_empty());
         assert!(config.post_bump_hooks.is_empty());
         assert!(config.additional_files.is_empty());
+        assert!(config.version_files.is_empty());
+        assert!(config.version_references.is_empty());
+        assert!(config.doc_files.is_empty());
     }
 
     #[test]
@@ -0,0 +1,332 @@
+//! Commit message of the version bump.
+//!
+//! By default `bump` commits with a conventional commits subject and a
+//! trailer that marks the commit as created by this tool:
+//!
+//! ```text
+//! chore(version): bump 0.1.0 -> 0.2.0
+//!
+//! Bumped-by: cargo-version-info
+//! ```
+//!
+//! # Configuration
+//!
+//! The message is configured under `[package.metadata.version-info.commit]`:
+//!
+//! ```toml
+//! [package.metadata.version-info.commit]
+//! message = "release: {package} {tag}\n\n{changelog}"
+//! release_as = true
+//! signoff = true
+//! skip_ci = true
+//! tag_format = "{name}-v{version}"
+//! trailers = { Reviewed-by = "Release Bot <bot@example.com>" }
+//! ```
+//!
+//! `tag_format` names the release tags, with `{name}` and `{version}`
+//! placeholders, like `publish-plan --tag-format` (default `v{version}`).
+//!
+//! # Placeholders
+//!
+//! `message` and the `trailers` values support:
+//!
+//! - `{package}`: The package name
+//! - `{old}`: The version before the bump
+//! - `{new}`: The version after the bump
+//! - `{tag}`: The release tag of the new version (`tag_format` with `{new}`)
+//! - `{changelog}`: One `- subject` line per commit since the previous bump (or
+//!   the release tag of `{old}`)
+//!
+//! # Trailers
+//!
+//! Trailers follow the message in this order: `trailers` (sorted by key),
+//! `Release-As: {new}` with `release_as`, `Signed-off-by` with the git
+//! identity with `signoff`, and always the
+//! [`BUMP_TRAILER`] last, which
+//! `cargo version-info unbump` looks for.
+
+use std::collections::BTreeMap;
+
+use anyhow::{
+    Context,
+    Result,
+};
+use bstr::ByteSlice;
+use serde::Deserialize;
+
+use super::commit::{
+    BUMP_TRAILER,
+    is_bump_message,
+};
+
+/// Template used when no `message` is configured.
+pub const DEFAULT_TEMPLATE: &str = "chore(version): bump {old} -> {new}";
+
+/// Release tag format used when no `tag_format` is configured.
+pub const DEFAULT_TAG_FORMAT: &str = "v{version}";
+
+/// Configuration of the bump commit message.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct CommitMessageConfig {
+    /// Message template (defaults to [`DEFAULT_TEMPLATE`]).
+    #[serde(default)]
+    pub message: Option<String>,
+
+    /// Custom trailers, with placeholders in the values.
+    #[serde(default)]
+    pub trailers: BTreeMap<String, String>,
+
+    /// Add a `Release-As: {new}` trailer (read by release-please).
+    #[serde(default)]
+    pub release_as: bool,
+
+    /// Add a `Signed-off-by` trailer with the git identity.
+    #[serde(default)]
+    pub signoff: bool,
+
+    /// Append `[skip ci]` to the subject.
+    #[serde(default)]
+    pub skip_ci: bool,
+
+    /// Release tag name, with `{name}` and `{version}` placeholders
+    /// (defaults to [`DEFAULT_TAG_FORMAT`]).
+    #[serde(default)]
+    pub tag_format: Option<String>,
+}
+
+/// Values of the placeholders.
+#[derive(Debug, Clone, Copy)]
+pub struct MessageContext<'a> {
+    /// The package name.
+    pub package: &'a str,
+    /// The version before the bump.
+    pub old_version: &'a str,
+    /// The version after the bump.
+    pub new_version: &'a str,
+    /// The release tag of the new version.
+    pub tag: &'a str,
+    /// Commit subjects since the previous bump, one `- subject` per line.
+    pub changelog: &'a str,
+}
+
+impl MessageContext<'_> {
+    /// Replace the placeholders in `template`.
+    fn expand(&self, template: &str) -> String {
+        template
+            .replace("{package}", self.package)
+            .replace("{old}", self.old_version)
+            .replace("{new}", self.new_version)
+            .replace("{tag}", self.tag)
+            .replace("{changelog}", self.changelog)
+    }
+}
+
+impl CommitMessageConfig {
+    /// The release tag name of `version` of `package`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cargo_version_info::commands::bump::message::CommitMessageConfig;
+    ///
+    /// assert_eq!(
+    ///     CommitMessageConfig::default().tag_name("my-crate", "0.2.0"),
+    ///     "v0.2.0"
+    /// );
+    ///
+    /// let config = CommitMessageConfig {
+    ///     tag_format: Some("{name}-v{version}".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(config.tag_name("my-crate", "0.2.0"), "my-crate-v0.2.0");
+    /// ```
+    pub fn tag_name(&self, package: &str, version: &str) -> String {
+        self.tag_format
+            .as_deref()
+            .unwrap_or(DEFAULT_TAG_FORMAT)
+            .replace("{name}", package)
+            .replace("{version}", version)
+    }
+
+    /// Whether the template uses `{changelog}`, which needs the history.
+    pub fn needs_changelog(&self) -> bool {
+        self.template().contains("{changelog}")
+    }
+
+    /// Render the commit message.
+    ///
+    /// `signed_off_by` is the `Name <email>` identity, used with `signoff`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cargo_version_info::commands::bump::message::{
+    ///     CommitMessageConfig,
+    ///     MessageContext,
+    /// };
+    ///
+    /// let config = CommitMessageConfig {
+    ///     message: Some("release: {package} {tag}".to_string()),
+    ///     release_as: true,
+    ///     skip_ci: true,
+    ///     ..Default::default()
+    /// };
+    /// let context = MessageContext {
+    ///     package: "my-crate",
+    ///     old_version: "0.1.0",
+    ///     new_version: "0.2.0",
+    ///     tag: "v0.2.0",
+    ///     changelog: "",
+    /// };
+    ///
+    /// assert_eq!(
+    ///     config.render(&context, None),
+    ///     "release: my-crate v0.2.0 [skip ci]\n\n\
+    ///      Release-As: 0.2.0\n\
+    ///      Bumped-by: cargo-version-info\n"
+    /// );
+    /// ```
+    pub fn render(&self, context: &MessageContext, signed_off_by: Option<&str>) -> String {
+        let body = context.expand(self.template());
+        let body = body.trim();
+        let (subject, rest) = body.split_once('\n').unwrap_or((body, ""));
+
+        let mut message = subject.trim_end().to_string();
+        if self.skip_ci && !message.contains("[skip ci]") {
+            message.push_str(" [skip ci]");
+        }
+        if !rest.is_empty() {
+            message.push('\n');
+            message.push_str(rest);
+        }
+
+        message.push_str("\n\n");
+        for (key, value) in &self.trailers {
+            message.push_str(&format!("{}: {}\n", key, context.expand(value)));
+        }
+        if self.release_as {
+            message.push_str(&format!("Release-As: {}\n", context.new_version));
+        }
+        if self.signoff
+            && let Some(identity) = signed_off_by
+        {
+            message.push_str(&format!("Signed-off-by: {}\n", identity));
+        }
+        message.push_str(BUMP_TRAILER);
+        message.push('\n');
+        message
+    }
+
+    /// The configured template or [`DEFAULT_TEMPLATE`].
+    fn template(&self) -> &str {
+        self.message.as_deref().unwrap_or(DEFAULT_TEMPLATE)
+    }
+}
+
+/// Subjects of the first-parent commits from `head_id` back to the previous
+/// bump commit or the commit tagged `old_tag`, one `- subject` per line,
+/// newest first.
+///
+/// # Errors
+///
+/// Returns an error if the history cannot be read.
+pub fn changelog_since_last_bump(
+    repo: &gix::Repository,
+    head_id: gix::ObjectId,
+    old_tag: &str,
+) -> Result<String> {
+    let tagged = repo
+        .find_reference(format!("refs/tags/{}", old_tag).as_str())
+        .ok()
+        .and_then(|mut reference| reference.peel_to_id().ok())
+        .map(|id| id.detach());
+
+    let walk = repo
+        .rev_walk([head_id])
+        .first_parent_only()
+        .all()
+        .context("Failed to walk commit history")?;
+    let mut changelog = String::new();
+    for info in walk {
+        let info = info.context("Failed to read commit while walking history")?;
+        if Some(info.id) == tagged {
+            break;
+        }
+        let commit = repo
+            .find_commit(info.id)
+            .context("Failed to find commit object")?;
+        let message = commit
+            .message_raw()
+            .context("Failed to read raw commit message")?
+            .to_str_lossy()
+            .into_owned();
+        if is_bump_message(&message) {
+            break;
+        }
+        if let Some(subject) = message.lines().next() {
+            changelog.push_str(&format!("- {}\n", subject.trim_end()));
+        }
+    }
+    Ok(changelog)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::bump::commit::commit_message;
+
+    const CONTEXT: MessageContext = MessageContext {
+        package: "my-crate",
+        old_version: "0.1.0",
+        new_version: "0.2.0",
+        tag: "v0.2.0",
+        changelog: "- feat: add things\n- fix: fix things\n",
+    };
+
+    #[test]
+    fn test_default_message() {
+        let message = CommitMessageConfig::default().render(&CONTEXT, Some("Test <t@example.com>"));
+
+        assert_eq!(message, commit_message("0.1.0", "0.2.0"));
+        assert!(is_bump_message(&message));
+    }
+
+    #[test]
+    fn test_template_with_changelog_and_trailers() {
+        let config = CommitMessageConfig {
+            message: Some("release: {package} {old} -> {new}\n\n{changelog}".to_string()),
+            trailers: BTreeMap::from([("Release-Tag".to_string(), "{tag}".to_string())]),
+            signoff: true,
+            skip_ci: true,
+            ..Default::default()
+        };
+
+        let message = config.render(&CONTEXT, Some("Test <t@example.com>"));
+
+        assert_eq!(
+            message,
+            "release: my-crate 0.1.0 -> 0.2.0 [skip ci]\n\n- feat: add things\n- fix: fix \
+             things\n\nRelease-Tag: v0.2.0\nSigned-off-by: Test <t@example.com>\nBumped-by: \
+             cargo-version-info\n"
+        );
+        assert!(is_bump_message(&message));
+    }
+
+    #[test]
+    fn test_config_from_metadata() {
+        let config: CommitMessageConfig = serde_json::from_value(serde_json::json!({
+            "message": "release {tag}",
+            "release_as": true,
+            "tag_format": "{name}@{version}",
+            "trailers": { "Reviewed-by": "Bot <bot@example.com>" }
+        }))
+        .unwrap();
+
+        assert_eq!(config.message.as_deref(), Some("release {tag}"));
+        assert!(config.release_as);
+        assert!(!config.signoff);
+        assert!(!config.needs_changelog());
+        assert_eq!(config.trailers["Reviewed-by"], "Bot <bot@example.com>");
+        assert_eq!(config.tag_name("my-crate", "0.2.0"), "my-crate@0.2.0");
+    }
+}
@@ -17,9 +17,14 @@
 //!
 //! - [`args`] - Command-line argument definitions
 //! - [`version_update`] - TOML file manipulation
+//! - [`version_files`] - Version sync for package.json, pyproject.toml, etc.
+//! - [`version_references`] - Regex-based version references in other files
+//! - [`doc_versions`] - `html_root_url` and doc comment versions in sources
 //! - [`index`] - Git index (staging area) operations
 //! - [`tree`] - Git tree building from index
 //! - [`commit`] - Commit orchestration and creation
+//! - [`dry_run`] - Preview of a bump as unified diffs (`--dry-run`)
+//! - [`transaction`] - Rollback of a failed bump
 //!
 //! # Usage Examples
 //!
@@ -37,17 +42,20 @@
 //! cargo version-info bump --version 2.0.0
 //!
 //! # Auto-suggest from GitHub releases
-//! cargo version-info bump --auto --github-token $TOKEN
+//! cargo version-info bump --auto --source releases --github-token $TOKEN
 //!
 //! # Update but don't commit
 //! cargo version-info bump --patch --no-commit
+//!
+//! # Preview file diffs and the commit without writing anything
+//! cargo version-info bump --patch --dry-run
 //! ```
 //!
 //! # Workflow
 //!
 //! 1. **Calculate Target Version**
 //!    - From explicit `--version` flag
-//!    - From GitHub API (`--auto`)
+//!    - From the latest release (`--auto`, see `--source`)
 //!    - From semantic version increment (`--major`, `--minor`, `--patch`)
 //!
 //! 2. **Update Cargo.toml**
@@ -95,9 +103,14 @@
 //! Commit messages follow the conventional commits format:
 //! ```text
 //! chore(version): bump X.Y.Z -> X.Y.Z
+//!
+//! Bumped-by: cargo-version-info
 //! ```
 //!
 //! The `chore` type indicates this is a maintenance task, not a feature or fix.
+//! The trailer marks the commit as created by this tool, which is how
+//! `cargo version-info unbump` finds it. The subject, body and additional
+//! trailers can be configured (see [`message`]).
 //!
 //! ## Workspace Support
 //!
@@ -112,13 +125,20 @@
 //! and why.
 
 pub mod args;
+pub mod branch;
 pub mod commit;
 pub mod diff;
+pub mod doc_versions;
+pub mod dry_run;
 pub mod hooks;
 pub mod index;
+pub mod message;
 pub mod readme_update;
 pub mod signing;
+pub mod transaction;
 pub mod tree;
+pub mod version_files;
+pub mod version_references;
 pub mod version_update;
 
 #[cfg(test)]
@@ -130,11 +150,9 @@ use anyhow::{
     Result,
 };
 pub use args::BumpArgs;
-use cargo_plugin_utils::common::{
-    find_package,
-    get_owner_repo,
-};
+use cargo_plugin_utils::common::find_package;
 
+use super::workflow;
 use crate::github;
 use crate::version::{
     format_version,
@@ -162,6 +180,9 @@ use crate::version::{
 ///
 /// 3. **Update Files**
 ///    - Modify Cargo.toml with new version
+///    - Sync configured `version_files` (package.json, pyproject.toml, ...)
+///    - Update `html_root_url` and doc comment versions in `src/lib.rs`
+///    - Apply configured `version_references` search/replace patterns
 ///    - Preserve all formatting and comments
 ///
 /// 4. **Create Commit** (unless `--no-commit`)
@@ -218,15 +239,18 @@ use crate::version::{
 ///
 /// ## Auto Mode
 ///
-/// The `--auto` flag queries the GitHub Releases API to find the latest
-/// published version and suggests the next appropriate version. This is
-/// useful in CI/CD pipelines where you want automated version suggestions.
+/// The `--auto` flag reads the latest released version from `--source` (git
+/// tags by default, or GitHub releases, Cargo.toml, or the registry) and
+/// suggests the next patch version. This is useful in CI/CD pipelines where
+/// you want automated version suggestions.
 ///
 /// # Commit Format
 ///
 /// Commits use the conventional commits format:
 /// ```text
 /// chore(version): bump 0.1.0 -> 0.2.0
+///
+/// Bumped-by: cargo-version-info
 /// ```
 ///
 /// This format:
@@ -234,6 +258,9 @@ use crate::version::{
 /// - Clearly indicates the type of change
 /// - Includes both old and new versions for context
 ///
+/// A template and trailers can be configured under
+/// `[package.metadata.version-info.commit]` (see [`message`]).
+///
 /// # No-Commit Mode
 ///
 /// The `--no-commit` flag allows updating the version without creating a
@@ -241,14 +268,31 @@ use crate::version::{
 /// - You want to review changes first
 /// - You're making multiple related changes
 /// - You prefer manual commit control
+///
+/// # Rollback
+///
+/// If a step fails after the files were updated (`cargo update`, a pre-bump
+/// hook, commit signing or the commit itself), the files, the git index and
+/// HEAD are restored to their state before the bump (see [`transaction`]).
+/// `--keep-on-failure` leaves the modified files in place for debugging.
+///
+/// # Dry-Run Mode
+///
+/// The `--dry-run` flag prints the changes and the commit as unified diffs
+/// (see [`dry_run`]) without writing files, creating git objects or running
+/// hooks.
+///
+/// # Other Branches
+///
+/// `--branch <name>` creates the commit on another branch without checking
+/// it out (see [`branch`]).
 pub fn bump(args: BumpArgs) -> Result<()> {
-    use commit::{
-        AdditionalFile,
-        FileType,
-    };
-
     let mut logger = cargo_plugin_utils::logger::Logger::new();
 
+    if let Some(branch) = &args.branch {
+        return branch::bump_branch(&args, branch, &mut logger);
+    }
+
     // Step 1: Get current version and package info from Cargo.toml
     logger.status("Reading", "current version");
     let package = find_package(args.manifest_path.as_deref())?;
@@ -272,18 +316,132 @@ pub fn bump(args: BumpArgs) -> Result<()> {
         );
     }
 
+    let manifest_path = args
+        .manifest_path
+        .as_deref()
+        .unwrap_or_else(|| std::path::Path::new("./Cargo.toml"));
+
+    if args.dry_run {
+        let preview = dry_run::preview(
+            &args,
+            manifest_path,
+            &package_name,
+            &current_version,
+            &target_version,
+            &hook_config,
+        )?;
+        print!("{}", preview.render());
+        return Ok(());
+    }
+
     logger.print_message(&format!(
         "Bumping version: {} -> {}",
         current_version, target_version
     ));
 
+    // Steps 4-11 modify files and create the commit; undo them if one fails
+    let context = BumpContext {
+        args: &args,
+        manifest_path,
+        package_name: &package_name,
+        current_version: &current_version,
+        target_version: &target_version,
+        config: &hook_config,
+    };
+    let mut transaction = transaction::Transaction::begin(manifest_path);
+    let commit_id = match update_and_commit(&context, &mut logger, &mut transaction) {
+        Ok(commit_id) => commit_id,
+        Err(err) => {
+            logger.clear_status();
+            if args.keep_on_failure {
+                workflow::warning("Bump failed; keeping modified files (--keep-on-failure)");
+            } else {
+                match transaction.rollback() {
+                    Ok(restored) => logger.print_message(&format!(
+                        "Bump failed; rolled back {} file{}",
+                        restored.len(),
+                        if restored.len() == 1 { "" } else { "s" }
+                    )),
+                    Err(rollback_err) => workflow::warning(&format!(
+                        "Bump failed and could not be rolled back: {:#}",
+                        rollback_err
+                    )),
+                }
+            }
+            return Err(err);
+        }
+    };
+
+    if let Some(commit_id) = commit_id {
+        workflow::append_step_summary(&bump_summary(
+            &package_name,
+            &current_version,
+            &target_version,
+            Some(&commit_id.to_string()),
+        ))?;
+
+        // Step 12: Run post-bump hooks (only after commit)
+        let manifest_dir = manifest_path
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."));
+        for hook in &hook_config.post_bump_hooks {
+            logger.status("Running", &format!("hook: {}", hook));
+            hooks::run_hook(hook, &target_version, manifest_dir)?;
+            logger.finish();
+        }
+    } else {
+        logger.print_message(&format!(
+            "✓ Updated version to {} (not committed)",
+            target_version
+        ));
+        workflow::append_step_summary(&bump_summary(
+            &package_name,
+            &current_version,
+            &target_version,
+            None,
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// What a bump works on, shared by its steps.
+struct BumpContext<'a> {
+    args: &'a BumpArgs,
+    manifest_path: &'a std::path::Path,
+    package_name: &'a str,
+    current_version: &'a str,
+    target_version: &'a str,
+    config: &'a hooks::VersionInfoConfig,
+}
+
+/// Update the files and create the commit (steps 4 to 11 of [`bump`]).
+///
+/// Every file is recorded in `transaction` before it is first written.
+/// Returns the commit, or `None` with `--no-commit`.
+fn update_and_commit(
+    context: &BumpContext,
+    logger: &mut cargo_plugin_utils::logger::Logger,
+    transaction: &mut transaction::Transaction,
+) -> Result<Option<gix::ObjectId>> {
+    use commit::{
+        AdditionalFile,
+        FileType,
+    };
+
+    let BumpContext {
+        args,
+        manifest_path,
+        package_name,
+        current_version,
+        target_version,
+        config: hook_config,
+    } = *context;
+
     // Step 4: Update Cargo.toml
     logger.status("Updating", "Cargo.toml");
-    let manifest_path = args
-        .manifest_path
-        .as_deref()
-        .unwrap_or_else(|| std::path::Path::new("./Cargo.toml"));
-    version_update::update_cargo_toml_version(manifest_path, &current_version, &target_version)?;
+    transaction.record(manifest_path)?;
+    version_update::update_cargo_toml_version(manifest_path, current_version, target_version)?;
     logger.finish();
 
     // Get the directory containing Cargo.toml for other files
@@ -303,6 +461,7 @@ pub fn bump(args: BumpArgs) -> Result<()> {
 
     if !args.no_lock {
         logger.status("Updating", "Cargo.lock");
+        transaction.record(&cargo_lock_path)?;
         let status = std::process::Command::new("cargo")
             .args(["update", "--workspace"])
             .current_dir(manifest_dir)
@@ -328,9 +487,9 @@ pub fn bump(args: BumpArgs) -> Result<()> {
         logger.status("Checking", "README.md");
         let result = readme_update::update_readme_file(
             &readme_path,
-            &package_name,
-            &current_version,
-            &target_version,
+            package_name,
+            current_version,
+            target_version,
         )?;
         logger.finish();
 
@@ -338,6 +497,7 @@ pub fn bump(args: BumpArgs) -> Result<()> {
             && update.modified
         {
             // Write the updated README
+            transaction.record(&readme_path)?;
             std::fs::write(&readme_path, &update.content)
                 .with_context(|| format!("Failed to write {}", readme_path.display()))?;
             logger.print_message("  Updated version in README.md");
@@ -347,101 +507,206 @@ pub fn bump(args: BumpArgs) -> Result<()> {
         None
     };
 
-    // Step 7: Run pre-bump hooks
+    // Step 7: Update configured version files (package.json, pyproject.toml,
+    // ...), capturing HEAD content first for selective staging
+    let mut version_files = Vec::new();
+    for file in &hook_config.version_files {
+        let path = manifest_dir.join(&file.path);
+        let head_content = get_file_head_content(manifest_path, &path).ok();
+        logger.status("Updating", &file.path);
+        transaction.record(&path)?;
+        let (path, modified) =
+            version_files::update_version_file(manifest_dir, file, target_version)?;
+        logger.finish();
+        if modified {
+            logger.print_message(&format!("  Updated version in {}", file.path));
+            version_files.push((path, head_content, file.clone()));
+        }
+    }
+
+    // Step 8: Update html_root_url and doc comment versions in crate sources
+    let doc_paths = doc_versions::doc_file_paths(manifest_dir, &hook_config.doc_files);
+    for path in &doc_paths {
+        transaction.record(path)?;
+    }
+    let mut reference_updates = Vec::new();
+    for update in
+        doc_versions::update_doc_files(&doc_paths, package_name, current_version, target_version)?
+    {
+        logger.print_message(&format!(
+            "  Updated doc versions in {}",
+            update.path.display()
+        ));
+        let head_content = get_file_head_content(manifest_path, &update.path).ok();
+        reference_updates.push((update, head_content));
+    }
+
+    // Step 9: Apply version references in other files, leaving files with
+    // their own update step alone
+    let mut skip = vec![manifest_path.to_path_buf(), cargo_lock_path.clone()];
+    if !args.no_readme {
+        skip.push(readme_path.clone());
+    }
+    skip.extend(version_files.iter().map(|(path, ..)| path.clone()));
+    skip.extend(doc_paths);
+    if !hook_config.version_references.is_empty() {
+        logger.status("Updating", "version references");
+        let mut references = Vec::new();
+        for update in version_references::find_reference_updates(
+            manifest_dir,
+            &hook_config.version_references,
+            current_version,
+            target_version,
+            &skip,
+        )? {
+            let head_content = get_file_head_content(manifest_path, &update.path).ok();
+            transaction.record(&update.path)?;
+            update.write()?;
+            references.push((update, head_content));
+        }
+        logger.finish();
+        for (update, _) in &references {
+            logger.print_message(&format!(
+                "  Updated version references in {}",
+                update.path.display()
+            ));
+        }
+        reference_updates.extend(references);
+    }
+
+    // Step 10: Run pre-bump hooks
     // These hooks run after all file updates but before commit, allowing them to
     // modify additional files that will be included in the commit
+    for file_path in &hook_config.additional_files {
+        transaction.record(&manifest_dir.join(file_path))?;
+    }
     for hook in &hook_config.pre_bump_hooks {
         logger.status("Running", &format!("hook: {}", hook));
-        hooks::run_hook(hook, &target_version, manifest_dir)?;
+        hooks::run_hook(hook, target_version, manifest_dir)?;
         logger.finish();
     }
 
-    // Step 8: Commit changes (unless --no-commit)
-    if !args.no_commit {
-        logger.status("Committing", "version changes");
+    // Step 11: Commit changes (unless --no-commit)
+    if args.no_commit {
+        return Ok(None);
+    }
 
-        // Collect additional files to commit (besides Cargo.toml which is handled
-        // specially)
-        let mut additional_files: Vec<AdditionalFile> = Vec::new();
+    logger.status("Committing", "version changes");
+
+    // Collect additional files to commit (besides Cargo.toml which is handled
+    // specially)
+    let mut additional_files: Vec<AdditionalFile> = Vec::new();
+
+    // Include Cargo.lock if it was updated
+    if !args.no_lock && cargo_lock_path.exists() {
+        let cargo_lock_content = std::fs::read_to_string(&cargo_lock_path)
+            .with_context(|| format!("Failed to read {}", cargo_lock_path.display()))?;
+        additional_files.push(AdditionalFile {
+            path: cargo_lock_path,
+            working_content: cargo_lock_content,
+            head_content: cargo_lock_head_content,
+            file_type: FileType::CargoLock,
+        });
+    }
 
-        // Include Cargo.lock if it was updated
-        if !args.no_lock && cargo_lock_path.exists() {
-            let cargo_lock_content = std::fs::read_to_string(&cargo_lock_path)
-                .with_context(|| format!("Failed to read {}", cargo_lock_path.display()))?;
-            additional_files.push(AdditionalFile {
-                path: cargo_lock_path,
-                working_content: cargo_lock_content,
-                head_content: cargo_lock_head_content,
-                file_type: FileType::CargoLock,
-            });
-        }
+    // Include README.md if it was modified
+    if let Some(update) = readme_update
+        && update.modified
+    {
+        additional_files.push(AdditionalFile {
+            path: readme_path,
+            working_content: update.content,
+            head_content: readme_head_content,
+            file_type: FileType::Readme(readme_update::reference_patterns(package_name)?),
+        });
+    }
 
-        // Include README.md if it was modified
-        if let Some(update) = readme_update
-            && update.modified
-        {
+    // Include updated version files
+    for (path, head_content, file) in version_files {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        additional_files.push(AdditionalFile {
+            path,
+            working_content: content,
+            head_content,
+            file_type: FileType::VersionFile(file),
+        });
+    }
+
+    // Include files with updated version references
+    for (update, head_content) in reference_updates {
+        additional_files.push(AdditionalFile {
+            path: update.path,
+            working_content: update.content,
+            head_content,
+            file_type: FileType::VersionReference(update.line_patterns),
+        });
+    }
+
+    // Include additional files from hook configuration
+    for file_path in &hook_config.additional_files {
+        let path = manifest_dir.join(file_path);
+        if additional_files.iter().any(|file| file.path == path) {
+            // Already included as a version file
+            continue;
+        }
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let head_content = get_file_head_content(manifest_path, &path).ok();
             additional_files.push(AdditionalFile {
-                path: readme_path,
-                working_content: update.content,
-                head_content: readme_head_content,
-                file_type: FileType::Readme,
+                path,
+                working_content: content,
+                head_content,
+                file_type: FileType::Other,
             });
+        } else {
+            workflow::warning(&format!("Additional file not found: {}", path.display()));
         }
+    }
 
-        // Include additional files from hook configuration
-        for file_path in &hook_config.additional_files {
-            let path = manifest_dir.join(file_path);
-            if path.exists() {
-                let content = std::fs::read_to_string(&path)
-                    .with_context(|| format!("Failed to read {}", path.display()))?;
-                let head_content = get_file_head_content(manifest_path, &path).ok();
-                additional_files.push(AdditionalFile {
-                    path,
-                    working_content: content,
-                    head_content,
-                    file_type: FileType::Other,
-                });
-            } else {
-                logger.print_message(&format!(
-                    "⚠️  Additional file not found: {}",
-                    path.display()
-                ));
-            }
-        }
-
-        // Commit Cargo.toml (with selective staging) plus additional files
-        commit::commit_version_changes_with_files(
-            manifest_path,
-            &package_name,
-            &current_version,
-            &target_version,
-            &additional_files,
-        )?;
-        logger.finish();
+    // Commit Cargo.toml (with selective staging) plus additional files
+    let commit_id = commit::commit_version_changes_with_files(
+        manifest_path,
+        package_name,
+        current_version,
+        target_version,
+        &additional_files,
+        &hook_config.commit,
+    )?;
+    logger.finish();
 
-        let file_count = additional_files.len() + 1; // +1 for Cargo.toml
-        logger.print_message(&format!(
-            "✓ Committed version bump: {} -> {} ({} file{})",
-            current_version,
-            target_version,
-            file_count,
-            if file_count == 1 { "" } else { "s" }
-        ));
+    let file_count = additional_files.len() + 1; // +1 for Cargo.toml
+    logger.print_message(&format!(
+        "✓ Committed version bump: {} -> {} ({} file{})",
+        current_version,
+        target_version,
+        file_count,
+        if file_count == 1 { "" } else { "s" }
+    ));
 
-        // Step 9: Run post-bump hooks (only after commit)
-        for hook in &hook_config.post_bump_hooks {
-            logger.status("Running", &format!("hook: {}", hook));
-            hooks::run_hook(hook, &target_version, manifest_dir)?;
-            logger.finish();
-        }
-    } else {
-        logger.print_message(&format!(
-            "✓ Updated version to {} (not committed)",
-            target_version
-        ));
-    }
+    Ok(Some(commit_id))
+}
 
-    Ok(())
+/// Step summary of a version bump for GitHub Actions.
+fn bump_summary(
+    package_name: &str,
+    current_version: &str,
+    target_version: &str,
+    commit: Option<&str>,
+) -> String {
+    let commit = commit.map_or_else(|| "not committed".to_string(), |sha| format!("`{}`", sha));
+    workflow::summary_table(
+        "Version bump",
+        &[
+            ("Package", format!("`{}`", package_name)),
+            (
+                "Version",
+                format!("`{}` → `{}`", current_version, target_version),
+            ),
+            ("Commit", commit),
+        ],
+    )
 }
 
 /// Calculate the target version based on command arguments.
@@ -449,7 +714,7 @@ pub fn bump(args: BumpArgs) -> Result<()> {
 /// This function implements the version selection logic for all supported
 /// modes:
 /// - Manual version specification
-/// - Automatic suggestion from GitHub
+/// - Automatic suggestion from the configured version source
 /// - Semantic version increments (major/minor/patch)
 ///
 /// # Arguments
@@ -464,7 +729,7 @@ pub fn bump(args: BumpArgs) -> Result<()> {
 /// # Errors
 ///
 /// Returns an error if:
-/// - GitHub API query fails (in auto mode)
+/// - The latest version lookup fails (in auto mode)
 /// - Version parsing fails
 /// - Network requests fail
 fn calculate_target_version(args: &BumpArgs, current_version: &str) -> Result<String> {
@@ -472,12 +737,18 @@ fn calculate_target_version(args: &BumpArgs, current_version: &str) -> Result<St
         // Manual version specified
         Ok(version.trim().to_string())
     } else if args.auto {
-        // Auto-suggest from GitHub releases
-        let (owner, repo) = get_owner_repo(args.owner.clone(), args.repo.clone())?;
-        let github_token = args.github_token.as_deref();
+        // Auto-suggest from the configured version source
+        let context = github::SourceContext {
+            owner: args.owner.clone(),
+            repo: args.repo.clone(),
+            github_token: args.github_token.clone(),
+            github_api_url: args.github_api_url.clone(),
+            manifest_path: args.manifest_path.clone(),
+            registry: args.registry.clone(),
+            index: args.index.clone(),
+        };
         let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-        let (_latest, next) =
-            rt.block_on(github::calculate_next_version(&owner, &repo, github_token))?;
+        let (_latest, next) = rt.block_on(github::calculate_next_version(args.source, &context))?;
         Ok(next)
     } else {
         // Semantic version increment
@@ -11,10 +11,25 @@
 //!
 //! # Detected Patterns
 //!
-//! The module searches for patterns like:
+//! The module searches for these references to the crate:
 //! - `crate-name = "X.Y.Z"` (TOML dependency format)
+//! - `crate-name = { version = "X.Y.Z", features = [...] }` (inline table)
+//! - `cargo add crate-name@X.Y.Z`
+//! - `cargo install crate-name --version X.Y.Z` (either argument order)
 //!
 //! Both hyphenated (`my-crate`) and underscored (`my_crate`) names are handled.
+//!
+//! # Requirement Rewriting
+//!
+//! References are rewritten the way Cargo reads them:
+//! - A full version (`"0.3.1"`, `"^0.3.1"`, `"=0.3.1"`) is replaced when it is
+//!   the old version.
+//! - A partial requirement (`"0.3"`, `"~1.2"`) is replaced only when it matched
+//!   the old version but does not match the new one, keeping its precision:
+//!   `"0.3"` becomes `"0.4"` on a minor bump in 0.x and stays `"0.3"` on a
+//!   patch bump.
+//!
+//! Requirement operators (`^`, `~`, `=`) are kept.
 
 use std::path::Path;
 
@@ -24,6 +39,166 @@ use anyhow::{
 };
 use regex::Regex;
 
+/// A version requirement: optional operator and a full or partial version.
+const REQUIREMENT: &str = r"[\^~=]?\d+(?:\.\d+){0,2}(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?";
+
+/// Patterns of the version references to `package_name`.
+///
+/// Every pattern has a `req` group with the requirement. The plain
+/// `name = "req"` pattern comes first; its `name` group is used to
+/// normalize the spacing around `=`.
+///
+/// # Errors
+///
+/// Returns an error if a pattern cannot be compiled.
+pub fn reference_patterns(package_name: &str) -> Result<Vec<Regex>> {
+    // Handle both hyphenated and underscored package names
+    // Cargo treats them as equivalent
+    let names = format!(
+        "(?:{}|{})",
+        regex::escape(&package_name.replace('_', "-")),
+        regex::escape(&package_name.replace('-', "_"))
+    );
+    let boundary = r"(?:^|[^\w-])";
+    let install = r"cargo[ \t]+b?install\b";
+    [
+        // name = "req"
+        format!(r#"(?m){boundary}(?P<name>{names})\s*=\s*"(?P<req>{REQUIREMENT})""#),
+        // name = {{ version = "req", ... }}
+        format!(
+            r#"(?m){boundary}{names}\s*=\s*\{{[^}}\n]*?\bversion\s*=\s*"(?P<req>{REQUIREMENT})""#
+        ),
+        // cargo add name@req
+        format!(r"(?m){boundary}{names}@(?P<req>{REQUIREMENT})"),
+        // cargo install name --version req
+        format!(
+            r#"(?m){install}[^\n]*?[^\w-]{names}(?:[ \t][^\n]*?)?[ \t]--version(?:[ \t]+|=)"?(?P<req>{REQUIREMENT})"#
+        ),
+        // cargo install --version req name
+        format!(
+            r#"(?m){install}[^\n]*?[ \t]--version(?:[ \t]+|=)"?(?P<req>{REQUIREMENT})"?[ \t](?:[^\n]*?[ \t])?{names}(?:[ \t]|$)"#
+        ),
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).context("Invalid README reference pattern"))
+    .collect()
+}
+
+/// Split a requirement into its operator and version.
+fn split_operator(requirement: &str) -> (&str, &str) {
+    let version = requirement.trim_start_matches(['^', '~', '=']);
+    (&requirement[..requirement.len() - version.len()], version)
+}
+
+/// Whether `version` has all three components (or pre-release/build
+/// metadata, which implies a full version).
+fn is_full_version(version: &str) -> bool {
+    version.contains(['-', '+']) || version.split('.').count() >= 3
+}
+
+/// Whether a partial requirement matches `version`.
+fn partial_matches(requirement: &str, version: &str) -> bool {
+    match (
+        semver::VersionReq::parse(requirement),
+        semver::Version::parse(version),
+    ) {
+        (Ok(req), Ok(version)) => req.matches(&version),
+        _ => false,
+    }
+}
+
+/// The requirement that replaces `requirement` when bumping from
+/// `old_version` to `new_version`, or `None` if it stays as it is.
+///
+/// # Examples
+///
+/// ```
+/// use cargo_version_info::commands::bump::readme_update::rewrite_requirement;
+///
+/// assert_eq!(
+///     rewrite_requirement("0.3.1", "0.3.1", "0.4.0").as_deref(),
+///     Some("0.4.0")
+/// );
+/// assert_eq!(
+///     rewrite_requirement("^0.3", "0.3.1", "0.4.0").as_deref(),
+///     Some("^0.4")
+/// );
+/// assert_eq!(rewrite_requirement("0.3", "0.3.1", "0.3.2"), None);
+/// assert_eq!(rewrite_requirement("1.2", "1.2.3", "1.3.0"), None);
+/// ```
+pub fn rewrite_requirement(
+    requirement: &str,
+    old_version: &str,
+    new_version: &str,
+) -> Option<String> {
+    let (operator, version) = split_operator(requirement);
+
+    if is_full_version(version) {
+        return (version == old_version).then(|| format!("{}{}", operator, new_version));
+    }
+
+    if !partial_matches(requirement, old_version) || partial_matches(requirement, new_version) {
+        return None;
+    }
+    let new_requirement = if new_version.contains(['-', '+']) {
+        new_version.to_string()
+    } else {
+        new_version
+            .split('.')
+            .take(version.split('.').count())
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+    Some(format!("{}{}", operator, new_requirement))
+}
+
+/// Whether `requirement` refers to `version`: a full version must be equal,
+/// a partial requirement must match it.
+///
+/// # Examples
+///
+/// ```
+/// use cargo_version_info::commands::bump::readme_update::requirement_matches;
+///
+/// assert!(requirement_matches("1.2", "1.3.0"));
+/// assert!(requirement_matches("^1.2.3", "1.2.3"));
+/// assert!(!requirement_matches("1.2.3", "1.2.4"));
+/// assert!(!requirement_matches("0.3", "0.4.0"));
+/// ```
+pub fn requirement_matches(requirement: &str, version: &str) -> bool {
+    let (_, bare) = split_operator(requirement);
+    if is_full_version(bare) {
+        bare == version
+    } else {
+        partial_matches(requirement, version)
+    }
+}
+
+/// Whether `requirement` refers to the old or new version of a bump.
+///
+/// Used for selective staging: such lines are the ones
+/// [`update_readme_content`] changes.
+pub fn is_bump_requirement(requirement: &str, old_version: &str, new_version: &str) -> bool {
+    requirement_matches(requirement, old_version) || requirement_matches(requirement, new_version)
+}
+
+/// Whether `line` holds a version reference matched by one of `patterns`
+/// (see [`reference_patterns`]) that refers to the old or new version.
+pub fn is_version_reference_line(
+    line: &str,
+    patterns: &[Regex],
+    old_version: &str,
+    new_version: &str,
+) -> bool {
+    patterns.iter().any(|re| {
+        re.captures_iter(line).any(|captures| {
+            captures
+                .name("req")
+                .is_some_and(|req| is_bump_requirement(req.as_str(), old_version, new_version))
+        })
+    })
+}
+
 /// Result of updating README version references.
 #[derive(Debug)]
 pub struct ReadmeUpdateResult {
@@ -35,8 +210,8 @@ pub struct ReadmeUpdateResult {
 
 /// Update version references in README.md content.
 ///
-/// Searches for patterns like `package-name = "old_version"` and replaces
-/// them with the new version.
+/// Searches for the references listed in the module documentation and
+/// rewrites their requirements with [`rewrite_requirement`].
 ///
 /// # Arguments
 ///
@@ -50,6 +225,10 @@ pub struct ReadmeUpdateResult {
 /// Returns `ReadmeUpdateResult` with the (potentially modified) content
 /// and a flag indicating if changes were made.
 ///
+/// # Errors
+///
+/// Returns an error if a pattern cannot be compiled.
+///
 /// # Examples
 ///
 /// ```
@@ -57,48 +236,50 @@ pub struct ReadmeUpdateResult {
 ///
 /// let content = r#"my-crate = "0.1.0""#;
 ///
-/// let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0");
+/// let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0")?;
 /// assert!(result.modified);
 /// assert!(result.content.contains(r#"my-crate = "0.2.0""#));
+/// # Ok::<(), anyhow::Error>(())
 /// ```
 pub fn update_readme_content(
     content: &str,
     package_name: &str,
     old_version: &str,
     new_version: &str,
-) -> ReadmeUpdateResult {
-    // Handle both hyphenated and underscored package names
-    // Cargo treats them as equivalent
-    let hyphenated = package_name.replace('_', "-");
-    let underscored = package_name.replace('-', "_");
-
+) -> Result<ReadmeUpdateResult> {
     let mut result = content.to_string();
-    let mut modified = false;
-
-    // Try both naming conventions
-    for name in [&hyphenated, &underscored] {
-        // Pattern: name = "version" (with optional whitespace)
-        // Captures: (name = ")version(")
-        let pattern = format!(
-            r#"({})\s*=\s*"{}""#,
-            regex::escape(name),
-            regex::escape(old_version)
-        );
 
-        if let Ok(re) = Regex::new(&pattern) {
-            let replacement = format!(r#"{} = "{}""#, name, new_version);
-            let new_result = re.replace_all(&result, replacement.as_str());
-            if new_result != result {
-                result = new_result.into_owned();
-                modified = true;
+    for re in reference_patterns(package_name)? {
+        let mut updated = String::with_capacity(result.len());
+        let mut last = 0;
+        for captures in re.captures_iter(&result) {
+            let Some(req) = captures.name("req") else {
+                continue;
+            };
+            let Some(new_requirement) = rewrite_requirement(req.as_str(), old_version, new_version)
+            else {
+                continue;
+            };
+
+            if let Some(name) = captures.name("name") {
+                // Plain `name = "req"`: normalize the spacing around `=`
+                updated.push_str(&result[last..name.start()]);
+                updated.push_str(&format!(r#"{} = "{}""#, name.as_str(), new_requirement));
+                last = req.end() + 1;
+            } else {
+                updated.push_str(&result[last..req.start()]);
+                updated.push_str(&new_requirement);
+                last = req.end();
             }
         }
+        updated.push_str(&result[last..]);
+        result = updated;
     }
 
-    ReadmeUpdateResult {
+    Ok(ReadmeUpdateResult {
+        modified: result != content,
         content: result,
-        modified,
-    }
+    })
 }
 
 /// Update version references in a README.md file.
@@ -130,7 +311,7 @@ pub fn update_readme_file(
     let content = std::fs::read_to_string(readme_path)
         .with_context(|| format!("Failed to read {}", readme_path.display()))?;
 
-    let result = update_readme_content(&content, package_name, old_version, new_version);
+    let result = update_readme_content(&content, package_name, old_version, new_version)?;
     Ok(Some(result))
 }
 
@@ -149,7 +330,7 @@ my-crate = "0.1.0"
 ```
 "#;
 
-        let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0");
+        let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0").unwrap();
         assert!(result.modified);
         assert!(result.content.contains(r#"my-crate = "0.2.0""#));
         assert!(!result.content.contains(r#"my-crate = "0.1.0""#));
@@ -159,7 +340,7 @@ my-crate = "0.1.0"
     fn test_update_underscored_name() {
         let content = r#"my_crate = "1.0.0""#;
 
-        let result = update_readme_content(content, "my-crate", "1.0.0", "1.1.0");
+        let result = update_readme_content(content, "my-crate", "1.0.0", "1.1.0").unwrap();
         assert!(result.modified);
         assert!(result.content.contains(r#"my_crate = "1.1.0""#));
     }
@@ -168,7 +349,7 @@ my-crate = "0.1.0"
     fn test_no_match_different_version() {
         let content = r#"my-crate = "0.1.0""#;
 
-        let result = update_readme_content(content, "my-crate", "0.2.0", "0.3.0");
+        let result = update_readme_content(content, "my-crate", "0.2.0", "0.3.0").unwrap();
         assert!(!result.modified);
         assert!(result.content.contains(r#"my-crate = "0.1.0""#));
     }
@@ -177,7 +358,7 @@ my-crate = "0.1.0"
     fn test_no_match_different_crate() {
         let content = r#"other-crate = "0.1.0""#;
 
-        let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0");
+        let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0").unwrap();
         assert!(!result.modified);
     }
 
@@ -189,7 +370,7 @@ my-crate = "0.1.0"
 my-crate = "0.1.0"
 "#;
 
-        let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0");
+        let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0").unwrap();
         assert!(result.modified);
         // Both should be updated
         assert_eq!(result.content.matches(r#"my-crate = "0.2.0""#).count(), 2);
@@ -199,8 +380,87 @@ my-crate = "0.1.0"
     fn test_whitespace_variations() {
         let content = r#"my-crate="0.1.0""#;
 
-        let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0");
+        let result = update_readme_content(content, "my-crate", "0.1.0", "0.2.0").unwrap();
         assert!(result.modified);
         assert!(result.content.contains(r#"my-crate = "0.2.0""#));
     }
+
+    #[test]
+    fn test_update_inline_table() {
+        let content = r#"my-crate = { version = "0.3.1", features = ["full"] }"#;
+
+        let result = update_readme_content(content, "my-crate", "0.3.1", "0.3.2").unwrap();
+        assert_eq!(
+            result.content,
+            r#"my-crate = { version = "0.3.2", features = ["full"] }"#
+        );
+    }
+
+    #[test]
+    fn test_update_partial_requirement() {
+        let content = "my-crate = \"0.3\"\nmy_crate = { version = \"^0.3\" }\n";
+
+        // Patch bump: "0.3" still covers the new version
+        let patch = update_readme_content(content, "my-crate", "0.3.1", "0.3.2").unwrap();
+        assert!(!patch.modified);
+
+        // Minor bump in 0.x: "0.3" no longer does
+        let minor = update_readme_content(content, "my-crate", "0.3.1", "0.4.0").unwrap();
+        assert_eq!(
+            minor.content,
+            "my-crate = \"0.4\"\nmy_crate = { version = \"^0.4\" }\n"
+        );
+    }
+
+    #[test]
+    fn test_update_cargo_commands() {
+        let content = r#"
+cargo add my-crate@0.3.1
+cargo add serde my-crate@0.3 --features full
+cargo install my-crate --version 0.3.1
+cargo install --locked --version=0.3.1 my-crate
+cargo install my-crate-cli --version 0.3.1
+"#;
+
+        let result = update_readme_content(content, "my-crate", "0.3.1", "0.4.0").unwrap();
+        assert_eq!(
+            result.content,
+            r#"
+cargo add my-crate@0.4.0
+cargo add serde my-crate@0.4 --features full
+cargo install my-crate --version 0.4.0
+cargo install --locked --version=0.4.0 my-crate
+cargo install my-crate-cli --version 0.3.1
+"#
+        );
+    }
+
+    #[test]
+    fn test_is_version_reference_line() {
+        let patterns = reference_patterns("my-crate").unwrap();
+        for line in [
+            r#"my-crate = "0.3.1""#,
+            r#"my-crate = "0.4""#,
+            r#"my-crate = { version = "0.4.0" }"#,
+            "cargo add my-crate@0.3.1",
+            "cargo install my-crate --version 0.4.0",
+        ] {
+            assert!(
+                is_version_reference_line(line, &patterns, "0.3.1", "0.4.0"),
+                "{}",
+                line
+            );
+        }
+        for line in [
+            r#"my-crate = "0.2.0""#,
+            r#"other-crate = "0.3.1""#,
+            "See the 0.3.1 release notes",
+        ] {
+            assert!(
+                !is_version_reference_line(line, &patterns, "0.3.1", "0.4.0"),
+                "{}",
+                line
+            );
+        }
+    }
 }
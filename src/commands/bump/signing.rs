@@ -28,12 +28,12 @@
 //! | Key not found | **Error** - fail the bump |
 //! | Signing failed | **Error** - fail the bump |
 
+#[cfg(feature = "signing")]
 use std::path::Path;
 
-use anyhow::{
-    Context,
-    Result,
-};
+#[cfg(feature = "signing")]
+use anyhow::Context;
+use anyhow::Result;
 use bstr::ByteSlice;
 
 /// The signing format to use.
@@ -156,7 +156,18 @@ pub fn sign_commit_payload(config: &SigningConfig, payload: &[u8]) -> Result<Opt
     Ok(Some(format_signature_for_header(&signature)))
 }
 
+/// Sign payload using SSH (unavailable without the `signing` feature).
+#[cfg(not(feature = "signing"))]
+fn sign_with_ssh(_signing_key: &str, _payload: &[u8]) -> Result<Vec<u8>> {
+    anyhow::bail!(
+        "SSH commit signing requires the `signing` feature of cargo-version-info.\n\
+         Disable signing for this repository with:\n  \
+         git config commit.gpgsign false"
+    );
+}
+
 /// Sign payload using SSH (agent or file).
+#[cfg(feature = "signing")]
 fn sign_with_ssh(signing_key: &str, payload: &[u8]) -> Result<Vec<u8>> {
     // Try SSH agent first
     match sign_with_ssh_agent(signing_key, payload) {
@@ -185,6 +196,7 @@ fn sign_with_ssh(signing_key: &str, payload: &[u8]) -> Result<Vec<u8>> {
 /// string    hash_algorithm
 /// string    H(message)
 /// ```
+#[cfg(feature = "signing")]
 fn sign_with_ssh_agent(signing_key: &str, payload: &[u8]) -> Result<Vec<u8>> {
     use ssh_agent_client_rs::Client;
     use ssh_key::sha2::{
@@ -259,6 +271,7 @@ fn sign_with_ssh_agent(signing_key: &str, payload: &[u8]) -> Result<Vec<u8>> {
 /// Find a matching identity from the SSH agent.
 ///
 /// Returns the index of the identity and the public key for creating SshSig.
+#[cfg(feature = "signing")]
 fn find_matching_identity(
     identities: &[ssh_agent_client_rs::Identity<'static>],
     signing_key: &str,
@@ -352,6 +365,7 @@ fn find_matching_identity(
 /// Sign using SSH key file directly.
 ///
 /// This is a fallback when SSH agent is not available.
+#[cfg(feature = "signing")]
 fn sign_with_ssh_file(signing_key: &str, payload: &[u8]) -> Result<Vec<u8>> {
     use ssh_key::{
         HashAlg,
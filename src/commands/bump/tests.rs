@@ -7,6 +7,7 @@ use bstr::ByteSlice;
 use tempfile::TempDir;
 
 use super::*;
+use crate::test_util::git;
 
 /// Create a temporary cargo project for testing.
 ///
@@ -90,9 +91,16 @@ version = "0.1.2"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true, // Don't commit in tests
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -103,6 +111,64 @@ version = "0.1.2"
     assert!(content.contains("version = \"0.1.3\""));
 }
 
+#[test]
+#[serial_test::serial]
+fn test_bump_auto_from_alternative_registry() {
+    let dir = create_temp_cargo_project(
+        r#"
+[package]
+name = "test-registry"
+version = "0.1.0"
+"#,
+    );
+    let manifest_path = dir.path().join("Cargo.toml");
+    init_test_git_repo(dir.path());
+
+    let index_dir = TempDir::new().unwrap();
+    std::fs::write(
+        index_dir.path().join("config.json"),
+        r#"{"dl":"https://example.com/api/v1/crates"}"#,
+    )
+    .unwrap();
+    let crate_path = index_dir
+        .path()
+        .join(crate::registry::sparse_index_path("test-registry"));
+    std::fs::create_dir_all(crate_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &crate_path,
+        r#"{"name":"test-registry","vers":"0.3.4","yanked":false}"#,
+    )
+    .unwrap();
+
+    let args = BumpArgs {
+        manifest_path: Some(manifest_path.clone()),
+        version: None,
+        auto: true,
+        major: false,
+        minor: false,
+        patch: false,
+        owner: None,
+        repo: None,
+        github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Registry,
+        registry: None,
+        index: Some(index_dir.path().display().to_string()),
+        no_commit: true,
+        no_lock: true,
+        no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
+    };
+
+    bump(args).unwrap();
+
+    // The next version comes from the given index, not crates.io
+    let content = std::fs::read_to_string(&manifest_path).unwrap();
+    assert!(content.contains("version = \"0.3.5\""), "{}", content);
+}
+
 #[test]
 #[serial_test::serial]
 fn test_bump_minor_version() {
@@ -125,9 +191,16 @@ version = "0.1.2"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -159,9 +232,16 @@ version = "0.1.2"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -193,9 +273,16 @@ version = "0.1.2"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -227,9 +314,16 @@ version = "0.1.2"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -477,9 +571,16 @@ edition = "2021"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false, // DO commit
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -571,9 +672,16 @@ license = "Apache-2.0"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -653,9 +761,16 @@ version = "0.5.0"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -779,9 +894,16 @@ version = "2.0.0"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -852,9 +974,16 @@ version = "1.0.0"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -971,9 +1100,16 @@ version = "0.5.0"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -1098,9 +1234,16 @@ edition = "2021"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
     bump(args).expect("Bump should succeed");
 
@@ -1215,9 +1358,16 @@ This is a new section that was added.
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,    // Skip Cargo.lock for this test
         no_readme: false, // DO update README
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -1409,6 +1559,7 @@ source = "registry+https://github.com/rust-lang/crates.io-index"
         "0.1.0",
         "0.2.0",
         &additional_files,
+        &super::message::CommitMessageConfig::default(),
     );
     assert!(result.is_ok(), "Commit failed: {:?}", result.err());
 
@@ -1577,9 +1728,16 @@ version = "2.0.0"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,    // Don't run cargo update
         no_readme: false, // Do update README
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -1682,9 +1840,16 @@ pre_bump_hooks = ["echo '{{{{version}}}}' > {}"]
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true, // Skip commit to isolate hook test
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -1738,9 +1903,16 @@ pre_bump_hooks = ["exit 1"]
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -1794,9 +1966,16 @@ post_bump_hooks = ["echo '{{{{version}}}}' > {}"]
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false, // Need commit for post_bump_hooks
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -1854,9 +2033,16 @@ post_bump_hooks = ["echo 'executed' > {}"]
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true, // Skip commit
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -1942,9 +2128,16 @@ additional_files = ["package.json"]
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: false,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -2022,9 +2215,16 @@ pre_bump_hooks = [
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -2060,9 +2260,16 @@ version = "1.0.0"
         owner: None,
         repo: None,
         github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
         no_commit: true,
         no_lock: true,
         no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
     };
 
     let result = bump(args);
@@ -2071,3 +2278,858 @@ version = "1.0.0"
     let content = std::fs::read_to_string(&manifest_path).unwrap();
     assert!(content.contains("version = \"1.0.1\""));
 }
+
+// ============================================================================
+// Version File Tests
+// ============================================================================
+
+/// Test that configured version files are updated and only their version
+/// lines are committed.
+#[test]
+#[serial_test::serial]
+fn test_version_files_updated_and_selectively_committed() {
+    let dir = create_temp_cargo_project(
+        r#"[package]
+name = "test-version-files"
+version = "1.0.0"
+
+[package.metadata.version-info]
+version_files = [
+    { path = "npm/package.json", kind = "json" },
+    { path = "pyproject.toml", kind = "toml", key = "project.version" },
+]
+"#,
+    );
+    let manifest_path = dir.path().join("Cargo.toml");
+    let package_json = dir.path().join("npm/package.json");
+    let pyproject = dir.path().join("pyproject.toml");
+
+    std::fs::create_dir_all(dir.path().join("npm")).unwrap();
+    std::fs::write(
+        &package_json,
+        "{\n  \"name\": \"test\",\n  \"version\": \"1.0.0\",\n  \"description\": \"old\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        &pyproject,
+        "[project]\nname = \"test\"\nversion = \"1.0.0\"\n",
+    )
+    .unwrap();
+
+    init_test_git_repo(dir.path());
+    for args in [
+        vec!["add", "npm/package.json", "pyproject.toml"],
+        vec!["commit", "-m", "Add manifests"],
+    ] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+    }
+
+    // Uncommitted work in package.json that must not end up in the bump commit
+    std::fs::write(
+        &package_json,
+        "{\n  \"name\": \"test\",\n  \"version\": \"1.0.0\",\n  \"description\": \"new\"\n}\n",
+    )
+    .unwrap();
+
+    let args = BumpArgs {
+        manifest_path: Some(manifest_path.clone()),
+        patch: true,
+        version: None,
+        auto: false,
+        major: false,
+        minor: false,
+        owner: None,
+        repo: None,
+        github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
+        no_commit: false,
+        no_lock: true,
+        no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
+    };
+
+    let result = bump(args);
+    assert!(result.is_ok(), "Bump failed: {:?}", result.err());
+
+    let show = |path: &str| {
+        let output = std::process::Command::new("git")
+            .args(["show", &format!("HEAD:{}", path)])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let committed_json = show("npm/package.json");
+    assert!(committed_json.contains("\"version\": \"1.0.1\""));
+    assert!(committed_json.contains("\"description\": \"old\""));
+    assert!(show("pyproject.toml").contains("version = \"1.0.1\""));
+
+    let working_json = std::fs::read_to_string(&package_json).unwrap();
+    assert!(working_json.contains("\"version\": \"1.0.1\""));
+    assert!(working_json.contains("\"description\": \"new\""));
+}
+
+/// Test that a version file whose version is out of step with Cargo.toml is
+/// synced with exactly one version key in the commit.
+#[test]
+#[serial_test::serial]
+fn test_version_file_out_of_sync_is_synced() {
+    let dir = create_temp_cargo_project(
+        r#"[package]
+name = "test-version-files"
+version = "1.0.0"
+
+[package.metadata.version-info]
+version_files = [{ path = "package.json", kind = "json" }]
+"#,
+    );
+    let manifest_path = dir.path().join("Cargo.toml");
+    let package_json = dir.path().join("package.json");
+
+    std::fs::write(
+        &package_json,
+        "{\n  \"name\": \"test\",\n  \"version\": \"0.9.0\",\n  \"description\": \"old\"\n}\n",
+    )
+    .unwrap();
+
+    init_test_git_repo(dir.path());
+    for args in [
+        vec!["add", "package.json"],
+        vec!["commit", "-m", "Add package.json"],
+    ] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+    }
+
+    // Uncommitted work that must stay out of the bump commit
+    std::fs::write(
+        &package_json,
+        "{\n  \"name\": \"test\",\n  \"version\": \"0.9.0\",\n  \"description\": \"new\"\n}\n",
+    )
+    .unwrap();
+
+    let args = BumpArgs {
+        manifest_path: Some(manifest_path.clone()),
+        patch: true,
+        version: None,
+        auto: false,
+        major: false,
+        minor: false,
+        owner: None,
+        repo: None,
+        github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
+        no_commit: false,
+        no_lock: true,
+        no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
+    };
+
+    let result = bump(args);
+    assert!(result.is_ok(), "Bump failed: {:?}", result.err());
+
+    let output = std::process::Command::new("git")
+        .args(["show", "HEAD:package.json"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let committed = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        committed,
+        "{\n  \"name\": \"test\",\n  \"version\": \"1.0.1\",\n  \"description\": \"old\"\n}\n"
+    );
+}
+
+/// Test that version references are replaced in files matching their glob
+/// and only the reference lines are committed.
+#[test]
+#[serial_test::serial]
+fn test_version_references_updated_and_selectively_committed() {
+    let dir = create_temp_cargo_project(
+        r#"[package]
+name = "test-version-references"
+version = "1.0.0"
+
+[package.metadata.version-info]
+version_references = [
+    { glob = "Dockerfile", search = 'LABEL version="{old_version}"', replace = 'LABEL version="{version}"' },
+    { glob = "docs/**/*.md", search = 'test-version-references = "{old_version}"', replace = 'test-version-references = "{version}"' },
+]
+"#,
+    );
+    let manifest_path = dir.path().join("Cargo.toml");
+    let dockerfile = dir.path().join("Dockerfile");
+    let guide = dir.path().join("docs/guide/install.md");
+
+    std::fs::create_dir_all(guide.parent().unwrap()).unwrap();
+    std::fs::write(
+        &dockerfile,
+        "FROM rust:1.93\nLABEL version=\"1.0.0\"\nRUN cargo build\n",
+    )
+    .unwrap();
+    std::fs::write(
+        &guide,
+        "# Install\n\n```toml\ntest-version-references = \"1.0.0\"\n```\n",
+    )
+    .unwrap();
+
+    init_test_git_repo(dir.path());
+    for args in [
+        vec!["add", "Dockerfile", "docs"],
+        vec!["commit", "-m", "Add docs"],
+    ] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+    }
+
+    // Uncommitted work next to the version reference
+    std::fs::write(
+        &dockerfile,
+        "FROM rust:slim\nLABEL version=\"1.0.0\"\nRUN cargo build\n",
+    )
+    .unwrap();
+
+    let args = BumpArgs {
+        manifest_path: Some(manifest_path.clone()),
+        patch: true,
+        version: None,
+        auto: false,
+        major: false,
+        minor: false,
+        owner: None,
+        repo: None,
+        github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
+        no_commit: false,
+        no_lock: true,
+        no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
+    };
+
+    let result = bump(args);
+    assert!(result.is_ok(), "Bump failed: {:?}", result.err());
+
+    let show = |path: &str| {
+        let output = std::process::Command::new("git")
+            .args(["show", &format!("HEAD:{}", path)])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(
+        show("Dockerfile"),
+        "FROM rust:1.93\nLABEL version=\"1.0.1\"\nRUN cargo build\n"
+    );
+    assert!(show("docs/guide/install.md").contains("test-version-references = \"1.0.1\""));
+    assert_eq!(
+        std::fs::read_to_string(&dockerfile).unwrap(),
+        "FROM rust:slim\nLABEL version=\"1.0.1\"\nRUN cargo build\n"
+    );
+}
+
+/// Test that html_root_url and doc comment versions in src/lib.rs are
+/// updated and only those lines are committed.
+#[test]
+#[serial_test::serial]
+fn test_doc_versions_updated_in_lib_rs() {
+    let dir = create_temp_cargo_project(
+        r#"[package]
+name = "test-doc-versions"
+version = "0.3.1"
+"#,
+    );
+    let manifest_path = dir.path().join("Cargo.toml");
+    let lib_rs = dir.path().join("src/lib.rs");
+
+    std::fs::write(
+        &lib_rs,
+        "//! test-doc-versions = \"0.3\"\n#![doc(html_root_url = \"https://docs.rs/test-doc-versions/0.3.1\")]\n\npub fn old() {}\n",
+    )
+    .unwrap();
+
+    init_test_git_repo(dir.path());
+    for args in [vec!["add", "src/lib.rs"], vec!["commit", "-m", "Add lib"]] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+    }
+
+    // Uncommitted work in lib.rs
+    let dirty = std::fs::read_to_string(&lib_rs)
+        .unwrap()
+        .replace("old", "new");
+    std::fs::write(&lib_rs, dirty).unwrap();
+
+    let args = BumpArgs {
+        manifest_path: Some(manifest_path.clone()),
+        patch: false,
+        version: None,
+        auto: false,
+        major: false,
+        minor: true,
+        owner: None,
+        repo: None,
+        github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
+        no_commit: false,
+        no_lock: true,
+        no_readme: true,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
+    };
+
+    let result = bump(args);
+    assert!(result.is_ok(), "Bump failed: {:?}", result.err());
+
+    let output = std::process::Command::new("git")
+        .args(["show", "HEAD:src/lib.rs"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "//! test-doc-versions = \"0.4\"\n#![doc(html_root_url = \"https://docs.rs/test-doc-versions/0.4.0\")]\n\npub fn old() {}\n"
+    );
+    assert!(
+        std::fs::read_to_string(&lib_rs)
+            .unwrap()
+            .contains("pub fn new() {}")
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_dry_run_previews_without_writing() {
+    let dir = create_temp_cargo_project(
+        r#"[package]
+name = "test-dry-run"
+version = "1.0.0"
+"#,
+    );
+    let manifest_path = dir.path().join("Cargo.toml");
+    let readme = dir.path().join("README.md");
+    let cargo_lock = dir.path().join("Cargo.lock");
+
+    std::fs::write(&readme, "```toml\ntest-dry-run = \"1.0.0\"\n```\n").unwrap();
+    std::fs::write(
+        &cargo_lock,
+        "version = 4\n\n[[package]]\nname = \"test-dry-run\"\nversion = \"1.0.0\"\n",
+    )
+    .unwrap();
+
+    init_test_git_repo(dir.path());
+    for args in [
+        vec!["add", "README.md", "Cargo.lock"],
+        vec!["commit", "-m", "Add README and lockfile"],
+    ] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+    }
+
+    // Uncommitted work in Cargo.toml that the commit would leave out
+    let dirty_manifest =
+        std::fs::read_to_string(&manifest_path).unwrap() + "description = \"wip\"\n";
+    std::fs::write(&manifest_path, &dirty_manifest).unwrap();
+
+    let git = |args: &[&str]| git(dir.path(), args);
+    let head_before = git(&["rev-parse", "HEAD"]);
+    let objects_before = git(&["count-objects", "-v"]);
+
+    let args = BumpArgs {
+        manifest_path: Some(manifest_path.clone()),
+        patch: false,
+        version: None,
+        auto: false,
+        major: false,
+        minor: true,
+        owner: None,
+        repo: None,
+        github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
+        no_commit: false,
+        no_lock: false,
+        no_readme: false,
+        dry_run: true,
+        keep_on_failure: false,
+        branch: None,
+    };
+
+    let config = hooks::VersionInfoConfig::default();
+    let preview = dry_run::preview(
+        &args,
+        &manifest_path,
+        "test-dry-run",
+        "1.0.0",
+        "1.1.0",
+        &config,
+    )
+    .unwrap();
+    let changed: Vec<_> = preview
+        .changes
+        .iter()
+        .map(|change| change.path.strip_prefix(dir.path()).unwrap())
+        .collect();
+    assert_eq!(
+        changed,
+        ["Cargo.toml", "Cargo.lock", "README.md"].map(std::path::Path::new)
+    );
+    assert!(!preview.staged[0].content.contains("wip"));
+    assert!(preview.staged[0].content.contains("version = \"1.1.0\""));
+
+    let rendered = preview.render();
+    assert!(rendered.contains("-test-dry-run = \"1.0.0\"\n+test-dry-run = \"1.1.0\"\n"));
+    assert!(rendered.contains("    chore(version): bump 1.0.0 -> 1.1.0\n"));
+
+    let result = bump(args);
+    assert!(result.is_ok(), "Bump failed: {:?}", result.err());
+
+    // Nothing was written
+    assert_eq!(
+        std::fs::read_to_string(&manifest_path).unwrap(),
+        dirty_manifest
+    );
+    assert!(
+        std::fs::read_to_string(&readme)
+            .unwrap()
+            .contains("\"1.0.0\"")
+    );
+    assert!(
+        std::fs::read_to_string(&cargo_lock)
+            .unwrap()
+            .contains("\"1.0.0\"")
+    );
+    assert_eq!(git(&["rev-parse", "HEAD"]), head_before);
+    assert_eq!(git(&["count-objects", "-v"]), objects_before);
+}
+
+/// Create a project with a README version reference and a lockfile, with
+/// everything committed.
+fn create_bump_project(metadata: &str, files: &[(&str, &str)]) -> TempDir {
+    let dir = create_temp_cargo_project(&format!(
+        "[package]\nname = \"test-bump\"\nversion = \"1.0.0\"\n{}",
+        metadata
+    ));
+    std::fs::write(dir.path().join("README.md"), "test-bump = \"1.0.0\"\n").unwrap();
+    for (path, content) in files {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    init_test_git_repo(dir.path());
+
+    // `cargo metadata` writes Cargo.lock when bump reads the package
+    let status = std::process::Command::new("cargo")
+        .args(["generate-lockfile", "--offline"])
+        .current_dir(dir.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-m", "Add project files"]);
+    dir
+}
+
+/// Arguments for a patch bump of a project from [`create_bump_project`].
+///
+/// Commits and updates Cargo.lock and README.md; set fields on the result to
+/// change that.
+fn bump_args(dir: &TempDir) -> BumpArgs {
+    BumpArgs {
+        manifest_path: Some(dir.path().join("Cargo.toml")),
+        patch: true,
+        version: None,
+        auto: false,
+        major: false,
+        minor: false,
+        owner: None,
+        repo: None,
+        github_token: None,
+        github_api_url: None,
+        source: crate::github::VersionSource::Tags,
+        registry: None,
+        index: None,
+        no_commit: false,
+        no_lock: false,
+        no_readme: false,
+        dry_run: false,
+        keep_on_failure: false,
+        branch: None,
+    }
+}
+
+/// Files, index and HEAD that a failed bump must leave as they were.
+fn rollback_snapshot(dir: &std::path::Path) -> Vec<String> {
+    let mut snapshot: Vec<String> = ["Cargo.toml", "README.md", "Cargo.lock", "docs.txt"]
+        .iter()
+        .map(|file| std::fs::read_to_string(dir.join(file)).unwrap_or_default())
+        .collect();
+    snapshot.push(git(dir, &["status", "--porcelain"]));
+    snapshot.push(git(dir, &["rev-parse", "HEAD"]));
+    snapshot
+}
+
+#[test]
+#[serial_test::serial]
+fn test_rollback_when_cargo_update_fails() {
+    // The member pins the old version, so resolving after the bump fails
+    let dir = create_bump_project(
+        "\n[workspace]\nmembers = [\"member\"]\n",
+        &[
+            (
+                "member/Cargo.toml",
+                "[package]\nname = \"member\"\nversion = \"0.1.0\"\n\n[dependencies]\ntest-bump = { path = \"..\", version = \"=1.0.0\" }\n",
+            ),
+            ("member/src/lib.rs", ""),
+        ],
+    );
+    let before = rollback_snapshot(dir.path());
+
+    let err = bump(bump_args(&dir)).unwrap_err();
+
+    assert!(err.to_string().contains("cargo update"), "{}", err);
+    assert_eq!(rollback_snapshot(dir.path()), before);
+}
+
+#[test]
+#[serial_test::serial]
+#[cfg(unix)]
+fn test_rollback_when_pre_bump_hook_fails() {
+    let dir = create_bump_project(
+        r#"
+[package.metadata.version-info]
+pre_bump_hooks = ["echo {{version}} > docs.txt && git add README.md docs.txt && exit 1"]
+additional_files = ["docs.txt"]
+"#,
+        &[],
+    );
+    let before = rollback_snapshot(dir.path());
+
+    let err = bump(BumpArgs {
+        no_lock: true,
+        ..bump_args(&dir)
+    })
+    .unwrap_err();
+
+    assert!(err.to_string().contains("Hook failed"), "{}", err);
+    // The README update, the hook's file and what it staged are all undone
+    assert_eq!(rollback_snapshot(dir.path()), before);
+    assert!(!dir.path().join("docs.txt").exists());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_rollback_when_signing_fails() {
+    let dir = create_bump_project("", &[]);
+    git(dir.path(), &["config", "commit.gpgsign", "true"]);
+    git(dir.path(), &["config", "gpg.format", "openpgp"]);
+    git(dir.path(), &["config", "user.signingkey", "ABCDEF"]);
+    let before = rollback_snapshot(dir.path());
+
+    let err = bump(BumpArgs {
+        no_lock: true,
+        ..bump_args(&dir)
+    })
+    .unwrap_err();
+
+    assert!(format!("{:#}", err).contains("GPG signing"), "{:#}", err);
+    assert_eq!(rollback_snapshot(dir.path()), before);
+}
+
+#[test]
+#[serial_test::serial]
+fn test_rollback_when_commit_fails() {
+    let dir = create_bump_project("", &[]);
+    // Updating HEAD fails on a detached HEAD, after the commit object exists
+    git(dir.path(), &["checkout", "--detach"]);
+    let before = rollback_snapshot(dir.path());
+
+    let err = bump(BumpArgs {
+        no_lock: true,
+        ..bump_args(&dir)
+    })
+    .unwrap_err();
+
+    assert!(format!("{:#}", err).contains("detached HEAD"), "{:#}", err);
+    assert_eq!(rollback_snapshot(dir.path()), before);
+}
+
+#[test]
+#[serial_test::serial]
+#[cfg(unix)]
+fn test_keep_on_failure_leaves_files_modified() {
+    let dir = create_bump_project(
+        r#"
+[package.metadata.version-info]
+pre_bump_hooks = ["exit 1"]
+"#,
+        &[],
+    );
+
+    assert!(
+        bump(BumpArgs {
+            no_lock: true,
+            keep_on_failure: true,
+            ..bump_args(&dir)
+        })
+        .is_err()
+    );
+
+    let manifest = std::fs::read_to_string(dir.path().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("version = \"1.0.1\""));
+    let readme = std::fs::read_to_string(dir.path().join("README.md")).unwrap();
+    assert_eq!(readme, "test-bump = \"1.0.1\"\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_commit_message_template_and_trailers() {
+    let dir = create_bump_project(
+        r#"
+[package.metadata.version-info.commit]
+message = "release: {package} {tag}\n\n{changelog}"
+release_as = true
+signoff = true
+skip_ci = true
+trailers = { Release-Tag = "{tag}" }
+"#,
+        &[],
+    );
+    std::fs::write(dir.path().join("notes.txt"), "notes\n").unwrap();
+    git(dir.path(), &["add", "notes.txt"]);
+    git(dir.path(), &["commit", "-m", "feat: add notes"]);
+
+    bump(BumpArgs {
+        no_lock: true,
+        ..bump_args(&dir)
+    })
+    .unwrap();
+
+    assert_eq!(
+        git(dir.path(), &["log", "-1", "--format=%B"]),
+        "release: test-bump v1.0.1 [skip ci]\n\n- feat: add notes\n- Add project \
+         files\n- Initial commit\n\nRelease-Tag: v1.0.1\nRelease-As: 1.0.1\nSigned-off-by: Test \
+         User <test@example.com>\nBumped-by: cargo-version-info\n\n"
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_commit_message_with_tag_format() {
+    let dir = create_bump_project(
+        r#"
+[package.metadata.version-info.commit]
+message = "release {tag}\n\n{changelog}"
+tag_format = "{name}-v{version}"
+"#,
+        &[],
+    );
+    git(dir.path(), &["tag", "test-bump-v1.0.0"]);
+    std::fs::write(dir.path().join("notes.txt"), "notes\n").unwrap();
+    git(dir.path(), &["add", "notes.txt"]);
+    git(dir.path(), &["commit", "-m", "feat: add notes"]);
+
+    bump(BumpArgs {
+        no_lock: true,
+        ..bump_args(&dir)
+    })
+    .unwrap();
+
+    // The changelog stops at the configured tag of the old version
+    assert_eq!(
+        git(dir.path(), &["log", "-1", "--format=%B"]),
+        "release test-bump-v1.0.1\n\n- feat: add notes\n\nBumped-by: cargo-version-info\n\n"
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_bump_branch_without_checkout() {
+    let dir = create_bump_project("", &[]);
+    git(dir.path(), &["branch", "release/1.x"]);
+    let branch_tip = git(dir.path(), &["rev-parse", "release/1.x"]);
+
+    // main moves on to 2.0.0 and has uncommitted work
+    let cargo_toml = dir.path().join("Cargo.toml");
+    let main_manifest = std::fs::read_to_string(&cargo_toml)
+        .unwrap()
+        .replace("1.0.0", "2.0.0");
+    std::fs::write(&cargo_toml, &main_manifest).unwrap();
+    git(dir.path(), &["commit", "-am", "Start 2.0.0"]);
+    std::fs::write(dir.path().join("README.md"), "work in progress\n").unwrap();
+    let before = rollback_snapshot(dir.path());
+
+    bump(BumpArgs {
+        branch: Some("release/1.x".to_string()),
+        ..bump_args(&dir)
+    })
+    .unwrap();
+
+    // Worktree, index and HEAD are untouched
+    assert_eq!(rollback_snapshot(dir.path()), before);
+
+    // The branch got the bump commit on top of its old tip
+    assert_eq!(git(dir.path(), &["rev-parse", "release/1.x~1"]), branch_tip);
+    assert_eq!(
+        git(dir.path(), &["log", "-1", "--format=%B", "release/1.x"]),
+        format!("{}\n", commit::commit_message("1.0.0", "1.0.1"))
+    );
+    assert!(git(dir.path(), &["show", "release/1.x:Cargo.toml"]).contains("version = \"1.0.1\""));
+    assert_eq!(
+        git(dir.path(), &["show", "release/1.x:README.md"]),
+        "test-bump = \"1.0.1\"\n"
+    );
+    assert!(
+        git(dir.path(), &["show", "release/1.x:Cargo.lock"])
+            .contains("name = \"test-bump\"\nversion = \"1.0.1\"")
+    );
+    assert_eq!(
+        git(
+            dir.path(),
+            &["diff", "--name-only", "release/1.x~1", "release/1.x"]
+        ),
+        "Cargo.lock\nCargo.toml\nREADME.md\n"
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_bump_branch_refuses_checked_out_branch() {
+    let dir = create_bump_project("", &[]);
+    let branch = git(dir.path(), &["branch", "--show-current"]);
+
+    let err = bump(BumpArgs {
+        no_lock: true,
+        branch: Some(branch.trim().to_string()),
+        ..bump_args(&dir)
+    })
+    .unwrap_err()
+    .to_string();
+
+    assert!(err.contains("is checked out"), "{}", err);
+}
+
+#[test]
+#[serial_test::serial]
+fn test_bump_branch_refuses_branch_checked_out_in_worktree() {
+    let dir = create_bump_project("", &[]);
+    let worktree = TempDir::new().unwrap();
+    let worktree_path = worktree.path().join("release");
+    git(
+        dir.path(),
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "release",
+            worktree_path.to_str().unwrap(),
+        ],
+    );
+    let tip = git(dir.path(), &["rev-parse", "release"]);
+
+    let err = bump(BumpArgs {
+        no_lock: true,
+        branch: Some("release".to_string()),
+        ..bump_args(&dir)
+    })
+    .unwrap_err()
+    .to_string();
+
+    assert!(err.contains("is checked out in"), "{}", err);
+    assert_eq!(git(dir.path(), &["rev-parse", "release"]), tip);
+}
+
+#[test]
+#[serial_test::serial]
+fn test_bump_branch_refuses_other_refs() {
+    let dir = create_bump_project("", &[]);
+    git(dir.path(), &["tag", "v1.0.0"]);
+    let tag = git(dir.path(), &["rev-parse", "v1.0.0"]);
+
+    let err = bump(BumpArgs {
+        no_lock: true,
+        branch: Some("refs/tags/v1.0.0".to_string()),
+        ..bump_args(&dir)
+    })
+    .unwrap_err()
+    .to_string();
+
+    assert!(err.contains("is not a branch"), "{}", err);
+    assert_eq!(git(dir.path(), &["rev-parse", "v1.0.0"]), tag);
+}
+
+#[test]
+#[serial_test::serial]
+fn test_bump_branch_auto_uses_the_branch() {
+    let dir = create_bump_project("", &[]);
+    git(dir.path(), &["tag", "v1.0.0"]);
+    git(dir.path(), &["branch", "release/1.x"]);
+
+    // main moves on to 2.0.0, so the repository's highest tag is v2.0.0
+    let cargo_toml = dir.path().join("Cargo.toml");
+    let main_manifest = std::fs::read_to_string(&cargo_toml)
+        .unwrap()
+        .replace("1.0.0", "2.0.0");
+    std::fs::write(&cargo_toml, main_manifest).unwrap();
+    git(dir.path(), &["commit", "-qam", "Start 2.0.0"]);
+    git(dir.path(), &["tag", "v2.0.0"]);
+    let auto_args = |source| BumpArgs {
+        patch: false,
+        auto: true,
+        source,
+        no_lock: true,
+        branch: Some("release/1.x".to_string()),
+        ..bump_args(&dir)
+    };
+
+    bump(auto_args(crate::github::VersionSource::Tags)).unwrap();
+    assert!(git(dir.path(), &["show", "release/1.x:Cargo.toml"]).contains("version = \"1.0.1\""));
+
+    bump(auto_args(crate::github::VersionSource::CargoToml)).unwrap();
+    assert!(git(dir.path(), &["show", "release/1.x:Cargo.toml"]).contains("version = \"1.0.2\""));
+
+    let err = bump(auto_args(crate::github::VersionSource::Registry))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("does not depend on the branch"), "{}", err);
+}
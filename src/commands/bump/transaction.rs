@@ -0,0 +1,202 @@
+//! Rollback of a failed bump.
+//!
+//! `bump` writes Cargo.toml, Cargo.lock, README.md and the configured files
+//! before it commits. If a later step fails (`cargo update`, a pre-bump
+//! hook, commit signing or the commit itself), those files would stay
+//! modified. A [`Transaction`] records the original content of each file
+//! before it is first written, along with the git index and HEAD, and
+//! restores all of them when the bump fails.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//!
+//! use cargo_version_info::commands::bump::transaction::Transaction;
+//!
+//! # fn example() -> anyhow::Result<()> {
+//! let manifest = Path::new("Cargo.toml");
+//! let mut transaction = Transaction::begin(manifest);
+//! transaction.record(manifest)?;
+//! std::fs::write(manifest, "[package]\n")?;
+//!
+//! // Something failed: put Cargo.toml back
+//! transaction.rollback()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+
+/// Original state of the files, index and HEAD touched by a bump.
+#[derive(Debug, Default)]
+pub struct Transaction {
+    /// Recorded files and their content (`None` if the file did not exist).
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+    /// Path and content of the git index.
+    index: Option<(PathBuf, Option<Vec<u8>>)>,
+    /// Git directory and the commit HEAD pointed to.
+    head: Option<(PathBuf, gix::ObjectId)>,
+}
+
+impl Transaction {
+    /// Start a transaction, recording the git index and HEAD of the
+    /// repository containing `manifest_path`.
+    ///
+    /// Outside a git repository (or without a commit) only files are
+    /// recorded.
+    pub fn begin(manifest_path: &Path) -> Self {
+        let mut transaction = Self::default();
+        let dir = match manifest_path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        if let Ok(repo) = gix::discover(dir) {
+            let index_path = repo.index_path();
+            let index = std::fs::read(&index_path).ok();
+            transaction.index = Some((index_path, index));
+            if let Ok(head) = repo.head_id() {
+                transaction.head = Some((repo.path().to_path_buf(), head.detach()));
+            }
+        }
+        transaction
+    }
+
+    /// Record the current content of `path` before it is written.
+    ///
+    /// Only the first recording of a path counts, so the rollback restores
+    /// the content from before the bump.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read.
+    pub fn record(&mut self, path: &Path) -> Result<()> {
+        if self.files.iter().any(|(recorded, _)| recorded == path) {
+            return Ok(());
+        }
+        let content = match std::fs::read(path) {
+            Ok(content) => Some(content),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        self.files.push((path.to_path_buf(), content));
+        Ok(())
+    }
+
+    /// Restore the recorded files, the git index and HEAD.
+    ///
+    /// Files that did not exist are removed. Returns the files whose content
+    /// was changed back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file, the index or HEAD cannot be restored.
+    /// Restoring continues past failed files, so the error names the first
+    /// one only.
+    pub fn rollback(self) -> Result<Vec<PathBuf>> {
+        let mut restored = Vec::new();
+        let mut first_error = None;
+        for (path, content) in &self.files {
+            match restore_file(path, content.as_deref()) {
+                Ok(true) => restored.push(path.clone()),
+                Ok(false) => {}
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+
+        if let Some((head_git_dir, head)) = &self.head
+            && let Err(err) = restore_head(head_git_dir, *head)
+        {
+            first_error.get_or_insert(err);
+        }
+        if let Some((index_path, content)) = &self.index
+            && let Err(err) = restore_file(index_path, content.as_deref())
+        {
+            first_error.get_or_insert(err);
+        }
+
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(restored),
+        }
+    }
+}
+
+/// Put `content` back at `path` (or remove the file if `content` is
+/// `None`). Returns whether anything changed.
+fn restore_file(path: &Path, content: Option<&[u8]>) -> Result<bool> {
+    let current = std::fs::read(path).ok();
+    if current.as_deref() == content {
+        return Ok(false);
+    }
+    match content {
+        Some(content) => std::fs::write(path, content)
+            .with_context(|| format!("Failed to restore {}", path.display()))?,
+        None => std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove {}", path.display()))?,
+    }
+    Ok(true)
+}
+
+/// Move the branch HEAD points to back to `commit_id` if a commit was
+/// created.
+fn restore_head(git_dir: &Path, commit_id: gix::ObjectId) -> Result<()> {
+    let repo = gix::open(git_dir).context("Failed to open git repository")?;
+    let head = repo.head_id().context("Failed to read HEAD")?;
+    if head == commit_id {
+        return Ok(());
+    }
+    repo.head()
+        .context("Failed to read HEAD")?
+        .try_into_referent()
+        .context("HEAD is not a reference (detached HEAD state)")?
+        .set_target_id(commit_id, "bump version: rollback")
+        .context("Failed to restore HEAD reference")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_restores_and_removes_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("Cargo.toml");
+        let created = dir.path().join("Cargo.lock");
+        let untouched = dir.path().join("README.md");
+        std::fs::write(&existing, "version = \"0.1.0\"\n").unwrap();
+        std::fs::write(&untouched, "# readme\n").unwrap();
+
+        let mut transaction = Transaction::default();
+        for path in [&existing, &created, &untouched] {
+            transaction.record(path).unwrap();
+        }
+        std::fs::write(&existing, "version = \"0.2.0\"\n").unwrap();
+        std::fs::write(&created, "version = 4\n").unwrap();
+        // Recording again keeps the original content
+        transaction.record(&existing).unwrap();
+
+        let restored = transaction.rollback().unwrap();
+
+        assert_eq!(restored, vec![existing.clone(), created.clone()]);
+        assert_eq!(
+            std::fs::read_to_string(&existing).unwrap(),
+            "version = \"0.1.0\"\n"
+        );
+        assert!(!created.exists());
+        assert_eq!(std::fs::read_to_string(&untouched).unwrap(), "# readme\n");
+    }
+}
@@ -0,0 +1,545 @@
+//! Version synchronization for non-Cargo manifests.
+//!
+//! Projects that ship more than a Rust crate often repeat the version in
+//! other manifests: `package.json` for npm bindings, `pyproject.toml` for
+//! Python wheels, `Chart.yaml` for Helm charts, or a generated header. This
+//! module rewrites the version in such files as part of `bump`, so they no
+//! longer need a `pre_bump_hooks` script.
+//!
+//! # Configuration
+//!
+//! Files are listed under `version_files` in
+//! `[package.metadata.version-info]`. Paths are relative to the directory
+//! containing Cargo.toml:
+//!
+//! ```toml
+//! [package.metadata.version-info]
+//! version_files = [
+//!     { path = "npm/package.json", kind = "json" },
+//!     { path = "pyproject.toml", kind = "toml", key = "project.version" },
+//!     { path = "chart/Chart.yaml", kind = "yaml", key = "appVersion" },
+//!     { path = "include/version.h", kind = "regex", pattern = '#define VERSION "([^"]+)"' },
+//! ]
+//! ```
+//!
+//! # Kinds
+//!
+//! - **json**: String value at the dotted `key` (default `version`)
+//! - **toml**: String value at the dotted `key` (default `version`), edited
+//!   with `toml_edit` like Cargo.toml
+//! - **yaml**: Scalar at the dotted `key` (default `version`) in block
+//!   mappings; quoting and trailing comments are kept
+//! - **regex**: First capture group (or the group named `version`) of every
+//!   match of `pattern`
+//!
+//! # Format Preservation
+//!
+//! Only the bytes of the version value change. Indentation, key order,
+//! comments and trailing newlines stay as they are, which keeps the diff to
+//! a single line per value and lets `bump` stage just those lines.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use regex::Regex;
+use serde::Deserialize;
+
+/// Key used when a `json`, `toml` or `yaml` entry has no `key`.
+pub const DEFAULT_KEY: &str = "version";
+
+/// How the version is located inside a [`VersionFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionFileKind {
+    /// JSON document, e.g. `package.json`
+    Json,
+    /// TOML document, e.g. `pyproject.toml`
+    Toml,
+    /// YAML document, e.g. `Chart.yaml`
+    Yaml,
+    /// Any text file, matched with a regular expression
+    Regex,
+}
+
+/// A file whose version is kept in sync with Cargo.toml.
+///
+/// Deserialized from the `version_files` list in
+/// `[package.metadata.version-info]`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VersionFile {
+    /// Path relative to the directory containing Cargo.toml.
+    pub path: String,
+
+    /// How the version is located in the file.
+    pub kind: VersionFileKind,
+
+    /// Dotted key of the version for `json`, `toml` and `yaml` files.
+    ///
+    /// Defaults to [`DEFAULT_KEY`].
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// Regular expression for `regex` files.
+    ///
+    /// The first capture group, or the group named `version`, is replaced.
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+impl VersionFile {
+    /// The dotted key of the version, or [`DEFAULT_KEY`].
+    pub fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or(DEFAULT_KEY)
+    }
+
+    /// Return `content` with the version replaced by `new_version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be parsed, the key or pattern
+    /// does not match, or the value at the key is not a string.
+    pub fn apply(&self, content: &str, new_version: &str) -> Result<String> {
+        match self.kind {
+            VersionFileKind::Json => update_json(content, self.key(), new_version),
+            VersionFileKind::Toml => update_toml(content, self.key(), new_version),
+            VersionFileKind::Yaml => update_yaml(content, self.key(), new_version),
+            VersionFileKind::Regex => {
+                let pattern = self
+                    .pattern
+                    .as_deref()
+                    .context("`regex` version files need a `pattern`")?;
+                update_regex(content, pattern, new_version)
+            }
+        }
+    }
+}
+
+/// Rewrite the version in `file` (relative to `base_dir`) to `new_version`.
+///
+/// Returns the path of the file and whether its content changed.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, updated or written.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use std::path::Path;
+/// # use anyhow::Result;
+/// # fn example() -> Result<()> {
+/// use cargo_version_info::commands::bump::version_files::{
+///     VersionFile,
+///     VersionFileKind,
+///     update_version_file,
+/// };
+///
+/// let file = VersionFile {
+///     path: "npm/package.json".to_string(),
+///     kind: VersionFileKind::Json,
+///     key: None,
+///     pattern: None,
+/// };
+/// update_version_file(Path::new("."), &file, "1.2.3")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn update_version_file(
+    base_dir: &Path,
+    file: &VersionFile,
+    new_version: &str,
+) -> Result<(PathBuf, bool)> {
+    let path = base_dir.join(&file.path);
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = file
+        .apply(&content, new_version)
+        .with_context(|| format!("Failed to update version in {}", path.display()))?;
+
+    let modified = updated != content;
+    if modified {
+        std::fs::write(&path, &updated)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok((path, modified))
+}
+
+/// Replace the byte range `span` of `content` with `replacement`.
+fn splice(content: &str, span: (usize, usize), replacement: &str) -> String {
+    format!(
+        "{}{}{}",
+        &content[..span.0],
+        replacement,
+        &content[span.1..]
+    )
+}
+
+/// Update the string at the dotted `key` of a JSON document.
+fn update_json(content: &str, key: &str, new_version: &str) -> Result<String> {
+    let path: Vec<&str> = key.split('.').collect();
+    let mut scanner = JsonScanner {
+        bytes: content.as_bytes(),
+        pos: 0,
+    };
+    let span = scanner
+        .find(&path)?
+        .with_context(|| format!("Key `{}` not found", key))?;
+    Ok(splice(content, span, new_version))
+}
+
+/// Minimal JSON scanner that locates values without re-serializing.
+struct JsonScanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl JsonScanner<'_> {
+    /// Find the contents (without quotes) of the string at `path`.
+    fn find(&mut self, path: &[&str]) -> Result<Option<(usize, usize)>> {
+        self.skip_whitespace();
+        if self.peek() != Some(b'{') {
+            self.skip_value()?;
+            return Ok(None);
+        }
+        self.pos += 1;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(None);
+                }
+                Some(b',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some(b'"') => {}
+                _ => anyhow::bail!("Invalid JSON at byte {}", self.pos),
+            }
+
+            let (start, end) = self.string()?;
+            let name = std::str::from_utf8(&self.bytes[start..end])?;
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                anyhow::bail!("Expected `:` at byte {}", self.pos);
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+
+            if name != path[0] {
+                self.skip_value()?;
+            } else if path.len() > 1 {
+                return self.find(&path[1..]);
+            } else if self.peek() == Some(b'"') {
+                return self.string().map(Some);
+            } else {
+                anyhow::bail!("Value of `{}` is not a string", name);
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume a string and return the range of its contents.
+    fn string(&mut self) -> Result<(usize, usize)> {
+        self.pos += 1;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok((start, self.pos - 1));
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(_) => self.pos += 1,
+                None => anyhow::bail!("Unterminated JSON string"),
+            }
+        }
+    }
+
+    /// Consume any value, tracking nesting of objects and arrays.
+    fn skip_value(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'"') => {
+                    self.string()?;
+                }
+                Some(b'{' | b'[') => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                Some(b'}' | b']') if depth > 0 => {
+                    depth -= 1;
+                    self.pos += 1;
+                }
+                Some(b',' | b':') if depth > 0 => self.pos += 1,
+                Some(b'}' | b']' | b',') => return Ok(()),
+                Some(_) => {
+                    while self
+                        .peek()
+                        .is_some_and(|byte| !b",:}] \t\r\n".contains(&byte))
+                    {
+                        self.pos += 1;
+                    }
+                }
+                None => anyhow::bail!("Unexpected end of JSON"),
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Update the string at the dotted `key` of a TOML document.
+fn update_toml(content: &str, key: &str, new_version: &str) -> Result<String> {
+    let mut doc = content
+        .parse::<toml_edit::DocumentMut>()
+        .context("Failed to parse TOML")?;
+
+    let mut item = doc.as_item_mut();
+    for part in key.split('.') {
+        item = item
+            .get_mut(part)
+            .with_context(|| format!("Key `{}` not found", key))?;
+    }
+    let value = item
+        .as_value_mut()
+        .filter(|value| value.is_str())
+        .with_context(|| format!("Value of `{}` is not a string", key))?;
+
+    // Keep the whitespace and comments around the value
+    let decor = value.decor().clone();
+    *value = toml_edit::Value::from(new_version);
+    *value.decor_mut() = decor;
+
+    Ok(doc.to_string())
+}
+
+/// Update the scalar at the dotted `key` of a YAML document.
+///
+/// Only block mappings are supported (`key: value`, nested by indentation),
+/// which covers the manifests this is meant for.
+fn update_yaml(content: &str, key: &str, new_version: &str) -> Result<String> {
+    let path: Vec<&str> = key.split('.').collect();
+    // (indent, key) of the mappings enclosing the current line
+    let mut parents: Vec<(usize, &str)> = Vec::new();
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let trimmed = line.trim_start();
+        if trimmed.trim_end().is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        let Some((name, rest)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let name = name.trim().trim_matches(|c| c == '"' || c == '\'');
+
+        parents.retain(|(parent_indent, _)| *parent_indent < indent);
+        parents.push((indent, name));
+
+        let matches = parents.len() == path.len()
+            && parents
+                .iter()
+                .zip(&path)
+                .all(|((_, parent), part)| parent == part);
+        if !matches {
+            continue;
+        }
+
+        let value_start = line_start + line.len() - rest.len();
+        let (start, end) = yaml_scalar_span(rest)
+            .with_context(|| format!("Value of `{}` is not a scalar", key))?;
+        return Ok(splice(
+            content,
+            (value_start + start, value_start + end),
+            new_version,
+        ));
+    }
+
+    anyhow::bail!("Key `{}` not found", key)
+}
+
+/// Range of the scalar (inside quotes, if any) in the rest of a YAML line.
+fn yaml_scalar_span(rest: &str) -> Option<(usize, usize)> {
+    let start = rest.len() - rest.trim_start().len();
+    let value = rest[start..].trim_end_matches(['\r', '\n']);
+
+    if let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = value[1..].find(quote)?;
+        return Some((start + 1, start + 1 + end));
+    }
+
+    let end = value.find(" #").unwrap_or(value.len());
+    let scalar = value[..end].trim_end();
+    if scalar.is_empty() || scalar.starts_with(['|', '>', '{', '[', '&', '*']) {
+        return None;
+    }
+    Some((start, start + scalar.len()))
+}
+
+/// Replace the version capture group of every match of `pattern`.
+fn update_regex(content: &str, pattern: &str, new_version: &str) -> Result<String> {
+    let re = Regex::new(pattern).with_context(|| format!("Invalid pattern: {}", pattern))?;
+    if re.captures_len() < 2 {
+        anyhow::bail!("Pattern needs a capture group for the version: {}", pattern);
+    }
+
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    for captures in re.captures_iter(content) {
+        let Some(group) = captures.name("version").or_else(|| captures.get(1)) else {
+            continue;
+        };
+        result.push_str(&content[last..group.start()]);
+        result.push_str(new_version);
+        last = group.end();
+    }
+    if last == 0 {
+        anyhow::bail!("Pattern did not match: {}", pattern);
+    }
+    result.push_str(&content[last..]);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version_file(kind: VersionFileKind, key: Option<&str>) -> VersionFile {
+        VersionFile {
+            path: String::new(),
+            kind,
+            key: key.map(str::to_string),
+            pattern: None,
+        }
+    }
+
+    #[test]
+    fn test_update_json_preserves_formatting() {
+        let content = r#"{
+  "name": "my-package",
+  "dependencies": { "version": "9.9.9", "other": [1, "two", {"a": null}] },
+  "version":   "0.1.0",
+  "private": true
+}
+"#;
+        let updated = version_file(VersionFileKind::Json, None)
+            .apply(content, "0.2.0")
+            .unwrap();
+
+        assert_eq!(updated, content.replace("0.1.0", "0.2.0"));
+    }
+
+    #[test]
+    fn test_update_json_nested_key() {
+        let content = "{\"package\": {\"meta\": {\"version\": \"1.0.0\"}}}";
+        let updated = version_file(VersionFileKind::Json, Some("package.meta.version"))
+            .apply(content, "1.1.0")
+            .unwrap();
+
+        assert_eq!(
+            updated,
+            "{\"package\": {\"meta\": {\"version\": \"1.1.0\"}}}"
+        );
+    }
+
+    #[test]
+    fn test_update_json_missing_key() {
+        let result = version_file(VersionFileKind::Json, None).apply("{\"name\": \"x\"}", "1.0.0");
+        assert!(result.unwrap_err().to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_update_toml_dotted_key() {
+        let content = r#"[project]
+name = "my-package"
+version = "0.1.0" # keep in sync
+
+[tool.other]
+version = "5.0.0"
+"#;
+        let updated = version_file(VersionFileKind::Toml, Some("project.version"))
+            .apply(content, "0.2.0")
+            .unwrap();
+
+        assert_eq!(updated, content.replace("0.1.0", "0.2.0"));
+    }
+
+    #[test]
+    fn test_update_yaml_nested_key() {
+        let content = r#"# Helm chart
+apiVersion: v2
+name: my-chart
+version: 0.1.0 # chart version
+app:
+  image:
+    version: "0.1.0"
+appVersion: '0.1.0'
+"#;
+        let chart = version_file(VersionFileKind::Yaml, None)
+            .apply(content, "0.2.0")
+            .unwrap();
+        assert!(chart.contains("version: 0.2.0 # chart version"));
+        assert!(chart.contains("    version: \"0.1.0\""));
+
+        let image = version_file(VersionFileKind::Yaml, Some("app.image.version"))
+            .apply(content, "0.2.0")
+            .unwrap();
+        assert!(image.contains("    version: \"0.2.0\""));
+
+        let app = version_file(VersionFileKind::Yaml, Some("appVersion"))
+            .apply(content, "0.2.0")
+            .unwrap();
+        assert!(app.contains("appVersion: '0.2.0'"));
+    }
+
+    #[test]
+    fn test_update_regex() {
+        let file = VersionFile {
+            pattern: Some(r#"#define VERSION "([^"]+)""#.to_string()),
+            ..version_file(VersionFileKind::Regex, None)
+        };
+        let updated = file
+            .apply("#pragma once\n#define VERSION \"0.1.0\"\n", "0.2.0")
+            .unwrap();
+        assert_eq!(updated, "#pragma once\n#define VERSION \"0.2.0\"\n");
+
+        let result = file.apply("nothing here\n", "0.2.0");
+        assert!(result.unwrap_err().to_string().contains("did not match"));
+    }
+
+    #[test]
+    fn test_deserialize_version_files() {
+        let json = serde_json::json!([
+            { "path": "package.json", "kind": "json" },
+            { "path": "pyproject.toml", "kind": "toml", "key": "project.version" },
+        ]);
+        let files: Vec<VersionFile> = serde_json::from_value(json).unwrap();
+
+        assert_eq!(files[0].kind, VersionFileKind::Json);
+        assert_eq!(files[0].key(), "version");
+        assert_eq!(files[1].key(), "project.version");
+    }
+}
@@ -0,0 +1,361 @@
+//! Regex-based version references in arbitrary text files.
+//!
+//! Besides README.md, the crate version tends to show up in documentation,
+//! Dockerfiles (`LABEL version="1.2.3"`), Helm charts (`appVersion: 1.2.3`)
+//! and `CITATION.cff`. This module lets a project declare search/replace
+//! patterns for such files, which `bump` applies before committing.
+//!
+//! # Configuration
+//!
+//! References are listed under `version_references` in
+//! `[package.metadata.version-info]`:
+//!
+//! ```toml
+//! [package.metadata.version-info]
+//! version_references = [
+//!     { glob = "docs/**/*.md", search = 'my-crate = "{old_version}"', replace = 'my-crate = "{version}"' },
+//!     { glob = "Dockerfile", search = 'LABEL version="[^"]*"', replace = 'LABEL version="{version}"' },
+//!     { glob = "CITATION.cff", search = '(?m)^version: .*$', replace = 'version: {version}' },
+//! ]
+//! ```
+//!
+//! # Patterns
+//!
+//! - **glob**: Matched against tracked files, relative to the directory
+//!   containing Cargo.toml. `*` does not cross `/`, `**` does.
+//! - **search**: Regular expression. `{old_version}` and `{version}` are
+//!   replaced with the escaped old and new version first.
+//! - **replace**: Replacement text with the same placeholders. Capture groups
+//!   can be referenced as `${1}` or `${name}`.
+//!
+//! # Selective Staging
+//!
+//! A changed line is committed only if it matches `search`, either as
+//! written or with `{old_version}` standing for the new version. Other work
+//! in the same files stays uncommitted.
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use regex::Regex;
+use serde::Deserialize;
+
+/// A search/replace pattern for version references in files matching a
+/// glob.
+///
+/// Deserialized from the `version_references` list in
+/// `[package.metadata.version-info]`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VersionReference {
+    /// Glob of the files to update, relative to the directory containing
+    /// Cargo.toml.
+    pub glob: String,
+
+    /// Regular expression matching a version reference.
+    pub search: String,
+
+    /// Replacement for each match.
+    pub replace: String,
+}
+
+impl VersionReference {
+    /// Compile `search` with the placeholders filled in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expanded pattern is not a valid regex.
+    pub fn search_regex(&self, old_version: &str, new_version: &str) -> Result<Regex> {
+        let pattern = expand(
+            &self.search,
+            &regex::escape(old_version),
+            &regex::escape(new_version),
+        );
+        Regex::new(&pattern).with_context(|| format!("Invalid search pattern: {}", self.search))
+    }
+
+    /// Patterns of the lines this reference changes, before and after the
+    /// bump.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `search` is not a valid regex.
+    pub fn line_patterns(&self, old_version: &str, new_version: &str) -> Result<Vec<Regex>> {
+        Ok(vec![
+            self.search_regex(old_version, new_version)?,
+            self.search_regex(new_version, new_version)?,
+        ])
+    }
+
+    /// Return `content` with every match of `search` replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `search` is not a valid regex.
+    pub fn apply(&self, content: &str, old_version: &str, new_version: &str) -> Result<String> {
+        let re = self.search_regex(old_version, new_version)?;
+        let replacement = expand(&self.replace, old_version, new_version);
+        Ok(re.replace_all(content, replacement.as_str()).into_owned())
+    }
+
+    /// Whether `path` (relative, with `/` separators) matches `glob`.
+    pub fn matches_path(&self, path: &str) -> bool {
+        gix::glob::wildmatch(
+            self.glob.as_str().into(),
+            path.into(),
+            gix::glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+        )
+    }
+}
+
+/// A file changed by one or more [`VersionReference`]s.
+#[derive(Debug)]
+pub struct ReferenceUpdate {
+    /// Path of the file (the base directory joined with the relative path).
+    pub path: PathBuf,
+    /// Updated content.
+    pub content: String,
+    /// Patterns of the changed lines, for selective staging.
+    pub line_patterns: Vec<Regex>,
+}
+
+impl ReferenceUpdate {
+    /// Write the updated content to disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn write(&self) -> Result<()> {
+        std::fs::write(&self.path, &self.content)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+/// Apply `references` to the tracked files under `base_dir`.
+///
+/// Files listed in `skip` (e.g. Cargo.toml or README.md, which have their
+/// own update steps) are left alone. Only files whose content changes are
+/// written and returned.
+///
+/// # Errors
+///
+/// Returns an error if `base_dir` is not in a git repository, a pattern is
+/// invalid, or a file cannot be read or written.
+pub fn update_version_references(
+    base_dir: &Path,
+    references: &[VersionReference],
+    old_version: &str,
+    new_version: &str,
+    skip: &[PathBuf],
+) -> Result<Vec<ReferenceUpdate>> {
+    let updates = find_reference_updates(base_dir, references, old_version, new_version, skip)?;
+    for update in &updates {
+        update.write()?;
+    }
+    Ok(updates)
+}
+
+/// Compute the files [`update_version_references`] would change, without
+/// writing them.
+///
+/// Files that are not valid UTF-8 are skipped.
+///
+/// # Errors
+///
+/// Returns an error if `base_dir` is not in a git repository, a pattern is
+/// invalid, or a file cannot be read.
+pub fn find_reference_updates(
+    base_dir: &Path,
+    references: &[VersionReference],
+    old_version: &str,
+    new_version: &str,
+    skip: &[PathBuf],
+) -> Result<Vec<ReferenceUpdate>> {
+    if references.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut updates = Vec::new();
+    for relative in tracked_files(base_dir)? {
+        let path = base_dir.join(&relative);
+        let matching: Vec<&VersionReference> = references
+            .iter()
+            .filter(|reference| reference.matches_path(&relative))
+            .collect();
+        if matching.is_empty() || skip.contains(&path) {
+            continue;
+        }
+
+        let original = match std::fs::read_to_string(&path) {
+            Ok(original) => original,
+            // Binary files matched by a broad glob cannot hold a reference
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        let mut content = original.clone();
+        let mut line_patterns = Vec::new();
+        for reference in matching {
+            content = reference.apply(&content, old_version, new_version)?;
+            line_patterns.extend(reference.line_patterns(old_version, new_version)?);
+        }
+
+        if content != original {
+            updates.push(ReferenceUpdate {
+                path,
+                content,
+                line_patterns,
+            });
+        }
+    }
+
+    Ok(updates)
+}
+
+/// Fill in the `{old_version}` and `{version}` placeholders.
+fn expand(template: &str, old_version: &str, new_version: &str) -> String {
+    template
+        .replace("{old_version}", old_version)
+        .replace("{version}", new_version)
+}
+
+/// Tracked files under `dir`, relative to it with `/` separators.
+fn tracked_files(dir: &Path) -> Result<Vec<String>> {
+    // `Path::new("Cargo.toml").parent()` is empty
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let repo = gix::discover(dir).context("Not in a git repository")?;
+    let workdir = repo
+        .workdir()
+        .context("Cannot update version references in a bare repository")?;
+
+    let workdir = workdir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", workdir.display()))?;
+    let dir = dir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", dir.display()))?;
+    let prefix = dir
+        .strip_prefix(&workdir)
+        .context("Directory is outside the git worktree")?
+        .to_string_lossy()
+        .replace('\\', "/");
+    let prefix = if prefix.is_empty() {
+        prefix
+    } else {
+        format!("{}/", prefix)
+    };
+
+    let index = repo.index_or_empty().context("Failed to read git index")?;
+    Ok(index
+        .entries()
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .path(&index)
+                .to_string()
+                .strip_prefix(&prefix)
+                .map(str::to_string)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    fn reference(glob: &str, search: &str, replace: &str) -> VersionReference {
+        VersionReference {
+            glob: glob.to_string(),
+            search: search.to_string(),
+            replace: replace.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_with_placeholders() {
+        let dockerfile = reference(
+            "Dockerfile",
+            r#"LABEL version="{old_version}""#,
+            r#"LABEL version="{version}""#,
+        );
+        let content = "FROM rust\nLABEL version=\"0.1.0\"\nLABEL other=\"0.1.0\"\n";
+
+        assert_eq!(
+            dockerfile.apply(content, "0.1.0", "0.2.0").unwrap(),
+            "FROM rust\nLABEL version=\"0.2.0\"\nLABEL other=\"0.1.0\"\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_with_capture_groups() {
+        let citation = reference("CITATION.cff", r"(?m)^(version:\s*).*$", "${1}{version}");
+
+        assert_eq!(
+            citation
+                .apply("title: x\nversion: 0.0.9\n", "0.1.0", "0.2.0")
+                .unwrap(),
+            "title: x\nversion: 0.2.0\n"
+        );
+    }
+
+    #[test]
+    fn test_old_version_is_escaped() {
+        let docs = reference("*.md", "v{old_version}", "v{version}");
+
+        // The `.` in the version must not match any character
+        assert_eq!(
+            docs.apply("v0x1x0 v0.1.0", "0.1.0", "0.2.0").unwrap(),
+            "v0x1x0 v0.2.0"
+        );
+    }
+
+    #[test]
+    fn test_matches_path() {
+        let docs = reference("docs/**/*.md", "", "");
+        assert!(docs.matches_path("docs/guide/install.md"));
+        assert!(docs.matches_path("docs/index.md"));
+        assert!(!docs.matches_path("README.md"));
+
+        let top_level = reference("*.md", "", "");
+        assert!(top_level.matches_path("CHANGELOG.md"));
+        assert!(!top_level.matches_path("docs/index.md"));
+    }
+
+    #[test]
+    fn test_line_patterns_match_old_and_new_lines() {
+        let docs = reference("*.md", r#"my-crate = "{old_version}""#, "");
+        let patterns = docs.line_patterns("0.1.0", "0.2.0").unwrap();
+
+        for line in ["my-crate = \"0.1.0\"", "my-crate = \"0.2.0\""] {
+            assert!(patterns.iter().any(|re| re.is_match(line)), "{}", line);
+        }
+        assert!(!patterns.iter().any(|re| re.is_match("other = \"0.1.0\"")));
+    }
+
+    #[test]
+    fn test_find_reference_updates_skips_binary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "uses v0.1.0\n").unwrap();
+        std::fs::write(dir.path().join("logo.txt"), [0xff, 0xfe, 0x00, 0x01]).unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["add", "notes.txt", "logo.txt"]);
+        let notes = reference("*.txt", "v{old_version}", "v{version}");
+
+        let updates = find_reference_updates(dir.path(), &[notes], "0.1.0", "0.2.0", &[]).unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert!(updates[0].path.ends_with("notes.txt"));
+        assert_eq!(updates[0].content, "uses v0.2.0\n");
+    }
+}
@@ -119,6 +119,29 @@ pub fn update_cargo_toml_version(
     let content = std::fs::read_to_string(manifest_path)
         .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
 
+    let updated = update_cargo_toml_content(manifest_path, &content, new_version)?;
+
+    // Write back the modified document
+    std::fs::write(manifest_path, updated)
+        .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+
+    Ok(())
+}
+
+/// Return the Cargo.toml `content` with its version set to `new_version`.
+///
+/// This is [`update_cargo_toml_version`] without the file access, e.g. for
+/// previewing a bump. `manifest_path` is only used in error messages.
+///
+/// # Errors
+///
+/// Returns an error if the TOML is invalid or no `[package]` or
+/// `[workspace.package]` version is found.
+pub fn update_cargo_toml_content(
+    manifest_path: &Path,
+    content: &str,
+    new_version: &str,
+) -> Result<String> {
     // Parse the TOML document while preserving formatting
     // This creates a DocumentMut which tracks all formatting information
     let mut doc = content
@@ -159,13 +182,46 @@ pub fn update_cargo_toml_version(
         );
     }
 
-    // Write back the modified document
-    // The to_string() method serializes the document while preserving all
-    // formatting that was tracked during parsing
-    std::fs::write(manifest_path, doc.to_string())
-        .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+    Ok(doc.to_string())
+}
 
-    Ok(())
+/// Return the Cargo.lock `content` with the version of `crate_name` set to
+/// `new_version`.
+///
+/// This is the part of `cargo update --workspace` that a bump commits,
+/// computed without running cargo (e.g. for previewing a bump). Only the
+/// crate's own `[[package]]` entry changes; the content is returned as is
+/// if there is no such entry.
+///
+/// # Examples
+///
+/// ```rust
+/// use cargo_version_info::commands::bump::version_update::update_cargo_lock_content;
+///
+/// let lock = "[[package]]\nname = \"my-crate\"\nversion = \"0.1.0\"\n";
+///
+/// assert_eq!(
+///     update_cargo_lock_content(lock, "my-crate", "0.2.0"),
+///     "[[package]]\nname = \"my-crate\"\nversion = \"0.2.0\"\n"
+/// );
+/// ```
+pub fn update_cargo_lock_content(content: &str, crate_name: &str, new_version: &str) -> String {
+    let Some((start, end)) = super::diff::find_package_block(content, crate_name) else {
+        return content.to_string();
+    };
+
+    let block: String = content[start..end]
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.starts_with("version = ") {
+                let ending = &line[line.trim_end().len()..];
+                format!("version = \"{}\"{}", new_version, ending)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    format!("{}{}{}", &content[..start], block, &content[end..])
 }
 
 #[cfg(test)]
@@ -284,6 +340,23 @@ version.workspace = true
         assert!(content.contains("version.workspace = true"));
     }
 
+    #[test]
+    fn test_update_cargo_lock_content_only_our_crate() {
+        let lock = "version = 4\n\n[[package]]\nname = \"dep\"\nversion = \"0.1.0\"\n\n[[package]]\nname = \"my-crate\"\nversion = \"0.1.0\"\ndependencies = [\n \"dep\",\n]\n";
+
+        let updated = update_cargo_lock_content(lock, "my-crate", "0.2.0");
+
+        assert_eq!(
+            updated,
+            lock.replacen(
+                "my-crate\"\nversion = \"0.1.0",
+                "my-crate\"\nversion = \"0.2.0",
+                1
+            )
+        );
+        assert_eq!(update_cargo_lock_content(lock, "other", "0.2.0"), lock);
+    }
+
     #[test]
     fn test_no_package_section_error() {
         let (_dir, manifest_path) = create_temp_manifest(
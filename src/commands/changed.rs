@@ -1,7 +1,9 @@
 //! Check if Cargo.toml version changed since last git tag command.
 //!
 //! This command compares the version in Cargo.toml with the latest git tag
-//! to determine if the version has been updated since the last release.
+//! (or, with `--against registry`, the latest version published to the crate
+//! registry) to determine if the version has been updated since the last
+//! release.
 //!
 //! # Examples
 //!
@@ -17,16 +19,47 @@
 //!
 //! # Use in GitHub Actions
 //! cargo version-info changed --format github-actions
+//!
+//! # Compare against what's published on crates.io
+//! cargo version-info changed --against registry --format json
 //! ```
 
-use std::path::PathBuf;
+use std::path::{
+    Path,
+    PathBuf,
+};
 
 use anyhow::{
     Context,
     Result,
 };
-use cargo_plugin_utils::common::get_package_version_from_manifest;
-use clap::Parser;
+use cargo_plugin_utils::common::{
+    find_package,
+    get_package_version_from_manifest,
+};
+use clap::{
+    Parser,
+    ValueEnum,
+};
+
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+use super::workflow;
+use crate::registry::RegistryIndex;
+
+/// What the Cargo.toml version is compared against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangedAgainst {
+    /// Highest semver git tag in the repository.
+    #[default]
+    Tags,
+    /// Latest non-yanked version in the crate registry.
+    Registry,
+}
 
 /// Arguments for the `changed` command.
 #[derive(Parser, Debug)]
@@ -43,23 +76,45 @@ pub struct ChangedArgs {
     #[arg(long, default_value = ".")]
     repo_path: PathBuf,
 
+    /// What to compare the Cargo.toml version against.
+    ///
+    /// - `tags` (default): Highest semver git tag
+    /// - `registry`: Latest non-yanked version published to the registry
+    #[arg(long, value_enum, default_value_t = ChangedAgainst::Tags)]
+    against: ChangedAgainst,
+
+    /// Alternative registry declared in `.cargo/config.toml`.
+    ///
+    /// Only used with `--against registry`. Defaults to crates.io.
+    #[arg(long, conflicts_with = "index")]
+    registry: Option<String>,
+
+    /// Sparse registry index URL or local index directory.
+    ///
+    /// Only used with `--against registry`.
+    #[arg(long)]
+    index: Option<String>,
+
     /// Output format for the comparison result.
     ///
     /// - `bool`: Print "true" if version changed, "false" if unchanged
     /// - `json`: Print JSON with changed, cargo_version, and latest_tag_version
-    ///   fields
+    ///   fields (with `--against registry`: latest_registry_version, published
+    ///   and yanked instead of latest_tag_version)
     /// - `diff`: Print human-readable diff (e.g., "Version changed: 0.1.0 ->
     ///   0.1.1")
-    /// - `github-actions`: Write to GITHUB_OUTPUT file in GitHub Actions format
+    /// - `github-actions`: Write changed, version and the latest version to
+    ///   GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same values as
+    ///   `KEY=value` lines (e.g. `VERSION_INFO_CHANGED=true`)
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "bool")]
     format: String,
 
-    /// Path to GitHub Actions output file.
-    ///
-    /// Only used when `--format github-actions` is specified.
-    /// Defaults to the `GITHUB_OUTPUT` environment variable or stdout.
-    #[arg(long, env = "GITHUB_OUTPUT")]
-    github_output: Option<String>,
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
 /// Check if the Cargo.toml version has changed since the last git tag.
@@ -122,29 +177,145 @@ pub struct ChangedArgs {
 /// Version unchanged: 0.1.0
 /// ```
 ///
-/// With `--format github-actions` (writes to GITHUB_OUTPUT):
+/// With `--format github-actions` (appends to GITHUB_OUTPUT):
 /// ```text
 /// changed=true
 /// version=0.1.1
 /// latest_tag_version=0.1.0
 /// ```
+///
+/// With `--against registry --format json`:
+/// ```json
+/// {"changed":true,"cargo_version":"0.1.1","latest_registry_version":"0.1.0","published":false,"yanked":[]}
+/// ```
 pub fn changed(args: ChangedArgs) -> Result<()> {
+    // `diff` is the only format not shared with the other commands
+    let format = (args.format != "diff")
+        .then(|| OutputFormat::parse(&args.format, "bool"))
+        .transpose()?;
+
     // Suppress progress when outputting to stdout (bool/json formats)
     let mut logger = cargo_plugin_utils::logger::Logger::new();
+    logger.status("Checking", "package version");
+    let options = ChangedOptions {
+        manifest_path: args
+            .manifest_path
+            .unwrap_or_else(|| PathBuf::from("./Cargo.toml")),
+        repo_path: args.repo_path,
+        against: args.against,
+        registry: args.registry,
+        index: args.index,
+    };
+    let status = changed_status(&options)?;
+    logger.finish();
+    workflow::append_step_summary(&step_summary(&status))?;
+
+    let Some(format) = format else {
+        print_diff(&status);
+        return Ok(());
+    };
+    let output_args = &args.output;
+    match status.against {
+        ChangedAgainst::Tags => format.write(
+            &TagsChangedOutput {
+                changed: status.changed,
+                cargo_version: &status.cargo_version,
+                latest_tag_version: &status.latest_version,
+            },
+            output_args,
+        ),
+        ChangedAgainst::Registry => format.write(
+            &RegistryChangedOutput {
+                changed: status.changed,
+                cargo_version: &status.cargo_version,
+                latest_registry_version: &status.latest_version,
+                published: status.published.unwrap_or(false),
+                yanked: &status.yanked,
+            },
+            output_args,
+        ),
+    }
+}
+
+/// Inputs for [`changed_status`].
+#[derive(Debug, Clone)]
+pub struct ChangedOptions {
+    /// Path to the Cargo.toml manifest.
+    pub manifest_path: PathBuf,
+    /// Path to the git repository (for [`ChangedAgainst::Tags`]).
+    pub repo_path: PathBuf,
+    /// What the Cargo.toml version is compared against.
+    pub against: ChangedAgainst,
+    /// Alternative registry (for [`ChangedAgainst::Registry`]).
+    pub registry: Option<String>,
+    /// Sparse registry index URL or local index directory (for
+    /// [`ChangedAgainst::Registry`]).
+    pub index: Option<String>,
+}
+
+impl Default for ChangedOptions {
+    fn default() -> Self {
+        Self {
+            manifest_path: PathBuf::from("./Cargo.toml"),
+            repo_path: PathBuf::from("."),
+            against: ChangedAgainst::default(),
+            registry: None,
+            index: None,
+        }
+    }
+}
+
+/// Whether the Cargo.toml version differs from the latest release.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ChangedStatus {
+    /// Whether the Cargo.toml version differs from the latest tag (for
+    /// [`ChangedAgainst::Registry`]: whether it is not published yet).
+    pub changed: bool,
+    /// Version in Cargo.toml.
+    pub cargo_version: String,
+    /// Latest tag or registry version ("0.0.0" if there is none).
+    pub latest_version: String,
+    /// What the version was compared against.
+    pub against: ChangedAgainst,
+    /// Whether the Cargo.toml version is already in the registry (only for
+    /// [`ChangedAgainst::Registry`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+    /// Yanked versions in the registry.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub yanked: Vec<String>,
+}
+
+/// Compare the Cargo.toml version with the latest release without printing.
+///
+/// # Errors
+///
+/// Returns an error if the manifest cannot be read or, with
+/// [`ChangedAgainst::Registry`], the registry index cannot be queried.
+pub fn changed_status(options: &ChangedOptions) -> Result<ChangedStatus> {
+    if options.against == ChangedAgainst::Registry {
+        return registry_status(options);
+    }
 
-    logger.status("Reading", "package version");
     // Get current version from Cargo.toml using cargo_metadata (idiomatic way)
-    let manifest_path = args
-        .manifest_path
-        .as_deref()
-        .unwrap_or_else(|| std::path::Path::new("./Cargo.toml"));
+    let manifest_path = options.manifest_path.as_path();
     let cargo_version = get_package_version_from_manifest(manifest_path)
         .with_context(|| format!("Failed to get version from {}", manifest_path.display()))?;
+    let latest_tag_version = latest_tag_version(&options.repo_path);
 
-    logger.status("Checking", "git tags");
+    Ok(ChangedStatus {
+        changed: cargo_version != latest_tag_version,
+        cargo_version,
+        latest_version: latest_tag_version,
+        against: ChangedAgainst::Tags,
+        published: None,
+        yanked: Vec::new(),
+    })
+}
 
-    // Find latest tag using gix
-    let latest_tag = gix::discover(&args.repo_path)
+/// Find the version of the highest semver tag, "0.0.0" if there is none.
+pub(crate) fn latest_tag_version(repo_path: &Path) -> String {
+    let latest_tag = gix::discover(repo_path)
         .ok()
         .and_then(|repo| {
             repo.references()
@@ -180,44 +351,139 @@ pub fn changed(args: ChangedArgs) -> Result<()> {
         .unwrap_or_else(|| "v0.0.0".to_string());
 
     // Strip optional leading v/V
-    let latest_tag_version = latest_tag
+    latest_tag
         .strip_prefix('v')
         .or_else(|| latest_tag.strip_prefix('V'))
         .unwrap_or(&latest_tag)
-        .to_string();
+        .to_string()
+}
 
-    let changed = cargo_version != latest_tag_version;
-    logger.finish();
+/// Output of `changed`.
+#[derive(serde::Serialize)]
+struct TagsChangedOutput<'a> {
+    changed: bool,
+    cargo_version: &'a str,
+    latest_tag_version: &'a str,
+}
 
-    match args.format.as_str() {
-        "bool" => println!("{}", changed),
-        "json" => println!(
-            "{{\"changed\":{},\"cargo_version\":\"{}\",\"latest_tag_version\":\"{}\"}}",
-            changed, cargo_version, latest_tag_version
-        ),
-        "diff" => {
-            if changed {
-                println!(
-                    "Version changed: {} -> {}",
-                    latest_tag_version, cargo_version
-                );
-            } else {
-                println!("Version unchanged: {}", cargo_version);
-            }
-        }
-        "github-actions" => {
-            let output_file = args.github_output.as_deref().unwrap_or("/dev/stdout");
-            let output = format!(
-                "changed={}\nversion={}\nlatest_tag_version={}\n",
-                changed, cargo_version, latest_tag_version
-            );
-            std::fs::write(output_file, output)
-                .with_context(|| format!("Failed to write to {}", output_file))?;
-        }
-        _ => anyhow::bail!("Invalid format: {}", args.format),
+impl CommandOutput for TagsChangedOutput<'_> {
+    fn text(&self) -> String {
+        self.changed.to_string()
+    }
+
+    fn variables(&self) -> Result<Vec<(String, String)>> {
+        Ok(vec![
+            ("changed".to_string(), self.changed.to_string()),
+            ("version".to_string(), self.cargo_version.to_string()),
+            (
+                "latest_tag_version".to_string(),
+                self.latest_tag_version.to_string(),
+            ),
+        ])
     }
+}
 
-    Ok(())
+/// Output of `changed --against registry`.
+#[derive(serde::Serialize)]
+struct RegistryChangedOutput<'a> {
+    changed: bool,
+    cargo_version: &'a str,
+    latest_registry_version: &'a str,
+    published: bool,
+    yanked: &'a [String],
+}
+
+impl CommandOutput for RegistryChangedOutput<'_> {
+    fn text(&self) -> String {
+        self.changed.to_string()
+    }
+
+    fn variables(&self) -> Result<Vec<(String, String)>> {
+        Ok(vec![
+            ("changed".to_string(), self.changed.to_string()),
+            ("version".to_string(), self.cargo_version.to_string()),
+            (
+                "latest_registry_version".to_string(),
+                self.latest_registry_version.to_string(),
+            ),
+            ("published".to_string(), self.published.to_string()),
+            ("yanked".to_string(), serde_json::to_string(self.yanked)?),
+        ])
+    }
+}
+
+/// Compare the Cargo.toml version with the latest version in the registry.
+fn registry_status(options: &ChangedOptions) -> Result<ChangedStatus> {
+    let manifest_path = options.manifest_path.as_path();
+    let package = find_package(Some(manifest_path))
+        .with_context(|| format!("Failed to read package from {}", manifest_path.display()))?;
+    let cargo_version = package.version.to_string();
+    let manifest_dir = package
+        .manifest_path
+        .parent()
+        .map(|dir| dir.as_std_path().to_path_buf())
+        .unwrap_or_default();
+
+    let index = RegistryIndex::resolve(
+        options.registry.as_deref(),
+        options.index.as_deref(),
+        &manifest_dir,
+    )?;
+
+    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+    let versions = rt.block_on(index.lookup(&package.name))?;
+    let latest = versions.latest().unwrap_or("0.0.0").to_string();
+
+    // A version that is already in the registry (even if yanked, or a
+    // backport below the latest) cannot be published again.
+    let published = versions.contains(&cargo_version);
+
+    Ok(ChangedStatus {
+        changed: !published,
+        published: Some(published),
+        cargo_version,
+        latest_version: latest,
+        against: ChangedAgainst::Registry,
+        yanked: versions.yanked,
+    })
+}
+
+/// Step summary of `changed` for GitHub Actions.
+fn step_summary(status: &ChangedStatus) -> String {
+    let latest = match status.against {
+        ChangedAgainst::Tags => "Latest tag",
+        ChangedAgainst::Registry => "Latest in registry",
+    };
+    let result = if status.yanked.contains(&status.cargo_version) {
+        "yanked"
+    } else if status.changed {
+        "changed"
+    } else {
+        "unchanged"
+    };
+    workflow::summary_table(
+        "Version check",
+        &[
+            ("Cargo.toml", format!("`{}`", status.cargo_version)),
+            (latest, format!("`{}`", status.latest_version)),
+            ("Result", result.to_string()),
+        ],
+    )
+}
+
+/// Print the `diff` format of `changed`.
+fn print_diff(status: &ChangedStatus) {
+    let cargo_version = status.cargo_version.as_str();
+    if status.yanked.iter().any(|version| version == cargo_version) {
+        println!("Version yanked: {}", cargo_version);
+    } else if status.changed {
+        println!(
+            "Version changed: {} -> {}",
+            status.latest_version, cargo_version
+        );
+    } else {
+        println!("Version unchanged: {}", cargo_version);
+    }
 }
 
 #[cfg(test)]
@@ -246,8 +512,11 @@ version = "0.1.0"
         let args = ChangedArgs {
             manifest_path: Some(manifest_path),
             repo_path: ".".into(),
+            against: ChangedAgainst::Tags,
+            registry: None,
+            index: None,
             format: "bool".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         // Will succeed if git repo exists, otherwise may fail on git describe
         let _ = changed(args);
@@ -266,8 +535,11 @@ version = "1.0.0"
         let args = ChangedArgs {
             manifest_path: Some(manifest_path),
             repo_path: ".".into(),
+            against: ChangedAgainst::Tags,
+            registry: None,
+            index: None,
             format: "json".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         let _ = changed(args);
     }
@@ -285,8 +557,11 @@ version = "2.0.0"
         let args = ChangedArgs {
             manifest_path: Some(manifest_path),
             repo_path: ".".into(),
+            against: ChangedAgainst::Tags,
+            registry: None,
+            index: None,
             format: "diff".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         let _ = changed(args);
     }
@@ -305,8 +580,14 @@ version = "3.0.0"
         let args = ChangedArgs {
             manifest_path: Some(manifest_path),
             repo_path: ".".into(),
+            against: ChangedAgainst::Tags,
+            registry: None,
+            index: None,
             format: "github-actions".to_string(),
-            github_output: Some(output_file.path().to_string_lossy().to_string()),
+            output: OutputArgs {
+                github_output: Some(output_file.path().to_string_lossy().to_string()),
+                ..Default::default()
+            },
         };
         let result = changed(args);
         // May succeed or fail depending on git state, but if it succeeds, check output
@@ -331,8 +612,11 @@ version = "1.0.0"
         let args = ChangedArgs {
             manifest_path: Some(manifest_path),
             repo_path: ".".into(),
+            against: ChangedAgainst::Tags,
+            registry: None,
+            index: None,
             format: "invalid".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         assert!(changed(args).is_err());
     }
@@ -342,8 +626,11 @@ version = "1.0.0"
         let args = ChangedArgs {
             manifest_path: Some("/nonexistent/Cargo.toml".into()),
             repo_path: ".".into(),
+            against: ChangedAgainst::Tags,
+            registry: None,
+            index: None,
             format: "bool".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         assert!(changed(args).is_err());
     }
@@ -360,8 +647,11 @@ name = "test"
         let args = ChangedArgs {
             manifest_path: Some(manifest_path),
             repo_path: ".".into(),
+            against: ChangedAgainst::Tags,
+            registry: None,
+            index: None,
             format: "bool".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         assert!(changed(args).is_err());
     }
@@ -378,9 +668,114 @@ version = "0.5.0"
         let args = ChangedArgs {
             manifest_path: Some(manifest_path),
             repo_path: ".".into(),
+            against: ChangedAgainst::Tags,
+            registry: None,
+            index: None,
             format: "bool".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         let _ = changed(args);
     }
+
+    #[test]
+    fn test_changed_against_local_registry_index() {
+        let dir = create_temp_cargo_project(
+            r#"
+[package]
+name = "test-crate"
+version = "0.2.0"
+edition = "2021"
+"#,
+        );
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("lib.rs"), "").unwrap();
+
+        let index_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            index_dir.path().join("config.json"),
+            r#"{"dl":"https://example.com/api/v1/crates"}"#,
+        )
+        .unwrap();
+        let crate_path = index_dir
+            .path()
+            .join(crate::registry::sparse_index_path("test-crate"));
+        std::fs::create_dir_all(crate_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &crate_path,
+            concat!(
+                r#"{"name":"test-crate","vers":"0.1.0","yanked":false}"#,
+                "\n",
+                r#"{"name":"test-crate","vers":"0.2.0","yanked":true}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let output_file = NamedTempFile::new().unwrap();
+        let args = ChangedArgs {
+            manifest_path: Some(dir.path().join("Cargo.toml")),
+            repo_path: ".".into(),
+            against: ChangedAgainst::Registry,
+            registry: None,
+            index: Some(index_dir.path().display().to_string()),
+            format: "github-actions".to_string(),
+            output: OutputArgs {
+                github_output: Some(output_file.path().to_string_lossy().to_string()),
+                ..Default::default()
+            },
+        };
+        changed(args).unwrap();
+
+        let content = std::fs::read_to_string(output_file.path()).unwrap();
+        // 0.2.0 is yanked but already published, so it cannot be published again
+        assert!(content.contains("changed=false"));
+        assert!(content.contains("latest_registry_version=0.1.0"));
+        assert!(content.contains("published=true"));
+    }
+
+    #[test]
+    fn test_changed_status_without_tags() {
+        let dir = create_temp_cargo_project(
+            r#"
+[package]
+name = "test-crate"
+version = "0.1.0"
+edition = "2021"
+"#,
+        );
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("lib.rs"), "").unwrap();
+
+        let status = changed_status(&ChangedOptions {
+            manifest_path: dir.path().join("Cargo.toml"),
+            repo_path: dir.path().to_path_buf(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(status.changed);
+        assert_eq!(status.latest_version, "0.0.0");
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"changed":true,"cargo_version":"0.1.0","latest_version":"0.0.0","against":"tags"}"#
+        );
+    }
+
+    #[test]
+    fn test_step_summary_reports_yanked_version() {
+        let status = ChangedStatus {
+            changed: false,
+            cargo_version: "0.2.0".to_string(),
+            latest_version: "0.1.0".to_string(),
+            against: ChangedAgainst::Registry,
+            published: Some(true),
+            yanked: vec!["0.2.0".to_string()],
+        };
+
+        let summary = step_summary(&status);
+
+        assert!(summary.starts_with("### Version check\n"));
+        assert!(summary.contains("| Latest in registry | `0.1.0` |"));
+        assert!(summary.contains("| Result | yanked |"));
+    }
 }
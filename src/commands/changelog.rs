@@ -26,6 +26,7 @@
 //! ```
 
 use std::collections::HashMap;
+use std::io::Write;
 
 use anyhow::{
     Context,
@@ -39,6 +40,7 @@ use cargo_plugin_utils::common::get_owner_repo;
 use clap::Parser;
 use regex::Regex;
 
+use super::workflow;
 use crate::version::parse_version;
 
 /// Arguments for the `changelog` command.
@@ -262,11 +264,11 @@ pub fn generate_changelog_to_writer(
         let start_oid = match resolve_to_commit_oid(&git_repo, start_ref) {
             Ok(oid) => Some(oid),
             Err(_) => {
-                eprintln!(
-                    "Warning: Start reference '{}' not found in repository, \
-                     generating changelog from beginning",
+                workflow::warning(&format!(
+                    "Start reference '{}' not found in repository, generating changelog from \
+                     beginning",
                     start_ref
-                );
+                ));
                 None
             }
         };
@@ -462,20 +464,31 @@ pub fn generate_changelog_to_writer(
 }
 
 /// Generate changelog from git commits.
+///
+/// In GitHub Actions, the beginning of the changelog is also appended to the
+/// step summary.
 pub fn changelog(args: ChangelogArgs) -> Result<()> {
     let output_path = args.output.clone();
 
+    let mut changelog = Vec::new();
+    generate_changelog_to_writer(&mut changelog, args)?;
+
     if let Some(ref path) = output_path {
         // Write to file
-        let mut file = std::fs::File::create(path)
-            .with_context(|| format!("Failed to create file {}", path))?;
-        generate_changelog_to_writer(&mut file, args)?;
+        std::fs::write(path, &changelog).with_context(|| format!("Failed to write {}", path))?;
     } else {
         // Write to stdout
-        let mut stdout = std::io::stdout();
-        generate_changelog_to_writer(&mut stdout, args)?;
+        std::io::stdout()
+            .write_all(&changelog)
+            .context("Failed to write changelog")?;
     }
 
+    let changelog = String::from_utf8_lossy(&changelog);
+    workflow::append_step_summary(&workflow::excerpt(
+        &changelog,
+        workflow::SUMMARY_CHANGELOG_LINES,
+    ))?;
+
     Ok(())
 }
 
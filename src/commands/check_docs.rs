@@ -0,0 +1,257 @@
+//! Doc version check command.
+//!
+//! This command verifies that the `html_root_url` attribute and the
+//! dependency snippets in doc comments of `src/lib.rs` (and the `doc_files`
+//! configured in `[package.metadata.version-info]`) match the Cargo.toml
+//! version. It fails (non-zero exit code) if any reference is out of date,
+//! which `cargo version-info bump` would otherwise have fixed.
+//!
+//! # Examples
+//!
+//! ```bash
+//! # Check src/lib.rs and configured doc files
+//! cargo version-info check-docs
+//!
+//! # Get JSON output
+//! cargo version-info check-docs --format json
+//! ```
+
+use std::path::PathBuf;
+
+use anyhow::{
+    Context,
+    Result,
+};
+use cargo_plugin_utils::common::find_package;
+use clap::Parser;
+
+use super::bump::doc_versions;
+use super::bump::hooks::VersionInfoConfig;
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+
+/// Arguments for the `check-docs` command.
+#[derive(Parser, Debug)]
+pub struct CheckDocsArgs {
+    /// Path to the Cargo.toml manifest file (standard cargo flag).
+    ///
+    /// When running as a cargo subcommand, this is automatically handled.
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Output format for the check results.
+    ///
+    /// - `text`: Print one line per version reference with ✓ or ✗
+    /// - `json`: Print JSON with name, version, up_to_date and references
+    ///   fields
+    /// - `github-actions`: Write the same fields to GITHUB_OUTPUT, with
+    ///   references as a JSON array
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same fields as
+    ///   `KEY=value` lines
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
+    #[arg(long, default_value = "text")]
+    format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// A version reference in a source file.
+#[derive(Debug, serde::Serialize)]
+struct FileDocVersion {
+    file: String,
+    #[serde(flatten)]
+    reference: doc_versions::DocVersion,
+}
+
+/// Output of the `check-docs` command.
+#[derive(serde::Serialize)]
+struct CheckDocsOutput<'a> {
+    name: &'a str,
+    version: &'a str,
+    up_to_date: bool,
+    references: &'a [FileDocVersion],
+}
+
+impl CommandOutput for CheckDocsOutput<'_> {
+    /// One line per reference with ✓ or ✗.
+    fn text(&self) -> String {
+        if self.references.is_empty() {
+            return "No doc version references found\n".to_string();
+        }
+        self.references
+            .iter()
+            .map(|entry| {
+                let reference = &entry.reference;
+                if reference.is_current() {
+                    format!("✓ {}:{}: {}\n", entry.file, reference.line, reference.found)
+                } else {
+                    format!(
+                        "✗ {}:{}: {} (expected {})\n",
+                        entry.file, reference.line, reference.found, reference.expected
+                    )
+                }
+            })
+            .collect()
+    }
+}
+
+/// Check that doc version references match the Cargo.toml version.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The manifest cannot be read
+/// - A configured doc file cannot be read
+/// - Any reference is out of date
+///
+/// # Examples
+///
+/// ```no_run
+/// use cargo_version_info::commands::{
+///     CheckDocsArgs,
+///     check_docs,
+/// };
+/// use clap::Parser;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let args = CheckDocsArgs::parse_from(&["cargo", "version-info", "check-docs"]);
+/// check_docs(args)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Example Output
+///
+/// With `--format text`:
+/// ```text
+/// ✓ src/lib.rs:12: 0.3
+/// ✗ src/lib.rs:30: 0.3.1 (expected 0.3.2)
+/// Error: 1 doc version reference is out of date with my-crate 0.3.2
+/// ```
+pub fn check_docs(args: CheckDocsArgs) -> Result<()> {
+    let format = OutputFormat::parse(&args.format, "text")?;
+
+    let package = find_package(args.manifest_path.as_deref())?;
+    let version = package.version.to_string();
+    let manifest_dir = package
+        .manifest_path
+        .parent()
+        .map(|dir| dir.as_std_path().to_path_buf())
+        .unwrap_or_default();
+    let config = VersionInfoConfig::from_package(&package);
+
+    let mut references = Vec::new();
+    for path in doc_versions::doc_file_paths(&manifest_dir, &config.doc_files) {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let file = path
+            .strip_prefix(&manifest_dir)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        for reference in doc_versions::find_doc_versions(&content, &package.name, &version)? {
+            references.push(FileDocVersion {
+                file: file.clone(),
+                reference,
+            });
+        }
+    }
+
+    let stale = references
+        .iter()
+        .filter(|entry| !entry.reference.is_current())
+        .count();
+
+    let output = CheckDocsOutput {
+        name: &package.name,
+        version: &version,
+        up_to_date: stale == 0,
+        references: &references,
+    };
+    format.write(&output, &args.output)?;
+
+    if stale > 0 {
+        anyhow::bail!(
+            "{} doc version reference{} out of date with {} {}",
+            stale,
+            if stale == 1 { " is" } else { "s are" },
+            package.name,
+            version
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_project(lib_rs: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"my-crate\"\nversion = \"0.3.2\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), lib_rs).unwrap();
+        dir
+    }
+
+    fn args(dir: &tempfile::TempDir) -> CheckDocsArgs {
+        CheckDocsArgs {
+            manifest_path: Some(dir.path().join("Cargo.toml")),
+            format: "json".to_string(),
+            output: OutputArgs::default(),
+        }
+    }
+
+    #[test]
+    fn test_check_docs_up_to_date() {
+        let dir = create_project(
+            "//! my-crate = \"0.3\"\n#![doc(html_root_url = \"https://docs.rs/my-crate/0.3.2\")]\n",
+        );
+
+        check_docs(args(&dir)).unwrap();
+    }
+
+    #[test]
+    fn test_check_docs_out_of_date() {
+        let dir = create_project(
+            "//! my-crate = \"0.3\"\n#![doc(html_root_url = \"https://docs.rs/my-crate/0.3.1\")]\n",
+        );
+
+        let err = check_docs(args(&dir)).unwrap_err().to_string();
+        assert!(
+            err.contains("1 doc version reference is out of date with my-crate 0.3.2"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_text_output() {
+        let references = [FileDocVersion {
+            file: "src/lib.rs".to_string(),
+            reference: doc_versions::DocVersion {
+                line: 3,
+                found: "0.3.1".to_string(),
+                expected: "0.3.2".to_string(),
+            },
+        }];
+        let output = CheckDocsOutput {
+            name: "my-crate",
+            version: "0.3.2",
+            up_to_date: false,
+            references: &references,
+        };
+
+        assert_eq!(output.text(), "✗ src/lib.rs:3: 0.3.1 (expected 0.3.2)\n");
+    }
+}
@@ -0,0 +1,705 @@
+//! Publish readiness check command.
+//!
+//! This command verifies that the current package can be published with
+//! `cargo publish` and fails (non-zero exit code) if any check does not pass.
+//! It is meant to run in CI right before publishing.
+//!
+//! # Checks
+//!
+//! - `registry`: The Cargo.toml version is not yet in the registry index
+//! - `tag`: The version tag (e.g. `v1.2.3`) exists and points at HEAD
+//! - `clean`: The working tree has no uncommitted changes to tracked files
+//! - `version`: The Cargo.toml version is not lower than the latest git tag
+//! - `path-deps`: Every path dependency of a publishable workspace member also
+//!   has a version requirement
+//!
+//! # Examples
+//!
+//! ```bash
+//! # Run all checks
+//! cargo version-info check-publish
+//!
+//! # Check against an alternative registry
+//! cargo version-info check-publish --registry my-registry
+//!
+//! # Skip the network lookup and the tag check
+//! cargo version-info check-publish --skip registry,tag
+//!
+//! # Get JSON output
+//! cargo version-info check-publish --format json
+//! ```
+
+use std::path::PathBuf;
+
+use anyhow::{
+    Context,
+    Result,
+};
+use cargo_metadata::semver::Version;
+use cargo_metadata::{
+    Dependency,
+    DependencyKind,
+    Metadata,
+};
+use cargo_plugin_utils::common::{
+    find_package,
+    get_metadata,
+};
+use clap::{
+    Parser,
+    ValueEnum,
+};
+
+use super::changed::latest_tag_version;
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+use crate::registry::RegistryIndex;
+use crate::worktree;
+
+/// A single publish readiness check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PublishCheck {
+    /// The version is not yet published to the registry.
+    Registry,
+    /// The version tag exists and points at HEAD.
+    Tag,
+    /// The working tree has no uncommitted changes.
+    Clean,
+    /// The version is not lower than the latest git tag.
+    Version,
+    /// Path dependencies also specify a version.
+    PathDeps,
+}
+
+/// Arguments for the `check-publish` command.
+#[derive(Parser, Debug)]
+pub struct CheckPublishArgs {
+    /// Path to the Cargo.toml manifest file (standard cargo flag).
+    ///
+    /// When running as a cargo subcommand, this is automatically handled.
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Path to the git repository.
+    ///
+    /// Defaults to the current directory.
+    #[arg(long, default_value = ".")]
+    repo_path: PathBuf,
+
+    /// Alternative registry declared in `.cargo/config.toml`.
+    ///
+    /// Defaults to crates.io.
+    #[arg(long, conflicts_with = "index")]
+    registry: Option<String>,
+
+    /// Sparse registry index URL or local index directory.
+    #[arg(long)]
+    index: Option<String>,
+
+    /// Prefix of the version tag (the tag for 1.2.3 is `v1.2.3` by default).
+    #[arg(long, default_value = "v")]
+    tag_prefix: String,
+
+    /// Checks to skip (comma-separated).
+    #[arg(long, value_enum, value_delimiter = ',')]
+    skip: Vec<PublishCheck>,
+
+    /// Output format for the check results.
+    ///
+    /// - `text`: Print one line per check with ✓ or ✗
+    /// - `json`: Print JSON with name, version, ready and checks fields
+    /// - `github-actions`: Write the same fields to GITHUB_OUTPUT, with checks
+    ///   as a JSON array
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same fields as
+    ///   `KEY=value` lines
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
+    #[arg(long, default_value = "text")]
+    format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Outcome of a single publish readiness check.
+#[derive(Debug, Clone, serde::Serialize)]
+struct CheckResult {
+    check: PublishCheck,
+    passed: bool,
+    message: String,
+}
+
+impl CheckResult {
+    fn pass(check: PublishCheck, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            passed: true,
+            message: message.into(),
+        }
+    }
+
+    fn fail(check: PublishCheck, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            passed: false,
+            message: message.into(),
+        }
+    }
+}
+
+/// Output of the `check-publish` command.
+#[derive(serde::Serialize)]
+struct CheckPublishOutput<'a> {
+    name: &'a str,
+    version: &'a str,
+    ready: bool,
+    checks: &'a [CheckResult],
+}
+
+impl CommandOutput for CheckPublishOutput<'_> {
+    /// One line per check with ✓ or ✗.
+    fn text(&self) -> String {
+        self.checks
+            .iter()
+            .map(|result| {
+                let mark = if result.passed { "✓" } else { "✗" };
+                let check = result
+                    .check
+                    .to_possible_value()
+                    .map(|value| value.get_name().to_string())
+                    .unwrap_or_default();
+                format!("{} {}: {}\n", mark, check, result.message)
+            })
+            .collect()
+    }
+}
+
+/// Check whether the current package is ready to be published.
+///
+/// Runs all checks that are not skipped, prints the results and fails if
+/// any of them did not pass. See the module documentation for the list of
+/// checks.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The manifest or cargo metadata cannot be read
+/// - The registry index cannot be resolved or queried
+/// - Any check fails
+///
+/// # Examples
+///
+/// ```no_run
+/// use cargo_version_info::commands::{
+///     CheckPublishArgs,
+///     check_publish,
+/// };
+/// use clap::Parser;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let args = CheckPublishArgs::parse_from(&["cargo", "version-info", "check-publish"]);
+/// check_publish(args)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Example Output
+///
+/// With `--format text`:
+/// ```text
+/// ✓ registry: 0.1.3 is not published to https://index.crates.io
+/// ✗ tag: Tag v0.1.3 does not exist
+/// ✓ clean: Working tree is clean
+/// ✓ version: 0.1.3 is not lower than the latest tag 0.1.2
+/// ✓ path-deps: All path dependencies have a version requirement
+/// Error: my-crate 0.1.3 is not ready to publish (1 check failed)
+/// ```
+///
+/// With `--format json`:
+/// ```json
+/// {"name":"my-crate","version":"0.1.3","ready":false,"checks":[
+///   {"check":"registry","passed":true,"message":"0.1.3 is not published to https://index.crates.io"},
+///   {"check":"tag","passed":false,"message":"Tag v0.1.3 does not exist"},
+///   {"check":"clean","passed":true,"message":"Working tree is clean"},
+///   {"check":"version","passed":true,"message":"0.1.3 is not lower than the latest tag 0.1.2"},
+///   {"check":"path-deps","passed":true,"message":"All path dependencies have a version requirement"}
+/// ]}
+/// ```
+///
+/// (wrapped for readability; the output is a single line)
+pub fn check_publish(args: CheckPublishArgs) -> Result<()> {
+    let format = OutputFormat::parse(&args.format, "text")?;
+    let mut logger = cargo_plugin_utils::logger::Logger::new();
+
+    logger.status("Reading", "package metadata");
+    let package = find_package(args.manifest_path.as_deref())?;
+    let version = package.version.to_string();
+    let manifest_dir = package
+        .manifest_path
+        .parent()
+        .map(|dir| dir.as_std_path().to_path_buf())
+        .unwrap_or_default();
+
+    let mut results = Vec::new();
+    let enabled = |check: PublishCheck| !args.skip.contains(&check);
+
+    if enabled(PublishCheck::Registry) {
+        let index = RegistryIndex::resolve(
+            args.registry.as_deref(),
+            args.index.as_deref(),
+            &manifest_dir,
+        )?;
+        logger.status("Checking", "registry index");
+        let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+        let versions = rt.block_on(index.lookup(&package.name))?;
+        results.push(check_registry(&version, &versions, &index));
+    }
+
+    if enabled(PublishCheck::Tag) || enabled(PublishCheck::Clean) {
+        logger.status("Checking", "git repository");
+        let repo = gix::discover(&args.repo_path).with_context(|| {
+            format!(
+                "Failed to discover git repository at {}",
+                args.repo_path.display()
+            )
+        })?;
+        if enabled(PublishCheck::Tag) {
+            results.push(check_tag(&repo, &format!("{}{}", args.tag_prefix, version)));
+        }
+        if enabled(PublishCheck::Clean) {
+            results.push(check_clean(&repo)?);
+        }
+    }
+
+    if enabled(PublishCheck::Version) {
+        let latest_tag = latest_tag_version(&args.repo_path);
+        results.push(check_version(&version, &latest_tag));
+    }
+
+    if enabled(PublishCheck::PathDeps) {
+        logger.status("Checking", "path dependencies");
+        let metadata = get_metadata(args.manifest_path.as_deref())?;
+        results.push(check_path_dependencies(&metadata));
+    }
+    logger.finish();
+
+    let failed = results.iter().filter(|result| !result.passed).count();
+
+    let output = CheckPublishOutput {
+        name: &package.name,
+        version: &version,
+        ready: failed == 0,
+        checks: &results,
+    };
+    format.write(&output, &args.output)?;
+
+    if failed > 0 {
+        anyhow::bail!(
+            "{} {} is not ready to publish ({} check{} failed)",
+            package.name,
+            version,
+            failed,
+            if failed == 1 { "" } else { "s" }
+        );
+    }
+
+    Ok(())
+}
+
+/// The version must not be in the index yet (yanked versions count too).
+fn check_registry(
+    version: &str,
+    versions: &crate::registry::RegistryVersions,
+    index: &RegistryIndex,
+) -> CheckResult {
+    if versions.is_yanked(version) {
+        CheckResult::fail(
+            PublishCheck::Registry,
+            format!("{} is already published to {} (yanked)", version, index),
+        )
+    } else if versions.contains(version) {
+        CheckResult::fail(
+            PublishCheck::Registry,
+            format!("{} is already published to {}", version, index),
+        )
+    } else {
+        CheckResult::pass(
+            PublishCheck::Registry,
+            format!("{} is not published to {}", version, index),
+        )
+    }
+}
+
+/// The version tag must exist and point at the HEAD commit.
+fn check_tag(repo: &gix::Repository, tag_name: &str) -> CheckResult {
+    let Ok(mut reference) = repo.find_reference(format!("refs/tags/{}", tag_name).as_str()) else {
+        return CheckResult::fail(
+            PublishCheck::Tag,
+            format!("Tag {} does not exist", tag_name),
+        );
+    };
+
+    let tag_commit = reference
+        .peel_to_id()
+        .ok()
+        .and_then(|id| repo.find_object(id).ok()?.peel_to_commit().ok())
+        .map(|commit| commit.id);
+    let head_commit = repo.head_commit().ok().map(|commit| commit.id);
+
+    match (tag_commit, head_commit) {
+        (Some(tag), Some(head)) if tag == head => CheckResult::pass(
+            PublishCheck::Tag,
+            format!(
+                "Tag {} points at HEAD ({})",
+                tag_name,
+                head.to_hex_with_len(7)
+            ),
+        ),
+        (Some(tag), Some(head)) => CheckResult::fail(
+            PublishCheck::Tag,
+            format!(
+                "Tag {} points at {}, but HEAD is {}",
+                tag_name,
+                tag.to_hex_with_len(7),
+                head.to_hex_with_len(7)
+            ),
+        ),
+        (None, _) => CheckResult::fail(
+            PublishCheck::Tag,
+            format!("Tag {} does not point to a commit", tag_name),
+        ),
+        (_, None) => CheckResult::fail(PublishCheck::Tag, "HEAD does not point to a commit"),
+    }
+}
+
+/// The working tree must have no staged or unstaged changes to tracked files.
+fn check_clean(repo: &gix::Repository) -> Result<CheckResult> {
+    let modified = worktree::modified_paths(repo)?;
+    if modified.is_empty() {
+        return Ok(CheckResult::pass(
+            PublishCheck::Clean,
+            "Working tree is clean",
+        ));
+    }
+
+    const MAX_LISTED: usize = 5;
+    let mut listed = modified
+        .iter()
+        .take(MAX_LISTED)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if modified.len() > MAX_LISTED {
+        listed.push_str(&format!(" and {} more", modified.len() - MAX_LISTED));
+    }
+    Ok(CheckResult::fail(
+        PublishCheck::Clean,
+        format!("Uncommitted changes: {}", listed),
+    ))
+}
+
+/// The version must not be lower than the highest version tag.
+fn check_version(version: &str, latest_tag: &str) -> CheckResult {
+    match (Version::parse(version), Version::parse(latest_tag)) {
+        (Ok(current), Ok(latest)) if current < latest => CheckResult::fail(
+            PublishCheck::Version,
+            format!("{} is lower than the latest tag {}", version, latest_tag),
+        ),
+        (Ok(_), Ok(_)) => CheckResult::pass(
+            PublishCheck::Version,
+            format!(
+                "{} is not lower than the latest tag {}",
+                version, latest_tag
+            ),
+        ),
+        _ => CheckResult::fail(
+            PublishCheck::Version,
+            format!("Cannot compare {} with tag {}", version, latest_tag),
+        ),
+    }
+}
+
+/// Path dependencies of publishable members must carry a version requirement.
+///
+/// `cargo publish` strips the `path` key, so a path dependency without a
+/// `version` cannot be resolved from the registry. Dev-dependencies are
+/// exempt because cargo drops them when they have no version.
+///
+/// Cargo metadata reports a missing `version` as `*`, the same as an explicit
+/// `version = "*"`, so the `version` key is looked up in the raw manifests.
+fn check_path_dependencies(metadata: &Metadata) -> CheckResult {
+    let workspace_dependencies = read_manifest(metadata.workspace_root.join("Cargo.toml"))
+        .and_then(|manifest| manifest.get("workspace")?.get("dependencies").cloned());
+
+    let missing: Vec<String> = metadata
+        .workspace_packages()
+        .into_iter()
+        .filter(|package| {
+            package
+                .publish
+                .as_ref()
+                .is_none_or(|registries| !registries.is_empty())
+        })
+        .flat_map(|package| {
+            let manifest = read_manifest(package.manifest_path.as_std_path());
+            package
+                .dependencies
+                .iter()
+                .filter(|dep| dep.path.is_some() && dep.kind != DependencyKind::Development)
+                .filter(|dep| {
+                    !declares_version(manifest.as_ref(), workspace_dependencies.as_ref(), dep)
+                })
+                .map(move |dep| format!("{} -> {}", package.name, dep.name))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    if missing.is_empty() {
+        CheckResult::pass(
+            PublishCheck::PathDeps,
+            "All path dependencies have a version requirement",
+        )
+    } else {
+        CheckResult::fail(
+            PublishCheck::PathDeps,
+            format!(
+                "Path dependencies without a version requirement: {}",
+                missing.join(", ")
+            ),
+        )
+    }
+}
+
+/// Parse a manifest as plain TOML, `None` if it cannot be read.
+fn read_manifest(path: impl AsRef<std::path::Path>) -> Option<toml::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&content).ok()
+}
+
+/// Whether the manifest entry of `dep` has a `version` key.
+///
+/// Follows `workspace = true` to the `[workspace.dependencies]` entry. Falls
+/// back to the requirement from cargo metadata if the entry is not found.
+fn declares_version(
+    manifest: Option<&toml::Value>,
+    workspace_dependencies: Option<&toml::Value>,
+    dep: &Dependency,
+) -> bool {
+    let key = dep.rename.as_deref().unwrap_or(&dep.name);
+    let section = match dep.kind {
+        DependencyKind::Build => "build-dependencies",
+        _ => "dependencies",
+    };
+    let entry = manifest.and_then(|manifest| match &dep.target {
+        Some(target) => manifest
+            .get("target")?
+            .get(target.to_string())?
+            .get(section)?
+            .get(key),
+        None => manifest.get(section)?.get(key),
+    });
+
+    let has_version = |entry: &toml::Value| entry.is_str() || entry.get("version").is_some();
+    match entry {
+        Some(entry) if entry.get("workspace").and_then(toml::Value::as_bool) == Some(true) => {
+            has_version(entry)
+                || workspace_dependencies
+                    .and_then(|dependencies| dependencies.get(key))
+                    .is_some_and(has_version)
+        }
+        Some(entry) => has_version(entry),
+        None => dep.req != cargo_metadata::semver::VersionReq::STAR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    /// Create a committed cargo project in a fresh git repository.
+    fn create_git_project(version: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"test-crate\"\nversion = \"{}\"\nedition = \"2021\"\n",
+                version
+            ),
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("lib.rs"), "// Test library\n").unwrap();
+
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.email", "test@example.com"]);
+        git(dir.path(), &["config", "user.name", "Test User"]);
+        git(dir.path(), &["config", "commit.gpgsign", "false"]);
+        git(dir.path(), &["config", "tag.gpgsign", "false"]);
+        git(dir.path(), &["add", "Cargo.toml", "src/lib.rs"]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        dir
+    }
+
+    #[test]
+    fn test_check_tag_points_at_head() {
+        let dir = create_git_project("0.1.0");
+        git(dir.path(), &["tag", "-a", "v0.1.0", "-m", "v0.1.0"]);
+        let repo = gix::discover(dir.path()).unwrap();
+
+        assert!(check_tag(&repo, "v0.1.0").passed);
+        assert!(!check_tag(&repo, "v0.2.0").passed);
+    }
+
+    #[test]
+    fn test_check_tag_points_elsewhere() {
+        let dir = create_git_project("0.1.0");
+        git(dir.path(), &["tag", "v0.1.0"]);
+        std::fs::write(dir.path().join("src").join("lib.rs"), "// Changed\n").unwrap();
+        git(dir.path(), &["commit", "-q", "-am", "change"]);
+        let repo = gix::discover(dir.path()).unwrap();
+
+        let result = check_tag(&repo, "v0.1.0");
+
+        assert!(!result.passed);
+        assert!(result.message.contains("but HEAD is"));
+    }
+
+    #[test]
+    fn test_check_clean_detects_changes() {
+        let dir = create_git_project("0.1.0");
+        let repo = gix::discover(dir.path()).unwrap();
+        assert!(check_clean(&repo).unwrap().passed);
+
+        // Untracked files are ignored
+        std::fs::write(dir.path().join("notes.txt"), "untracked").unwrap();
+        assert!(check_clean(&repo).unwrap().passed);
+
+        std::fs::write(dir.path().join("src").join("lib.rs"), "// Modified\n").unwrap();
+        let result = check_clean(&repo).unwrap();
+        assert!(!result.passed);
+        assert!(result.message.contains("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_check_clean_detects_staged_changes() {
+        let dir = create_git_project("0.1.0");
+        std::fs::write(dir.path().join("new.rs"), "// New\n").unwrap();
+        git(dir.path(), &["add", "new.rs"]);
+        let repo = gix::discover(dir.path()).unwrap();
+
+        let result = check_clean(&repo).unwrap();
+
+        assert!(!result.passed);
+        assert!(result.message.contains("new.rs"));
+    }
+
+    #[test]
+    fn test_check_version() {
+        assert!(check_version("0.2.0", "0.1.0").passed);
+        assert!(check_version("0.1.0", "0.1.0").passed);
+        assert!(!check_version("0.1.0", "0.2.0").passed);
+    }
+
+    #[test]
+    fn test_check_registry_yanked_counts_as_published() {
+        let versions = crate::registry::RegistryVersions {
+            versions: vec!["0.1.0".to_string()],
+            yanked: vec!["0.1.1".to_string()],
+        };
+        let index = RegistryIndex::crates_io();
+
+        assert!(!check_registry("0.1.0", &versions, &index).passed);
+        assert!(!check_registry("0.1.1", &versions, &index).passed);
+        assert!(check_registry("0.1.2", &versions, &index).passed);
+    }
+
+    #[test]
+    fn test_check_path_dependencies_without_version() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"app\", \"util\"]\nresolver = \"2\"\n",
+        )
+        .unwrap();
+        for (name, deps) in [("app", "util = { path = \"../util\" }\n"), ("util", "")] {
+            let member = dir.path().join(name);
+            std::fs::create_dir_all(member.join("src")).unwrap();
+            std::fs::write(member.join("src").join("lib.rs"), "").unwrap();
+            std::fs::write(
+                member.join("Cargo.toml"),
+                format!(
+                    "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n{}",
+                    name, deps
+                ),
+            )
+            .unwrap();
+        }
+        let metadata = get_metadata(Some(&dir.path().join("Cargo.toml"))).unwrap();
+
+        let result = check_path_dependencies(&metadata);
+
+        assert!(!result.passed);
+        assert!(result.message.contains("app -> util"));
+    }
+
+    #[test]
+    fn test_check_path_dependencies_with_star_version() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"app\", \"util\", \"tool\"]\nresolver = \"2\"\n\n\
+             [workspace.dependencies]\ntool = { path = \"tool\", version = \"0.1.0\" }\n",
+        )
+        .unwrap();
+        for (name, deps) in [
+            (
+                "app",
+                "util = { path = \"../util\", version = \"*\" }\ntool = { workspace = true }\n",
+            ),
+            ("util", ""),
+            ("tool", ""),
+        ] {
+            let member = dir.path().join(name);
+            std::fs::create_dir_all(member.join("src")).unwrap();
+            std::fs::write(member.join("src").join("lib.rs"), "").unwrap();
+            std::fs::write(
+                member.join("Cargo.toml"),
+                format!(
+                    "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n{}",
+                    name, deps
+                ),
+            )
+            .unwrap();
+        }
+        let metadata = get_metadata(Some(&dir.path().join("Cargo.toml"))).unwrap();
+
+        let result = check_path_dependencies(&metadata);
+
+        assert!(result.passed, "{}", result.message);
+    }
+
+    #[test]
+    fn test_check_publish_fails_when_not_ready() {
+        let dir = create_git_project("0.1.0");
+        std::fs::write(dir.path().join("src").join("lib.rs"), "// Modified\n").unwrap();
+        let args = CheckPublishArgs {
+            manifest_path: Some(dir.path().join("Cargo.toml")),
+            repo_path: dir.path().to_path_buf(),
+            registry: None,
+            index: None,
+            tag_prefix: "v".to_string(),
+            skip: vec![PublishCheck::Registry],
+            format: "json".to_string(),
+            output: OutputArgs::default(),
+        };
+
+        let error = check_publish(args).unwrap_err();
+
+        assert!(error.to_string().contains("2 checks failed"));
+    }
+}
@@ -19,6 +19,11 @@
 use anyhow::Result;
 use clap::Parser;
 
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
 use crate::version::compare_versions;
 
 /// Arguments for the `compare` command.
@@ -39,8 +44,17 @@ pub struct CompareArgs {
     /// - `bool`: Print "true" if version1 > version2, "false" otherwise
     /// - `json`: Print JSON with result, version1, and version2 fields
     /// - `diff`: Print human-readable comparison (e.g., "0.2.0 > 0.1.0")
+    /// - `github-actions`: Write result, version1 and version2 to GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same values as
+    ///   `KEY=value` lines
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "bool")]
     format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
 /// Compare two semantic version strings.
@@ -119,39 +133,58 @@ pub struct CompareArgs {
 /// 0.1.0 == 0.1.0
 /// ```
 pub fn compare(args: CompareArgs) -> Result<()> {
-    let comparison = compare_versions(&args.version1, &args.version2)?;
+    // `diff` is the only format not shared with the other commands
+    let format = (args.format != "diff")
+        .then(|| OutputFormat::parse(&args.format, "bool"))
+        .transpose()?;
+
+    let result = match compare_versions(&args.version1, &args.version2)? {
+        Some(true) => Comparison::Greater,
+        Some(false) => Comparison::Less,
+        None => Comparison::Equal,
+    };
+    let output = CompareOutput {
+        result,
+        version1: &args.version1,
+        version2: &args.version2,
+    };
 
-    match args.format.as_str() {
-        "bool" => {
-            match comparison {
-                Some(true) => println!("true"),   // version1 > version2
-                Some(false) => println!("false"), // version1 < version2
-                None => println!("false"),        // version1 == version2
-            }
+    match format {
+        Some(format) => format.write(&output, &args.output),
+        None => {
+            let operator = match result {
+                Comparison::Greater => ">",
+                Comparison::Less => "<",
+                Comparison::Equal => "==",
+            };
+            println!("{} {} {}", args.version1, operator, args.version2);
+            Ok(())
         }
-        "json" => match comparison {
-            Some(true) => println!(
-                "{{\"result\":\"greater\",\"version1\":\"{}\",\"version2\":\"{}\"}}",
-                args.version1, args.version2
-            ),
-            Some(false) => println!(
-                "{{\"result\":\"less\",\"version1\":\"{}\",\"version2\":\"{}\"}}",
-                args.version1, args.version2
-            ),
-            None => println!(
-                "{{\"result\":\"equal\",\"version1\":\"{}\",\"version2\":\"{}\"}}",
-                args.version1, args.version2
-            ),
-        },
-        "diff" => match comparison {
-            Some(true) => println!("{} > {}", args.version1, args.version2),
-            Some(false) => println!("{} < {}", args.version1, args.version2),
-            None => println!("{} == {}", args.version1, args.version2),
-        },
-        _ => anyhow::bail!("Invalid format: {}", args.format),
     }
+}
+
+/// Relationship of version1 to version2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Comparison {
+    Greater,
+    Less,
+    Equal,
+}
 
-    Ok(())
+/// Output of `compare`.
+#[derive(serde::Serialize)]
+struct CompareOutput<'a> {
+    result: Comparison,
+    version1: &'a str,
+    version2: &'a str,
+}
+
+impl CommandOutput for CompareOutput<'_> {
+    /// "true" if version1 > version2, "false" otherwise.
+    fn text(&self) -> String {
+        (self.result == Comparison::Greater).to_string()
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +197,7 @@ mod tests {
             version1: "0.2.0".to_string(),
             version2: "0.1.0".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
@@ -174,6 +208,7 @@ mod tests {
             version1: "0.1.0".to_string(),
             version2: "0.2.0".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
@@ -184,6 +219,7 @@ mod tests {
             version1: "0.1.0".to_string(),
             version2: "0.1.0".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
@@ -194,16 +230,36 @@ mod tests {
             version1: "1.0.0".to_string(),
             version2: "0.9.0".to_string(),
             format: "json".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
 
+    #[test]
+    fn test_compare_github_actions_format() {
+        let output_file = tempfile::NamedTempFile::new().unwrap();
+        let args = CompareArgs {
+            version1: "1.0.0".to_string(),
+            version2: "1.0.0".to_string(),
+            format: "github-actions".to_string(),
+            output: OutputArgs {
+                github_output: Some(output_file.path().to_string_lossy().to_string()),
+                ..Default::default()
+            },
+        };
+        assert!(compare(args).is_ok());
+
+        let content = std::fs::read_to_string(output_file.path()).unwrap();
+        assert_eq!(content, "result=equal\nversion1=1.0.0\nversion2=1.0.0\n");
+    }
+
     #[test]
     fn test_compare_diff_format() {
         let args = CompareArgs {
             version1: "2.0.0".to_string(),
             version2: "1.0.0".to_string(),
             format: "diff".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
@@ -214,6 +270,7 @@ mod tests {
             version1: "v0.2.0".to_string(),
             version2: "v0.1.0".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
@@ -224,6 +281,7 @@ mod tests {
             version1: "invalid".to_string(),
             version2: "0.1.0".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_err());
     }
@@ -234,6 +292,7 @@ mod tests {
             version1: "0.1.0".to_string(),
             version2: "invalid".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_err());
     }
@@ -244,6 +303,7 @@ mod tests {
             version1: "0.1.0".to_string(),
             version2: "0.2.0".to_string(),
             format: "invalid".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_err());
     }
@@ -254,6 +314,7 @@ mod tests {
             version1: "2.0.0".to_string(),
             version2: "1.9.9".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
@@ -264,6 +325,7 @@ mod tests {
             version1: "1.2.0".to_string(),
             version2: "1.1.9".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
@@ -274,6 +336,7 @@ mod tests {
             version1: "1.1.2".to_string(),
             version2: "1.1.1".to_string(),
             format: "bool".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(compare(args).is_ok());
     }
@@ -19,15 +19,21 @@
 //! cargo version-info current --format github-actions
 //! ```
 
-use std::path::PathBuf;
-
-use anyhow::{
-    Context,
-    Result,
+use std::path::{
+    Path,
+    PathBuf,
 };
+
+use anyhow::Result;
 use cargo_plugin_utils::common::find_package;
 use clap::Parser;
 
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+
 /// Arguments for the `current` command.
 #[derive(Parser, Debug)]
 pub struct CurrentArgs {
@@ -40,17 +46,18 @@ pub struct CurrentArgs {
     /// Output format for the version.
     ///
     /// - `version`: Print just the version number (e.g., "0.1.2")
-    /// - `json`: Print JSON with version field
+    /// - `json`: Print JSON with name and version fields
     /// - `github-actions`: Write to GITHUB_OUTPUT file in GitHub Actions format
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print
+    ///   `VERSION_INFO_NAME=...` and `VERSION_INFO_VERSION=...` lines
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "version")]
     format: String,
 
-    /// Path to GitHub Actions output file.
-    ///
-    /// Only used when `--format github-actions` is specified.
-    /// Defaults to the `GITHUB_OUTPUT` environment variable or stdout.
-    #[arg(long, env = "GITHUB_OUTPUT")]
-    github_output: Option<String>,
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
 /// Get the current version from a Cargo.toml manifest file.
@@ -90,36 +97,57 @@ pub struct CurrentArgs {
 ///
 /// With `--format json`:
 /// ```json
-/// {"version":"0.1.2"}
+/// {"name":"my-crate","version":"0.1.2"}
 /// ```
 ///
-/// With `--format github-actions` (writes to GITHUB_OUTPUT):
+/// With `--format github-actions` (appends to GITHUB_OUTPUT):
 /// ```text
+/// name=my-crate
 /// version=0.1.2
 /// ```
 pub fn current(args: CurrentArgs) -> Result<()> {
+    let format = OutputFormat::parse(&args.format, "version")?;
     let mut logger = cargo_plugin_utils::logger::Logger::new();
 
     logger.status("Reading", "package version");
+    let current = current_version(args.manifest_path.as_deref())?;
+    logger.finish();
+
+    format.write(&current, &args.output)
+}
+
+/// Name and version of a package.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CurrentVersion {
+    /// Package name.
+    pub name: String,
+    /// Package version from Cargo.toml.
+    pub version: String,
+}
+
+/// Read the current version of a package without printing it.
+///
+/// `manifest_path` defaults to the package in the current directory, with
+/// the same workspace handling as [`current`].
+///
+/// # Errors
+///
+/// Returns an error if the manifest cannot be read or does not describe a
+/// package.
+pub fn current_version(manifest_path: Option<&Path>) -> Result<CurrentVersion> {
     // Use find_package which automatically handles --manifest-path and workspace
     // logic
-    let package = find_package(args.manifest_path.as_deref())?;
-    let version = package.version.to_string();
-    logger.finish();
+    let package = find_package(manifest_path)?;
+    Ok(CurrentVersion {
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+    })
+}
 
-    match args.format.as_str() {
-        "version" => println!("{}", version),
-        "json" => println!("{{\"version\":\"{}\"}}", version),
-        "github-actions" => {
-            let output_file = args.github_output.as_deref().unwrap_or("/dev/stdout");
-            let output = format!("version={}\n", version);
-            std::fs::write(output_file, output)
-                .with_context(|| format!("Failed to write to {}", output_file))?;
-        }
-        _ => anyhow::bail!("Invalid format: {}", args.format),
+impl CommandOutput for CurrentVersion {
+    fn text(&self) -> String {
+        self.version.clone()
     }
-
-    Ok(())
 }
 
 #[cfg(test)]
@@ -176,7 +204,7 @@ version.workspace = true
         let args = CurrentArgs {
             manifest_path: Some(manifest_path),
             format: "version".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         assert!(current(args).is_ok());
     }
@@ -194,7 +222,7 @@ version = "1.2.3"
         let args = CurrentArgs {
             manifest_path: Some(manifest_path.clone()),
             format: "version".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         let result = current(args);
         if let Err(e) = &result {
@@ -217,7 +245,7 @@ version = "0.5.0"
         let args = CurrentArgs {
             manifest_path: Some(manifest_path),
             format: "json".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         assert!(current(args).is_ok());
     }
@@ -236,7 +264,10 @@ version = "2.0.0"
         let args = CurrentArgs {
             manifest_path: Some(manifest_path),
             format: "github-actions".to_string(),
-            github_output: Some(output_file.path().to_string_lossy().to_string()),
+            output: OutputArgs {
+                github_output: Some(output_file.path().to_string_lossy().to_string()),
+                ..Default::default()
+            },
         };
         assert!(current(args).is_ok());
 
@@ -257,7 +288,7 @@ version = "1.0.0"
         let args = CurrentArgs {
             manifest_path: Some(manifest_path),
             format: "invalid".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         assert!(current(args).is_err());
     }
@@ -267,7 +298,7 @@ version = "1.0.0"
         let args = CurrentArgs {
             manifest_path: Some("/nonexistent/Cargo.toml".into()),
             format: "version".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         assert!(current(args).is_err());
     }
@@ -286,7 +317,7 @@ name = "test"
         let args = CurrentArgs {
             manifest_path: Some(manifest_path),
             format: "version".to_string(),
-            github_output: None,
+            output: OutputArgs::default(),
         };
         // Cargo defaults to 0.0.0, so this should succeed
         let result = current(args);
@@ -295,4 +326,20 @@ name = "test"
         // (We can't easily capture stdout in this test, but the function should
         // complete)
     }
+
+    #[test]
+    fn test_current_version_typed() {
+        let dir = create_temp_cargo_project(
+            r#"
+[package]
+name = "test"
+version = "1.2.3"
+"#,
+        );
+
+        let current = current_version(Some(&dir.path().join("Cargo.toml"))).unwrap();
+
+        assert_eq!(current.name, "test");
+        assert_eq!(current.version, "1.2.3");
+    }
 }
@@ -1,7 +1,9 @@
 //! Generate dev version from git SHA command.
 //!
 //! This command generates a development version string from the current git
-//! commit SHA. The format is `0.0.0-dev-<short-sha>`.
+//! commit SHA. The format is `0.0.0-dev-<short-sha>`, or with `--describe` a
+//! `git describe`-style version such as `1.2.3-dev.14+g1a2b3c4` (see
+//! [`crate::describe`]).
 //!
 //! # Examples
 //!
@@ -14,6 +16,12 @@
 //!
 //! # Use a different repository path
 //! cargo version-info dev --repo-path /path/to/repo
+//!
+//! # Describe-style version (e.g., "1.2.3-dev.14+g1a2b3c4")
+//! cargo version-info dev --describe
+//!
+//! # Custom describe format
+//! cargo version-info dev --describe-format '{version}+{distance}.g{sha}{dirty}'
 //! ```
 
 use std::path::PathBuf;
@@ -24,6 +32,16 @@ use anyhow::{
 };
 use clap::Parser;
 
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+use crate::describe::{
+    self,
+    DEFAULT_DESCRIBE_FORMAT,
+};
+
 /// Arguments for the `dev` command.
 #[derive(Parser, Debug)]
 pub struct DevArgs {
@@ -34,12 +52,71 @@ pub struct DevArgs {
     #[arg(long, default_value = ".")]
     repo_path: PathBuf,
 
+    /// Compute a `git describe`-style version from the nearest semver tag.
+    ///
+    /// Produces e.g. `1.2.3-dev.14+g1a2b3c4`, with a `.dirty` suffix when
+    /// tracked files have uncommitted changes.
+    #[arg(long)]
+    describe: bool,
+
+    /// Format string for `--describe` (implies `--describe`).
+    ///
+    /// Placeholders: `{version}`, `{tag}`, `{distance}`, `{sha}`, `{dirty}`.
+    /// Defaults to `{version}-dev.{distance}+g{sha}{dirty}`.
+    #[arg(long, env = "VERSION_INFO_DESCRIBE_FORMAT")]
+    describe_format: Option<String>,
+
+    /// Count untracked files (not ignored by `.gitignore`) as dirty.
+    ///
+    /// Only used with `--describe`.
+    #[arg(long, env = "VERSION_INFO_INCLUDE_UNTRACKED")]
+    include_untracked: bool,
+
     /// Output format for the dev version.
     ///
     /// - `version`: Print just the dev version (e.g., "0.0.0-dev-a1b2c3d")
-    /// - `json`: Print JSON with version and sha fields
+    /// - `json`: Print JSON with version and sha fields (with `--describe`:
+    ///   also tag, distance and dirty)
+    /// - `github-actions`: Write the same fields to GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same fields as
+    ///   `KEY=value` lines
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "version")]
     format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Output of `dev`.
+#[derive(serde::Serialize)]
+struct DevOutput<'a> {
+    version: &'a str,
+    sha: &'a str,
+}
+
+impl CommandOutput for DevOutput<'_> {
+    fn text(&self) -> String {
+        self.version.to_string()
+    }
+}
+
+/// Output of `dev --describe`.
+#[derive(serde::Serialize)]
+struct DescribeOutput<'a> {
+    version: &'a str,
+    sha: &'a str,
+    tag: Option<&'a str>,
+    distance: usize,
+    dirty: bool,
+}
+
+impl CommandOutput for DescribeOutput<'_> {
+    fn text(&self) -> String {
+        self.version.to_string()
+    }
 }
 
 /// Generate a development version from the current git commit SHA.
@@ -82,7 +159,33 @@ pub struct DevArgs {
 /// ```json
 /// {"version":"0.0.0-dev-a1b2c3d","sha":"a1b2c3d"}
 /// ```
+///
+/// With `--describe --format json`:
+/// ```json
+/// {"version":"1.2.3-dev.14+g1a2b3c4","sha":"1a2b3c4","tag":"v1.2.3","distance":14,"dirty":false}
+/// ```
 pub fn dev(args: DevArgs) -> Result<()> {
+    let format = OutputFormat::parse(&args.format, "version")?;
+    let output_args = &args.output;
+
+    if args.describe || args.describe_format.is_some() {
+        let describe_format = args
+            .describe_format
+            .as_deref()
+            .unwrap_or(DEFAULT_DESCRIBE_FORMAT);
+        let describe = describe::describe(&args.repo_path, args.include_untracked)?;
+        let version = describe.format(describe_format);
+
+        let output = DescribeOutput {
+            version: &version,
+            sha: &describe.sha,
+            tag: describe.tag.as_deref(),
+            distance: describe.distance,
+            dirty: describe.dirty,
+        };
+        return format.write(&output, output_args);
+    }
+
     let repo = gix::discover(&args.repo_path).with_context(|| {
         format!(
             "Failed to discover git repository at {}",
@@ -96,18 +199,16 @@ pub fn dev(args: DevArgs) -> Result<()> {
         .shorten()
         .context("Failed to shorten commit SHA")?;
 
+    let short_sha = short_sha.to_string();
     let dev_version = format!("0.0.0-dev-{}", short_sha);
 
-    match args.format.as_str() {
-        "version" => println!("{}", dev_version),
-        "json" => println!(
-            "{{\"version\":\"{}\",\"sha\":\"{}\"}}",
-            dev_version, short_sha
-        ),
-        _ => anyhow::bail!("Invalid format: {}", args.format),
-    }
-
-    Ok(())
+    format.write(
+        &DevOutput {
+            version: &dev_version,
+            sha: &short_sha,
+        },
+        output_args,
+    )
 }
 
 #[cfg(test)]
@@ -119,7 +220,11 @@ mod tests {
         // Test with current directory (should work if run from git repo)
         let args = DevArgs {
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            include_untracked: false,
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         // This will only work if run from a git repository
         // We'll just verify it doesn't panic on invalid format
@@ -140,7 +245,11 @@ mod tests {
     fn test_dev_json_format() {
         let args = DevArgs {
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            include_untracked: false,
             format: "json".to_string(),
+            output: OutputArgs::default(),
         };
         // Same as above - will work if in git repo, otherwise fail gracefully
         let _ = dev(args);
@@ -150,7 +259,11 @@ mod tests {
     fn test_dev_invalid_format() {
         let args = DevArgs {
             repo_path: ".".into(),
+            describe: false,
+            describe_format: None,
+            include_untracked: false,
             format: "invalid".to_string(),
+            output: OutputArgs::default(),
         };
         // Should fail on invalid format even if repo is valid
         let result = dev(args);
@@ -175,7 +288,24 @@ mod tests {
     fn test_dev_nonexistent_repo() {
         let args = DevArgs {
             repo_path: "/nonexistent/path".into(),
+            describe: false,
+            describe_format: None,
+            include_untracked: false,
+            format: "version".to_string(),
+            output: OutputArgs::default(),
+        };
+        assert!(dev(args).is_err());
+    }
+
+    #[test]
+    fn test_dev_describe_nonexistent_repo() {
+        let args = DevArgs {
+            repo_path: "/nonexistent/path".into(),
+            describe: true,
+            describe_format: None,
+            include_untracked: false,
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(dev(args).is_err());
     }
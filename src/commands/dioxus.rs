@@ -25,6 +25,12 @@ use anyhow::{
 };
 use clap::Parser;
 
+use super::output::{
+    OutputArgs,
+    OutputFormat,
+    VersionOutput,
+};
+
 /// Arguments for the `dioxus` command.
 #[derive(Parser, Debug)]
 pub struct DioxusArgs {
@@ -38,8 +44,17 @@ pub struct DioxusArgs {
     ///
     /// - `version`: Print just the version number (e.g., "0.7.0")
     /// - `json`: Print JSON with version field
+    /// - `github-actions`: Write version to GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print a
+    ///   `VERSION_INFO_VERSION=...` line
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "version")]
     format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
 /// Get the Dioxus framework version from a Cargo.toml manifest file.
@@ -113,13 +128,8 @@ pub fn dioxus(args: DioxusArgs) -> Result<()> {
         })
         .with_context(|| format!("No dioxus version found in {}", args.manifest.display()))?;
 
-    match args.format.as_str() {
-        "version" => println!("{}", version),
-        "json" => println!("{{\"version\":\"{}\"}}", version),
-        _ => anyhow::bail!("Invalid format: {}", args.format),
-    }
-
-    Ok(())
+    OutputFormat::parse(&args.format, "version")?
+        .write(&VersionOutput { version: &version }, &args.output)
 }
 
 #[cfg(test)]
@@ -147,6 +157,7 @@ dioxus = { version = "0.7.0" }
         let args = DioxusArgs {
             manifest: manifest.path().to_path_buf(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(dioxus(args).is_ok());
     }
@@ -165,6 +176,7 @@ dioxus = { workspace = true }
         let args = DioxusArgs {
             manifest: manifest.path().to_path_buf(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(dioxus(args).is_ok());
     }
@@ -180,6 +192,7 @@ dioxus = { version = "0.9.0" }
         let args = DioxusArgs {
             manifest: manifest.path().to_path_buf(),
             format: "json".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(dioxus(args).is_ok());
     }
@@ -195,6 +208,7 @@ serde = { version = "1.0" }
         let args = DioxusArgs {
             manifest: manifest.path().to_path_buf(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(dioxus(args).is_err());
     }
@@ -204,6 +218,7 @@ serde = { version = "1.0" }
         let args = DioxusArgs {
             manifest: "/nonexistent/Cargo.toml".into(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(dioxus(args).is_err());
     }
@@ -219,6 +234,7 @@ dioxus = { version = "0.7.0" }
         let args = DioxusArgs {
             manifest: manifest.path().to_path_buf(),
             format: "invalid".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(dioxus(args).is_err());
     }
@@ -233,6 +249,7 @@ dioxus = { version = "0.7.0" }
         let args = DioxusArgs {
             manifest: manifest.path().to_path_buf(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(dioxus(args).is_err());
     }
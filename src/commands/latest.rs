@@ -1,7 +1,13 @@
-//! Get latest GitHub release version command.
+//! Get latest released version command.
 //!
 //! This command queries the GitHub Releases API to find the latest release
-//! version for a repository.
+//! version for a repository. By default the first release GitHub lists is
+//! used; drafts, pre-releases and tags outside a prefix can be filtered out,
+//! and `--highest` picks the highest semantic version across all releases.
+//!
+//! With `--source registry` it instead reports the latest non-yanked version
+//! published to crates.io, an alternative registry (`--registry`) or any
+//! sparse index (`--index`), listing yanked versions separately.
 //!
 //! # Examples
 //!
@@ -17,19 +23,45 @@
 //!
 //! # Specify repository explicitly
 //! cargo version-info latest --owner owner --repo repo
+//!
+//! # Highest stable release, ignoring drafts and pre-releases
+//! cargo version-info latest --exclude-drafts --exclude-prereleases --highest
+//!
+//! # Only consider releases of one crate in a monorepo
+//! cargo version-info latest --tag-prefix my-crate-v --highest
+//!
+//! # Query a GitHub Enterprise Server instance
+//! cargo version-info latest --github-api-url https://ghe.example.com/api/v3
+//!
+//! # Latest version published on crates.io
+//! cargo version-info latest --source registry
+//!
+//! # Latest version published to an alternative registry
+//! cargo version-info latest --source registry --registry my-registry
 //! ```
 
+use std::path::PathBuf;
+
 use anyhow::{
     Context,
     Result,
 };
-use cargo_plugin_utils::common::get_owner_repo;
+use cargo_plugin_utils::common::{
+    find_package,
+    get_owner_repo,
+};
 use clap::Parser;
 
-use crate::github;
-use crate::version::{
-    format_tag,
-    parse_version,
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+use crate::github::{
+    self,
+    ReleaseFilter,
+    SourceContext,
+    VersionSource,
 };
 
 /// Arguments for the `latest` command.
@@ -56,24 +88,147 @@ pub struct LatestArgs {
     #[arg(long, env = "GITHUB_TOKEN")]
     github_token: Option<String>,
 
+    /// GitHub REST API base URL.
+    ///
+    /// Defaults to the `GITHUB_API_URL` environment variable (set by GitHub
+    /// Actions) or `https://api.github.com`. Set this for GitHub Enterprise
+    /// Server, e.g. `https://ghe.example.com/api/v3`.
+    #[arg(long, env = "GITHUB_API_URL")]
+    github_api_url: Option<String>,
+
+    /// Where to read the latest released version from.
+    ///
+    /// - `releases` (default): Latest GitHub release
+    /// - `registry`: Latest non-yanked version in the crate registry
+    /// - `tags`: Highest semver git tag in the local repository
+    /// - `cargo-toml`: Current version in Cargo.toml
+    ///
+    /// The release filters below only apply to `releases`.
+    #[arg(long, value_enum, default_value_t = VersionSource::Releases)]
+    source: VersionSource,
+
+    /// Alternative registry declared in `.cargo/config.toml`.
+    ///
+    /// Only used with `--source registry`.
+    #[arg(long, conflicts_with = "index")]
+    registry: Option<String>,
+
+    /// Sparse registry index URL or local index directory.
+    ///
+    /// Only used with `--source registry`. Accepts `sparse+https://...`,
+    /// `https://...`, `file://...` or a plain path.
+    #[arg(long)]
+    index: Option<String>,
+
+    /// Path to the Cargo.toml manifest file (standard cargo flag).
+    ///
+    /// Only used with `--source cargo-toml` and `--source registry`.
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Skip draft releases.
+    #[arg(long)]
+    exclude_drafts: bool,
+
+    /// Skip releases marked as pre-release.
+    #[arg(long)]
+    exclude_prereleases: bool,
+
+    /// Only consider releases (or tags, with `--source tags`) whose tag
+    /// starts with this prefix.
+    ///
+    /// The prefix is stripped before the version is parsed, e.g. with
+    /// `--tag-prefix my-crate-v` the tag `my-crate-v1.2.3` yields `1.2.3`.
+    /// Sources without tags report `{prefix}{version}` as the tag.
+    #[arg(long)]
+    tag_prefix: Option<String>,
+
+    /// Pick the highest semantic version across all releases.
+    ///
+    /// Without this flag, the first matching release in GitHub's listing
+    /// order (most recently created) is used.
+    #[arg(long)]
+    highest: bool,
+
     /// Output format for the version.
     ///
     /// - `version`: Print just the version number (e.g., "0.1.2")
     /// - `tag`: Print the tag with 'v' prefix (e.g., "v0.1.2")
-    /// - `json`: Print JSON with version and tag fields
+    /// - `json`: Print JSON with version, tag, name, url, published_at and
+    ///   prerelease fields (with `--source registry`: version, tag, source,
+    ///   index and yanked; other sources: version, tag and source)
+    /// - `github-actions`: Write the same fields to GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same fields as
+    ///   `KEY=value` lines
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "version")]
     format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Output of the `latest` command.
+#[derive(serde::Serialize)]
+struct LatestOutput<'a> {
+    version: &'a str,
+    tag: String,
+    name: Option<&'a str>,
+    url: Option<&'a str>,
+    published_at: Option<&'a str>,
+    prerelease: bool,
 }
 
-/// Get the latest GitHub release version for a repository.
+/// Output of the `latest` command for `--source registry`.
+#[derive(serde::Serialize)]
+struct RegistryLatestOutput<'a> {
+    version: &'a str,
+    tag: String,
+    source: VersionSource,
+    index: String,
+    yanked: &'a [String],
+}
+
+/// Output of the `latest` command for `--source tags|cargo-toml`.
+#[derive(serde::Serialize)]
+struct SourceLatestOutput<'a> {
+    version: &'a str,
+    tag: String,
+    source: VersionSource,
+}
+
+impl CommandOutput for LatestOutput<'_> {
+    fn text(&self) -> String {
+        self.version.to_string()
+    }
+}
+
+impl CommandOutput for RegistryLatestOutput<'_> {
+    fn text(&self) -> String {
+        self.version.to_string()
+    }
+}
+
+impl CommandOutput for SourceLatestOutput<'_> {
+    fn text(&self) -> String {
+        self.version.to_string()
+    }
+}
+
+/// Get the latest released version for a repository or crate.
 ///
-/// Queries the GitHub Releases API to find the most recent release version.
-/// Returns "0.0.0" if no releases exist.
+/// By default queries the GitHub Releases API to find the most recent release
+/// version, applying the draft, pre-release, tag prefix and highest-semver
+/// options. Other sources are selected with `--source`. Returns "0.0.0" if no
+/// (matching) version exists.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The GitHub repository cannot be detected or accessed
+/// - The registry index cannot be resolved or queried
 /// - The API request fails (network error, authentication failure, etc.)
 /// - The release version cannot be parsed
 ///
@@ -115,35 +270,139 @@ pub struct LatestArgs {
 ///
 /// With `--format json`:
 /// ```json
-/// {"version":"0.1.2","tag":"v0.1.2"}
+/// {"version":"0.1.2","tag":"v0.1.2","name":"Release 0.1.2","url":"https://github.com/owner/repo/releases/tag/v0.1.2","published_at":"2025-01-01T00:00:00+00:00","prerelease":false}
+/// ```
+///
+/// With `--source registry --format json`:
+/// ```json
+/// {"version":"0.1.2","tag":"v0.1.2","source":"registry","index":"https://index.crates.io","yanked":["0.1.3"]}
 /// ```
 pub fn latest(args: LatestArgs) -> Result<()> {
+    match args.source {
+        VersionSource::Releases => latest_release(args),
+        VersionSource::Registry => latest_registry(args),
+        VersionSource::Tags | VersionSource::CargoToml => latest_from_source(args),
+    }
+}
+
+/// Print the latest GitHub release version.
+fn latest_release(args: LatestArgs) -> Result<()> {
     let (owner, repo) = get_owner_repo(args.owner, args.repo)?;
     let github_token = args.github_token.as_deref();
 
+    let filter = ReleaseFilter {
+        exclude_drafts: args.exclude_drafts,
+        exclude_prereleases: args.exclude_prereleases,
+        tag_prefix: args.tag_prefix.clone(),
+        highest_semver: args.highest,
+    };
+
     let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-    let latest = rt.block_on(github::get_latest_release_version(
+    let release = rt.block_on(github::get_latest_release(
         &owner,
         &repo,
         github_token,
+        args.github_api_url.as_deref(),
+        &filter,
     ))?;
 
-    let latest = latest.unwrap_or_else(|| "0.0.0".to_string());
+    let latest = release
+        .as_ref()
+        .map(|release| release.version.clone())
+        .unwrap_or_else(|| "0.0.0".to_string());
+
+    let tag = match &release {
+        Some(release) => release.tag_name.clone(),
+        None => tag_of(&latest, args.tag_prefix.as_deref()),
+    };
+
+    let output = LatestOutput {
+        version: &latest,
+        tag,
+        name: release.as_ref().and_then(|release| release.name.as_deref()),
+        url: release.as_ref().map(|release| release.url.as_str()),
+        published_at: release
+            .as_ref()
+            .and_then(|release| release.published_at.as_deref()),
+        prerelease: release.as_ref().is_some_and(|release| release.prerelease),
+    };
+    write_latest(&args.format, &output, &output.tag, &args.output)
+}
+
+/// Print the latest non-yanked version published to the crate registry.
+fn latest_registry(args: LatestArgs) -> Result<()> {
+    let package = find_package(args.manifest_path.as_deref())?;
+    let manifest_dir = package
+        .manifest_path
+        .parent()
+        .map(|dir| dir.as_std_path().to_path_buf())
+        .unwrap_or_default();
+    let context = SourceContext {
+        registry: args.registry,
+        index: args.index,
+        ..Default::default()
+    };
+    let index = context.registry_index(&manifest_dir)?;
+
+    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+    let versions = rt.block_on(index.lookup(&package.name))?;
+    let latest = versions.latest().unwrap_or("0.0.0");
+
+    let output = RegistryLatestOutput {
+        version: latest,
+        tag: tag_of(latest, args.tag_prefix.as_deref()),
+        source: VersionSource::Registry,
+        index: index.to_string(),
+        yanked: &versions.yanked,
+    };
+    write_latest(&args.format, &output, &output.tag, &args.output)
+}
 
-    match args.format.as_str() {
-        "version" => println!("{}", latest),
+/// Print the latest version from a local source (git tags or Cargo.toml).
+fn latest_from_source(args: LatestArgs) -> Result<()> {
+    let context = SourceContext {
+        manifest_path: args.manifest_path,
+        ..Default::default()
+    };
+
+    let tag_prefix = args.tag_prefix.as_deref();
+    let (tag, latest) = if args.source == VersionSource::Tags {
+        github::get_latest_git_tag(tag_prefix)?
+            .unwrap_or_else(|| (tag_of("0.0.0", tag_prefix), "0.0.0".to_string()))
+    } else {
+        let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+        let latest = rt
+            .block_on(github::get_latest_version(args.source, &context))?
+            .unwrap_or_else(|| "0.0.0".to_string());
+        (tag_of(&latest, tag_prefix), latest)
+    };
+
+    let output = SourceLatestOutput {
+        version: &latest,
+        tag,
+        source: args.source,
+    };
+    write_latest(&args.format, &output, &output.tag, &args.output)
+}
+
+/// Tag for `version` when the source has no tag name of its own (e.g.
+/// "v0.1.2", or "my-crate-v0.1.2" with a `my-crate-v` prefix).
+fn tag_of(version: &str, tag_prefix: Option<&str>) -> String {
+    format!("{}{}", tag_prefix.unwrap_or("v"), version)
+}
+
+/// Print a `latest` result, handling the `tag` format shared by all sources.
+fn write_latest<T: CommandOutput>(
+    format: &str,
+    output: &T,
+    tag: &str,
+    output_args: &OutputArgs,
+) -> Result<()> {
+    match format {
         "tag" => {
-            let (major, minor, patch) = parse_version(&latest)?;
-            println!("{}", format_tag(major, minor, patch));
+            println!("{}", tag);
+            Ok(())
         }
-        "json" => {
-            println!("{{\"version\":\"{}\",\"tag\":\"{}\"}}", latest, {
-                let (major, minor, patch) = parse_version(&latest)?;
-                format_tag(major, minor, patch)
-            });
-        }
-        _ => anyhow::bail!("Invalid format: {}", args.format),
+        format => OutputFormat::parse(format, "version")?.write(output, output_args),
     }
-
-    Ok(())
 }
@@ -1,95 +1,193 @@
 //! Command implementations.
+//!
+//! The build version computation
+//! ([`compute_version_string`](crate::commands::compute_version_string) and
+//! friends) is always available; the commands themselves require the `cli`
+//! feature.
 
+#[cfg(feature = "badges")]
 mod badge;
 mod build_version;
+#[cfg(feature = "cli")]
 pub mod bump;
+#[cfg(feature = "cli")]
 mod changed;
+#[cfg(feature = "cli")]
 pub mod changelog;
+#[cfg(feature = "cli")]
+mod check_docs;
+#[cfg(feature = "cli")]
+mod check_publish;
+#[cfg(feature = "cli")]
 mod compare;
+#[cfg(feature = "cli")]
 mod current;
+#[cfg(feature = "cli")]
 mod dev;
+#[cfg(feature = "cli")]
 mod dioxus;
+#[cfg(feature = "cli")]
 mod latest;
+#[cfg(feature = "cli")]
 mod next;
+#[cfg(feature = "cli")]
+mod output;
+#[cfg(feature = "cli")]
 mod post_bump_hook;
+#[cfg(feature = "cli")]
 mod pr_log;
+#[cfg(feature = "cli")]
 mod pre_bump_hook;
+#[cfg(feature = "cli")]
+mod publish_plan;
+#[cfg(feature = "badges")]
 mod release_page;
+#[cfg(feature = "cli")]
 mod rust_toolchain;
+#[cfg(feature = "cli")]
 mod tag;
+#[cfg(feature = "cli")]
+mod unbump;
+#[cfg(feature = "badges")]
 mod update_readme;
+#[cfg(feature = "cli")]
+mod workflow;
 
 // Re-export all command argument structs
+#[cfg(feature = "badges")]
 pub use badge::{
     BadgeArgs,
     badge,
 };
+pub use build_version::{
+    BuildVersion,
+    BuildVersionOptions,
+    BuildVersionSource,
+    DIRTY_SUFFIX_ENV,
+    compute_build_version,
+    compute_dirty,
+    compute_version_string,
+};
+#[cfg(feature = "cli")]
 pub use build_version::{
     BuildVersionArgs,
     build_version,
     build_version_default,
     build_version_for_repo,
-    compute_version_string,
 };
+#[cfg(feature = "cli")]
 pub use bump::{
     BumpArgs,
     bump,
 };
+#[cfg(feature = "cli")]
 pub use changed::{
+    ChangedAgainst,
     ChangedArgs,
+    ChangedOptions,
+    ChangedStatus,
     changed,
+    changed_status,
 };
+#[cfg(feature = "cli")]
 pub use changelog::{
     ChangelogArgs,
     changelog,
 };
+#[cfg(feature = "cli")]
+pub use check_docs::{
+    CheckDocsArgs,
+    check_docs,
+};
+#[cfg(feature = "cli")]
+pub use check_publish::{
+    CheckPublishArgs,
+    PublishCheck,
+    check_publish,
+};
+#[cfg(feature = "cli")]
 pub use compare::{
     CompareArgs,
     compare,
 };
+#[cfg(feature = "cli")]
 pub use current::{
     CurrentArgs,
+    CurrentVersion,
     current,
+    current_version,
 };
+#[cfg(feature = "cli")]
 pub use dev::{
     DevArgs,
     dev,
 };
+#[cfg(feature = "cli")]
 pub use dioxus::{
     DioxusArgs,
     dioxus,
 };
+#[cfg(feature = "cli")]
 pub use latest::{
     LatestArgs,
     latest,
 };
-pub use next::NextArgs;
-// Re-export all command functions
-pub use next::next;
+#[cfg(feature = "cli")]
+pub use next::{
+    NextArgs,
+    NextVersion,
+    next,
+    next_version,
+};
+#[cfg(feature = "cli")]
+pub use output::{
+    CiProvider,
+    CommandOutput,
+    DEFAULT_ENV_PREFIX,
+    OutputArgs,
+    OutputFormat,
+};
+#[cfg(feature = "cli")]
 pub use post_bump_hook::{
     PostBumpHookArgs,
     post_bump_hook,
 };
+#[cfg(feature = "cli")]
 pub use pr_log::{
     PrLogArgs,
     pr_log,
 };
+#[cfg(feature = "cli")]
 pub use pre_bump_hook::{
     PreBumpHookArgs,
     pre_bump_hook,
 };
+#[cfg(feature = "cli")]
+pub use publish_plan::{
+    PublishPlanArgs,
+    publish_plan,
+};
+#[cfg(feature = "badges")]
 pub use release_page::{
     ReleasePageArgs,
     release_page,
 };
+#[cfg(feature = "cli")]
 pub use rust_toolchain::{
     RustToolchainArgs,
     rust_toolchain,
 };
+#[cfg(feature = "cli")]
 pub use tag::{
     TagArgs,
     tag,
 };
+#[cfg(feature = "cli")]
+pub use unbump::{
+    UnbumpArgs,
+    unbump,
+};
+#[cfg(feature = "badges")]
 pub use update_readme::{
     UpdateReadmeArgs,
     update_readme,
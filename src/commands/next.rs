@@ -1,7 +1,16 @@
 //! Calculate next patch version command.
 //!
-//! This command queries the GitHub API to find the latest release version and
-//! calculates the next patch version by incrementing the patch number.
+//! This command finds the latest released version from a configurable source
+//! (`--source`) and calculates the next patch version by incrementing the
+//! patch number.
+//!
+//! # Version Sources
+//!
+//! - `tags` (default): Highest semver git tag in the local repository
+//! - `releases`: Latest GitHub release (works in shallow clones without tags)
+//! - `cargo-toml`: Current version in Cargo.toml
+//! - `registry`: Latest non-yanked version published on crates.io (or the
+//!   registry selected with `--registry`/`--index`)
 //!
 //! # Examples
 //!
@@ -9,6 +18,9 @@
 //! # Get next version (e.g., "0.1.3")
 //! cargo version-info next
 //!
+//! # Compute from GitHub releases instead of local tags
+//! cargo version-info next --source releases
+//!
 //! # Get next tag (e.g., "v0.1.3")
 //! cargo version-info next --format tag
 //!
@@ -19,14 +31,25 @@
 //! cargo version-info next --format github-actions
 //! ```
 
+use std::path::PathBuf;
+
 use anyhow::{
     Context,
     Result,
 };
-use cargo_plugin_utils::common::get_owner_repo;
 use clap::Parser;
 
-use crate::github;
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+use super::workflow;
+use crate::github::{
+    self,
+    SourceContext,
+    VersionSource,
+};
 use crate::version::{
     format_tag,
     parse_version,
@@ -56,34 +79,75 @@ pub struct NextArgs {
     #[arg(long, env = "GITHUB_TOKEN")]
     github_token: Option<String>,
 
+    /// GitHub REST API base URL.
+    ///
+    /// Defaults to the `GITHUB_API_URL` environment variable (set by GitHub
+    /// Actions) or `https://api.github.com`. Set this for GitHub Enterprise
+    /// Server, e.g. `https://ghe.example.com/api/v3`.
+    #[arg(long, env = "GITHUB_API_URL")]
+    github_api_url: Option<String>,
+
+    /// Where to read the latest released version from.
+    ///
+    /// - `tags` (default): Highest semver git tag in the local repository
+    /// - `releases`: Latest GitHub release (uses `--owner`, `--repo`,
+    ///   `--github-token` and `--github-api-url`)
+    /// - `cargo-toml`: Current version in Cargo.toml
+    /// - `registry`: Latest non-yanked version published on crates.io (or
+    ///   `--registry`/`--index`)
+    #[arg(long, value_enum, default_value_t = VersionSource::Tags)]
+    source: VersionSource,
+
+    /// Alternative registry declared in `.cargo/config.toml`.
+    ///
+    /// Only used with `--source registry`.
+    #[arg(long, conflicts_with = "index")]
+    registry: Option<String>,
+
+    /// Sparse registry index URL or local index directory.
+    ///
+    /// Only used with `--source registry`.
+    #[arg(long)]
+    index: Option<String>,
+
+    /// Path to the Cargo.toml manifest file (standard cargo flag).
+    ///
+    /// Only used with `--source cargo-toml` and `--source registry`.
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+
     /// Output format for the version information.
     ///
     /// - `version`: Print just the next version number (e.g., "0.1.3")
     /// - `tag`: Print the next tag with 'v' prefix (e.g., "v0.1.3")
-    /// - `json`: Print JSON with latest, next, and next_tag fields
-    /// - `github-actions`: Write to GITHUB_OUTPUT file in GitHub Actions format
+    /// - `json`: Print JSON with latest, next, next_tag, and source fields
+    /// - `github-actions`: Write latest_version, next_version, next_tag and
+    ///   source to GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same values as
+    ///   `KEY=value` lines (e.g. `VERSION_INFO_NEXT_TAG=v0.1.3`)
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "version")]
     format: String,
 
-    /// Path to GitHub Actions output file.
-    ///
-    /// Only used when `--format github-actions` is specified.
-    /// Defaults to the `GITHUB_OUTPUT` environment variable or stdout.
-    #[arg(long, env = "GITHUB_OUTPUT")]
-    github_output: Option<String>,
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
-/// Calculate the next patch version from the latest GitHub release.
+/// Calculate the next patch version from the latest released version.
 ///
-/// Queries the GitHub Releases API to find the latest release version,
-/// then increments the patch number. If no releases exist, returns "0.0.1".
+/// Reads the latest version from the configured [`VersionSource`] (local git
+/// tags by default), then increments the patch number. If the source has no
+/// version yet, returns "0.0.1".
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The GitHub repository cannot be detected or accessed
+/// - The git repository, GitHub repository, manifest or registry cannot be
+///   accessed (depending on the source)
 /// - The API request fails (network error, authentication failure, etc.)
-/// - The latest release version cannot be parsed
+/// - The latest version cannot be parsed
 ///
 /// # Examples
 ///
@@ -123,48 +187,96 @@ pub struct NextArgs {
 ///
 /// With `--format json`:
 /// ```json
-/// {"latest":"0.1.2","next":"0.1.3","next_tag":"v0.1.3"}
+/// {"latest":"0.1.2","next":"0.1.3","next_tag":"v0.1.3","source":"tags"}
 /// ```
 ///
-/// With `--format github-actions` (writes to GITHUB_OUTPUT):
+/// With `--format github-actions` (appends to GITHUB_OUTPUT):
 /// ```text
 /// latest_version=0.1.2
 /// next_version=0.1.3
 /// next_tag=v0.1.3
+/// source=tags
 /// ```
 pub fn next(args: NextArgs) -> Result<()> {
-    let (owner, repo) = get_owner_repo(args.owner, args.repo)?;
-    let github_token = args.github_token.as_deref();
+    let context = SourceContext {
+        owner: args.owner,
+        repo: args.repo,
+        github_token: args.github_token,
+        github_api_url: args.github_api_url,
+        manifest_path: args.manifest_path,
+        registry: args.registry,
+        index: args.index,
+    };
+    let next = next_version(args.source, &context)?;
+    workflow::append_step_summary(&workflow::summary_table(
+        "Next version",
+        &[
+            ("Latest", format!("`{}` ({})", next.latest, next.source)),
+            ("Next", format!("`{}`", next.next)),
+            ("Tag", format!("`{}`", next.next_tag)),
+        ],
+    ))?;
+
+    match args.format.as_str() {
+        "tag" => println!("{}", next.next_tag),
+        format => OutputFormat::parse(format, "version")?.write(&next, &args.output)?,
+    }
+
+    Ok(())
+}
+
+/// Latest released version and the next patch version.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct NextVersion {
+    /// Latest released version ("0.0.0" if there is none).
+    pub latest: String,
+    /// Next patch version.
+    pub next: String,
+    /// Tag for the next version (e.g. "v0.1.3").
+    pub next_tag: String,
+    /// Where the latest version was read from.
+    pub source: VersionSource,
+}
 
+/// Calculate the next patch version without printing it.
+///
+/// Blocks on its own tokio runtime; async callers can use
+/// [`github::calculate_next_version`] instead.
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be read or the latest version cannot
+/// be parsed (see [`next`]).
+pub fn next_version(source: VersionSource, context: &SourceContext) -> Result<NextVersion> {
     let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-    let (latest, next) =
-        rt.block_on(github::calculate_next_version(&owner, &repo, github_token))?;
+    let (latest, next) = rt.block_on(github::calculate_next_version(source, context))?;
 
     let next_tag = {
         let (major, minor, patch) = parse_version(&next)?;
         format_tag(major, minor, patch)
     };
 
-    match args.format.as_str() {
-        "version" => println!("{}", next),
-        "tag" => println!("{}", next_tag),
-        "json" => {
-            println!(
-                "{{\"latest\":\"{}\",\"next\":\"{}\",\"next_tag\":\"{}\"}}",
-                latest, next, next_tag
-            );
-        }
-        "github-actions" => {
-            let output_file = args.github_output.as_deref().unwrap_or("/dev/stdout");
-            let output = format!(
-                "latest_version={}\nnext_version={}\nnext_tag={}\n",
-                latest, next, next_tag
-            );
-            std::fs::write(output_file, output)
-                .with_context(|| format!("Failed to write to {}", output_file))?;
-        }
-        _ => anyhow::bail!("Invalid format: {}", args.format),
+    Ok(NextVersion {
+        latest,
+        next,
+        next_tag,
+        source,
+    })
+}
+
+impl CommandOutput for NextVersion {
+    fn text(&self) -> String {
+        self.next.clone()
     }
 
-    Ok(())
+    /// Uses `latest_version` and `next_version` rather than the JSON field
+    /// names, as the `github-actions` output always has.
+    fn variables(&self) -> Result<Vec<(String, String)>> {
+        Ok(vec![
+            ("latest_version".to_string(), self.latest.clone()),
+            ("next_version".to_string(), self.next.clone()),
+            ("next_tag".to_string(), self.next_tag.clone()),
+            ("source".to_string(), self.source.to_string()),
+        ])
+    }
 }
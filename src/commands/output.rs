@@ -0,0 +1,443 @@
+//! Output formats shared by all commands.
+//!
+//! Each command computes a serializable result and hands it to
+//! [`OutputFormat::write`]. JSON is produced by serde, and the `key=value`
+//! formats are derived from the top-level fields of the same result, so every
+//! command supports the same set of formats:
+//!
+//! | Format           | Output                                          |
+//! | ---------------- | ----------------------------------------------- |
+//! | text             | The command's plain text output (e.g. `version`) |
+//! | `json`           | One line of JSON                                |
+//! | `github-actions` | `key=value` lines appended to `GITHUB_OUTPUT`   |
+//! | `dotenv`/`env`   | `KEY=value` lines, double-quoted where needed   |
+//! | `shell-export`   | `export KEY='value'` lines (alias `shell`)      |
+//! | `gitlab-dotenv`  | `KEY=value` lines for a GitLab dotenv report    |
+//! | `ci`             | `github-actions` or `gitlab-dotenv`, detected   |
+//!
+//! The name of the text format depends on the command (`version`, `tag`,
+//! `bool` or `text`); commands with additional text formats such as `diff`
+//! handle those before falling back to [`OutputFormat::parse`].
+//!
+//! Variable names in the dotenv, shell and GitLab formats are the uppercased
+//! field names with a prefix ([`DEFAULT_ENV_PREFIX`] unless overridden with
+//! `--env-prefix`), e.g. `VERSION_INFO_NEXT_TAG`. The `github-actions` format
+//! keeps the bare lowercase names, as step outputs are already scoped.
+
+use std::io::Write;
+
+use anyhow::{
+    Context,
+    Result,
+};
+
+/// Default prefix of variable names in the dotenv-style formats.
+pub const DEFAULT_ENV_PREFIX: &str = "VERSION_INFO_";
+
+/// Where and how the `key=value` formats are written.
+///
+/// Shared by all commands via `#[command(flatten)]`.
+#[derive(clap::Args, Debug, Clone)]
+pub struct OutputArgs {
+    /// Path to GitHub Actions output file.
+    ///
+    /// Only used when `--format github-actions` is specified.
+    /// Defaults to the `GITHUB_OUTPUT` environment variable or stdout.
+    #[arg(long, env = "GITHUB_OUTPUT")]
+    pub github_output: Option<String>,
+
+    /// File to append `dotenv` and `gitlab-dotenv` output to.
+    ///
+    /// Use the file listed under `artifacts:reports:dotenv` in GitLab CI.
+    /// Defaults to stdout.
+    #[arg(long, env = "VERSION_INFO_DOTENV_FILE")]
+    pub dotenv_file: Option<String>,
+
+    /// Prefix of variable names in the `dotenv`, `shell-export` and
+    /// `gitlab-dotenv` formats (e.g. `VERSION_INFO_NEXT_TAG`).
+    #[arg(long, env = "VERSION_INFO_ENV_PREFIX", default_value = DEFAULT_ENV_PREFIX)]
+    pub env_prefix: String,
+}
+
+impl Default for OutputArgs {
+    fn default() -> Self {
+        Self {
+            github_output: None,
+            dotenv_file: None,
+            env_prefix: DEFAULT_ENV_PREFIX.to_string(),
+        }
+    }
+}
+
+/// CI system the command runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiProvider {
+    /// GitHub Actions (`GITHUB_ACTIONS` is set).
+    GithubActions,
+    /// GitLab CI (`GITLAB_CI` is set).
+    GitlabCi,
+}
+
+impl CiProvider {
+    /// Detect the CI system from its environment variables.
+    #[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+    pub fn detect() -> Option<Self> {
+        if std::env::var("GITHUB_ACTIONS").is_ok() {
+            Some(Self::GithubActions)
+        } else if std::env::var("GITLAB_CI").is_ok() {
+            Some(Self::GitlabCi)
+        } else {
+            None
+        }
+    }
+}
+
+/// Output format selected with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The command's plain text output ([`CommandOutput::text`]).
+    Text,
+    /// Serialized result as JSON.
+    Json,
+    /// `key=value` lines for `$GITHUB_OUTPUT`.
+    GithubActions,
+    /// `KEY=value` lines in dotenv syntax.
+    Dotenv,
+    /// `export KEY='value'` lines for POSIX shells.
+    ShellExport,
+    /// `KEY=value` lines for a GitLab CI dotenv report artifact.
+    GitlabDotenv,
+}
+
+impl OutputFormat {
+    /// Parse a `--format` value.
+    ///
+    /// `text` is the command's name for its plain text format, e.g. `version`
+    /// for `current` or `bool` for `compare`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value names no known format.
+    pub fn parse(value: &str, text: &str) -> Result<Self> {
+        Ok(match value {
+            value if value == text => Self::Text,
+            "json" => Self::Json,
+            "github-actions" => Self::GithubActions,
+            "dotenv" | "env" => Self::Dotenv,
+            "shell-export" | "shell" => Self::ShellExport,
+            "gitlab-dotenv" => Self::GitlabDotenv,
+            "ci" => match CiProvider::detect() {
+                Some(CiProvider::GithubActions) => Self::GithubActions,
+                Some(CiProvider::GitlabCi) => Self::GitlabDotenv,
+                None => Self::Dotenv,
+            },
+            _ => anyhow::bail!("Invalid format: {}", value),
+        })
+    }
+
+    /// Print `output` in this format.
+    ///
+    /// The `github-actions` format is appended to
+    /// [`OutputArgs::github_output`] and the `dotenv` and `gitlab-dotenv`
+    /// formats to [`OutputArgs::dotenv_file`]; without a file, and for all
+    /// other formats, the output is printed to stdout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the result cannot be serialized or written.
+    pub fn write<T: CommandOutput>(self, output: &T, args: &OutputArgs) -> Result<()> {
+        let rendered = self.render(output, &args.env_prefix)?;
+        let file = match self {
+            Self::GithubActions => args.github_output.as_deref(),
+            Self::Dotenv | Self::GitlabDotenv => args.dotenv_file.as_deref(),
+            Self::Text | Self::Json | Self::ShellExport => None,
+        };
+        match file {
+            Some(path) => {
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("Failed to open {}", path))?;
+                file.write_all(rendered.as_bytes())
+                    .with_context(|| format!("Failed to write to {}", path))?;
+            }
+            None => print!("{}", rendered),
+        }
+        Ok(())
+    }
+
+    /// Render `output` in this format, including the trailing newline.
+    ///
+    /// `env_prefix` is prepended to the variable names of the dotenv-style
+    /// formats.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the result cannot be serialized, or if a value
+    /// cannot be represented in the format (multi-line values in
+    /// `gitlab-dotenv`).
+    pub fn render<T: CommandOutput>(self, output: &T, env_prefix: &str) -> Result<String> {
+        if self == Self::Text {
+            let text = output.text();
+            return Ok(if text.is_empty() || text.ends_with('\n') {
+                text
+            } else {
+                format!("{}\n", text)
+            });
+        }
+        if self == Self::Json {
+            return Ok(format!("{}\n", serde_json::to_string(output)?));
+        }
+
+        let mut rendered = String::new();
+        for (key, value) in output.variables()? {
+            let env_key = format!("{}{}", env_prefix, env_key(&key));
+            let line = match self {
+                Self::GithubActions => github_actions_line(&key, &value),
+                Self::Dotenv => format!("{}={}\n", env_key, dotenv_quote(&value)),
+                Self::ShellExport => format!("export {}={}\n", env_key, shell_quote(&value)),
+                Self::GitlabDotenv => {
+                    if value.contains('\n') {
+                        anyhow::bail!(
+                            "{} contains a newline, which GitLab dotenv reports do not support",
+                            key
+                        );
+                    }
+                    format!("{}={}\n", env_key, value)
+                }
+                Self::Text | Self::Json => unreachable!("handled above"),
+            };
+            rendered.push_str(&line);
+        }
+        Ok(rendered)
+    }
+}
+
+/// Serializable result of a command.
+pub trait CommandOutput: serde::Serialize {
+    /// Plain text output, printed by the command's default format.
+    fn text(&self) -> String;
+
+    /// Named values for the `key=value` formats, in output order.
+    ///
+    /// Defaults to the top-level fields of the serialized result, skipping
+    /// `null` fields and encoding arrays and objects as JSON. Names are
+    /// lowercase; the dotenv-style formats uppercase them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the result does not serialize to a JSON object.
+    fn variables(&self) -> Result<Vec<(String, String)>> {
+        let serde_json::Value::Object(fields) = serde_json::to_value(self)? else {
+            anyhow::bail!("Output does not serialize to an object");
+        };
+        Ok(fields
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::Null => return None,
+                    serde_json::Value::String(value) => value,
+                    value => value.to_string(),
+                };
+                Some((key, value))
+            })
+            .collect())
+    }
+}
+
+/// Output of commands that only report a version.
+#[derive(serde::Serialize)]
+pub(super) struct VersionOutput<'a> {
+    pub(super) version: &'a str,
+}
+
+impl CommandOutput for VersionOutput<'_> {
+    fn text(&self) -> String {
+        self.version.to_string()
+    }
+}
+
+/// Variable name for the dotenv-style formats (`next_tag` → `NEXT_TAG`).
+fn env_key(key: &str) -> String {
+    key.to_ascii_uppercase().replace('-', "_")
+}
+
+/// Whether `value` can be written without quotes.
+fn is_bare(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "._+-/:@,".contains(ch))
+}
+
+/// Quote `value` for dotenv files.
+fn dotenv_quote(value: &str) -> String {
+    if is_bare(value) {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+/// Quote `value` for POSIX shells.
+fn shell_quote(value: &str) -> String {
+    if is_bare(value) {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// One `$GITHUB_OUTPUT` entry, using the heredoc syntax for multi-line values.
+fn github_actions_line(key: &str, value: &str) -> String {
+    if value.contains('\n') {
+        let delimiter = format!("VERSION_INFO_EOF_{}", key.to_ascii_uppercase());
+        format!("{}<<{}\n{}\n{}\n", key, delimiter, value, delimiter)
+    } else {
+        format!("{}={}\n", key, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Sample {
+        version: String,
+        next_tag: String,
+        dirty: bool,
+        sha: Option<String>,
+        yanked: Vec<String>,
+    }
+
+    impl CommandOutput for Sample {
+        fn text(&self) -> String {
+            self.version.clone()
+        }
+    }
+
+    fn sample() -> Sample {
+        Sample {
+            version: "1.2.3".to_string(),
+            next_tag: "it's \"v1.2.3\"".to_string(),
+            dirty: false,
+            sha: None,
+            yanked: vec!["1.0.0".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        assert_eq!(
+            OutputFormat::parse("bool", "bool").unwrap(),
+            OutputFormat::Text
+        );
+        assert_eq!(
+            OutputFormat::parse("env", "version").unwrap(),
+            OutputFormat::Dotenv
+        );
+        assert_eq!(
+            OutputFormat::parse("shell", "version").unwrap(),
+            OutputFormat::ShellExport
+        );
+        assert!(OutputFormat::parse("bool", "version").is_err());
+        assert!(OutputFormat::parse("yaml", "version").is_err());
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let output = sample();
+
+        assert_eq!(OutputFormat::Text.render(&output, "").unwrap(), "1.2.3\n");
+        assert_eq!(
+            OutputFormat::Json.render(&output, "").unwrap(),
+            "{\"version\":\"1.2.3\",\"next_tag\":\"it's \\\"v1.2.3\\\"\",\"dirty\":false,\"sha\":null,\"yanked\":[\"1.0.0\"]}\n"
+        );
+        assert_eq!(
+            OutputFormat::GithubActions.render(&output, "").unwrap(),
+            "version=1.2.3\nnext_tag=it's \"v1.2.3\"\ndirty=false\nyanked=[\"1.0.0\"]\n"
+        );
+        assert_eq!(
+            OutputFormat::Dotenv.render(&output, "").unwrap(),
+            "VERSION=1.2.3\nNEXT_TAG=\"it's \\\"v1.2.3\\\"\"\nDIRTY=false\nYANKED=\"[\\\"1.0.0\\\"]\"\n"
+        );
+        assert_eq!(
+            OutputFormat::ShellExport.render(&output, "").unwrap(),
+            "export VERSION=1.2.3\nexport NEXT_TAG='it'\\''s \"v1.2.3\"'\nexport DIRTY=false\nexport YANKED='[\"1.0.0\"]'\n"
+        );
+        assert_eq!(
+            OutputFormat::GitlabDotenv.render(&output, "").unwrap(),
+            "VERSION=1.2.3\nNEXT_TAG=it's \"v1.2.3\"\nDIRTY=false\nYANKED=[\"1.0.0\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_render_env_prefix() {
+        assert_eq!(
+            OutputFormat::GitlabDotenv
+                .render(&sample(), DEFAULT_ENV_PREFIX)
+                .unwrap(),
+            "VERSION_INFO_VERSION=1.2.3\nVERSION_INFO_NEXT_TAG=it's \"v1.2.3\"\nVERSION_INFO_DIRTY=false\nVERSION_INFO_YANKED=[\"1.0.0\"]\n"
+        );
+        assert!(
+            OutputFormat::GithubActions
+                .render(&sample(), DEFAULT_ENV_PREFIX)
+                .unwrap()
+                .starts_with("version=1.2.3\n")
+        );
+    }
+
+    #[test]
+    fn test_multiline_values() {
+        let output = Sample {
+            version: "1.2.3\nmore".to_string(),
+            ..sample()
+        };
+
+        assert!(
+            OutputFormat::GithubActions
+                .render(&output, "")
+                .unwrap()
+                .starts_with(
+                    "version<<VERSION_INFO_EOF_VERSION\n1.2.3\nmore\nVERSION_INFO_EOF_VERSION\n"
+                )
+        );
+        assert!(OutputFormat::GitlabDotenv.render(&output, "").is_err());
+    }
+
+    #[test]
+    fn test_github_actions_appends_to_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "existing=1\n").unwrap();
+        let path = file.path().to_string_lossy().to_string();
+
+        let args = OutputArgs {
+            github_output: Some(path),
+            ..Default::default()
+        };
+
+        OutputFormat::GithubActions.write(&sample(), &args).unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert!(content.starts_with("existing=1\nversion=1.2.3\n"));
+    }
+
+    #[test]
+    fn test_gitlab_dotenv_writes_to_dotenv_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let args = OutputArgs {
+            dotenv_file: Some(file.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+
+        OutputFormat::GitlabDotenv.write(&sample(), &args).unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert!(content.starts_with("VERSION_INFO_VERSION=1.2.3\n"));
+    }
+}
@@ -35,6 +35,8 @@ use anyhow::{
 use cargo_plugin_utils::common::get_package_version_from_manifest;
 use clap::Parser;
 
+use super::workflow;
+
 /// Arguments for the `post-bump-hook` command.
 #[derive(Parser, Debug)]
 pub struct PostBumpHookArgs {
@@ -149,10 +151,10 @@ pub fn post_bump_hook(args: PostBumpHookArgs) -> Result<()> {
     if let Some(target) = &args.target_version {
         let target_trimmed = target.trim();
         if cargo_version != target_trimmed {
-            eprintln!(
-                "❌ Error: Cargo.toml version ({}) doesn't match expected target ({})",
+            workflow::error(&format!(
+                "Cargo.toml version ({}) doesn't match expected target ({})",
                 cargo_version, target_trimmed
-            );
+            ));
             if args.exit_on_error {
                 anyhow::bail!("Version bump verification failed");
             }
@@ -168,10 +170,7 @@ pub fn post_bump_hook(args: PostBumpHookArgs) -> Result<()> {
     if let Some(previous) = &args.previous_version {
         let previous_trimmed = previous.trim();
         if cargo_version == previous_trimmed {
-            eprintln!(
-                "⚠️  Warning: Version didn't change (still {})",
-                cargo_version
-            );
+            workflow::warning(&format!("Version didn't change (still {})", cargo_version));
             if args.exit_on_error {
                 anyhow::bail!("Version bump appears to have failed");
             }
@@ -34,6 +34,7 @@ use anyhow::{
 use cargo_plugin_utils::common::get_package_version_from_manifest;
 use clap::Parser;
 
+use super::workflow;
 use crate::version::parse_version;
 
 /// Arguments for the `pre-bump-hook` command.
@@ -192,15 +193,17 @@ pub fn pre_bump_hook(args: PreBumpHookArgs) -> Result<()> {
 
     // Verify Cargo.toml version matches latest tag (if tag exists)
     if latest_tag_version != "0.0.0" && cargo_version != latest_tag_version {
-        eprintln!(
-            "⚠️  Warning: Cargo.toml version ({}) doesn't match latest git tag ({})",
+        let message = format!(
+            "Cargo.toml version ({}) doesn't match latest git tag ({})",
             cargo_version, latest_tag_version
         );
         if args.exit_on_error {
+            workflow::error(&message);
             anyhow::bail!(
                 "Version mismatch detected. Sync Cargo.toml with git tags before bumping."
             );
         }
+        workflow::warning(&message);
     }
 
     // If target version is provided, check for major bump from 0.0.0
@@ -211,11 +214,11 @@ pub fn pre_bump_hook(args: PreBumpHookArgs) -> Result<()> {
         {
             // Warn if bumping from 0.0.0 to 1.0.0 (major version jump)
             if current_major == 0 && current_minor == 0 && current_patch == 0 && target_major == 1 {
-                eprintln!(
-                    "⚠️  Warning: Major version bump from 0.0.0 to {}",
+                workflow::warning(&format!(
+                    "Major version bump from 0.0.0 to {}. This will change the placeholder \
+                     version.",
                     target_trimmed
-                );
-                eprintln!("   This will change the placeholder version. Continue?");
+                ));
                 // Don't fail, just warn - let user decide
             }
         }
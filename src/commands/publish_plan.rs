@@ -0,0 +1,572 @@
+//! Workspace publish order planner command.
+//!
+//! `cargo publish` uploads one crate at a time and every internal dependency
+//! must already be in the registry, so workspace crates have to be published
+//! in topological order. This command builds the dependency graph between
+//! publishable workspace members, drops crates whose current version is
+//! already released, and prints the order in which to publish the rest.
+//!
+//! Path-only dev-dependencies are not part of the graph because cargo strips
+//! them when packaging. Dev-dependencies with a version requirement are kept
+//! (the registry must resolve them), which is how cycles can arise: cargo
+//! itself rejects cycles through normal and build dependencies.
+//!
+//! # Examples
+//!
+//! ```bash
+//! # Print crates to publish, in order
+//! cargo version-info publish-plan
+//!
+//! # Decide "already released" by asking the registry instead of git tags
+//! cargo version-info publish-plan --against registry
+//!
+//! # Per-crate tags in a monorepo
+//! cargo version-info publish-plan --tag-format '{name}-v{version}'
+//!
+//! # Use in GitHub Actions (writes a job matrix to GITHUB_OUTPUT)
+//! cargo version-info publish-plan --format github-actions
+//! ```
+
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+use std::path::PathBuf;
+
+use anyhow::{
+    Context,
+    Result,
+};
+use cargo_metadata::semver::VersionReq;
+use cargo_metadata::{
+    DependencyKind,
+    Metadata,
+    Package,
+};
+use cargo_plugin_utils::common::get_metadata;
+use clap::Parser;
+
+use super::changed::ChangedAgainst;
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
+use crate::registry::RegistryIndex;
+
+/// Arguments for the `publish-plan` command.
+#[derive(Parser, Debug)]
+pub struct PublishPlanArgs {
+    /// Path to the workspace Cargo.toml manifest file (standard cargo flag).
+    ///
+    /// When running as a cargo subcommand, this is automatically handled.
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Path to the git repository.
+    ///
+    /// Defaults to the current directory. Used to look up release tags.
+    #[arg(long, default_value = ".")]
+    repo_path: PathBuf,
+
+    /// How to decide whether a crate version is already released.
+    ///
+    /// - `tags` (default): A git tag matching `--tag-format` exists
+    /// - `registry`: The version is in the registry index (yanked or not)
+    #[arg(long, value_enum, default_value_t = ChangedAgainst::Tags)]
+    against: ChangedAgainst,
+
+    /// Release tag name for a crate, with `{name}` and `{version}`
+    /// placeholders.
+    ///
+    /// Only used with `--against tags`.
+    #[arg(long, default_value = "v{version}")]
+    tag_format: String,
+
+    /// Alternative registry declared in `.cargo/config.toml`.
+    ///
+    /// Only used with `--against registry`. Defaults to crates.io.
+    #[arg(long, conflicts_with = "index")]
+    registry: Option<String>,
+
+    /// Sparse registry index URL or local index directory.
+    ///
+    /// Only used with `--against registry`.
+    #[arg(long)]
+    index: Option<String>,
+
+    /// Include crates whose current version is already released.
+    #[arg(long)]
+    include_released: bool,
+
+    /// Output format for the publish plan.
+    ///
+    /// - `text`: Print one `name version` line per crate, in publish order
+    /// - `json`: Print JSON with a `crates` array of name, version and
+    ///   manifest_path, in publish order
+    /// - `github-actions`: Write `crates`, `count` and a job `matrix` to
+    ///   GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print the same values as
+    ///   `KEY=value` lines
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
+    #[arg(long, default_value = "text")]
+    format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// One crate in the publish plan.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct PlannedCrate {
+    name: String,
+    version: String,
+    manifest_path: String,
+}
+
+impl From<&Package> for PlannedCrate {
+    fn from(package: &Package) -> Self {
+        Self {
+            name: package.name.to_string(),
+            version: package.version.to_string(),
+            manifest_path: package.manifest_path.to_string(),
+        }
+    }
+}
+
+/// Output of the `publish-plan` command.
+#[derive(serde::Serialize)]
+struct PublishPlanOutput<'a> {
+    crates: &'a [PlannedCrate],
+}
+
+impl CommandOutput for PublishPlanOutput<'_> {
+    /// One `name version` line per crate.
+    fn text(&self) -> String {
+        self.crates
+            .iter()
+            .map(|planned| format!("{} {}\n", planned.name, planned.version))
+            .collect()
+    }
+
+    /// The crate names, their count and a job matrix with name and version.
+    fn variables(&self) -> Result<Vec<(String, String)>> {
+        let names: Vec<&str> = self
+            .crates
+            .iter()
+            .map(|planned| planned.name.as_str())
+            .collect();
+        let include: Vec<serde_json::Value> = self
+            .crates
+            .iter()
+            .map(|planned| serde_json::json!({ "name": planned.name, "version": planned.version }))
+            .collect();
+        Ok(vec![
+            ("crates".to_string(), serde_json::to_string(&names)?),
+            ("count".to_string(), self.crates.len().to_string()),
+            (
+                "matrix".to_string(),
+                serde_json::json!({ "include": include }).to_string(),
+            ),
+        ])
+    }
+}
+
+/// Print the order in which unreleased workspace crates must be published.
+///
+/// Publishable workspace members (those without `publish = false`) are
+/// sorted so that every crate comes after the workspace crates it depends
+/// on; crates with no ordering constraint between them are sorted by name.
+/// Crates whose version is already released are then left out.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The cargo metadata cannot be read
+/// - The internal dependency graph contains a cycle
+/// - The registry index cannot be resolved or queried
+/// - The output file cannot be written (for github-actions format)
+///
+/// # Examples
+///
+/// ```no_run
+/// use cargo_version_info::commands::{
+///     PublishPlanArgs,
+///     publish_plan,
+/// };
+/// use clap::Parser;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let args = PublishPlanArgs::parse_from(&["cargo", "version-info", "publish-plan"]);
+/// publish_plan(args)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Example Output
+///
+/// With `--format text`:
+/// ```text
+/// my-core 0.2.0
+/// my-macros 0.2.0
+/// my-crate 0.2.0
+/// ```
+///
+/// With `--format json`:
+/// ```json
+/// {"crates":[{"name":"my-core","version":"0.2.0","manifest_path":"/path/to/my-core/Cargo.toml"}]}
+/// ```
+///
+/// With `--format github-actions` (writes to GITHUB_OUTPUT):
+/// ```text
+/// crates=["my-core","my-crate"]
+/// count=2
+/// matrix={"include":[{"name":"my-core","version":"0.2.0"},{"name":"my-crate","version":"0.2.0"}]}
+/// ```
+pub fn publish_plan(args: PublishPlanArgs) -> Result<()> {
+    let format = OutputFormat::parse(&args.format, "text")?;
+    let mut logger = cargo_plugin_utils::logger::Logger::new();
+
+    logger.status("Reading", "workspace metadata");
+    let metadata = get_metadata(args.manifest_path.as_deref())?;
+    let ordered = publish_order(&metadata)?;
+
+    let plan: Vec<PlannedCrate> = if args.include_released {
+        ordered.into_iter().map(PlannedCrate::from).collect()
+    } else {
+        let released = released_crates(&args, &metadata, &ordered, &mut logger)?;
+        ordered
+            .into_iter()
+            .filter(|package| !released.contains(package.name.as_str()))
+            .map(PlannedCrate::from)
+            .collect()
+    };
+    logger.finish();
+
+    format.write(&PublishPlanOutput { crates: &plan }, &args.output)
+}
+
+/// Names of crates whose current version is already released.
+fn released_crates(
+    args: &PublishPlanArgs,
+    metadata: &Metadata,
+    packages: &[&Package],
+    logger: &mut cargo_plugin_utils::logger::Logger,
+) -> Result<BTreeSet<String>> {
+    let mut released = BTreeSet::new();
+
+    match args.against {
+        ChangedAgainst::Tags => {
+            logger.status("Checking", "git tags");
+            let repo = gix::discover(&args.repo_path).with_context(|| {
+                format!(
+                    "Failed to discover git repository at {}",
+                    args.repo_path.display()
+                )
+            })?;
+            for package in packages {
+                let tag = args
+                    .tag_format
+                    .replace("{name}", &package.name)
+                    .replace("{version}", &package.version.to_string());
+                if repo
+                    .try_find_reference(format!("refs/tags/{}", tag).as_str())?
+                    .is_some()
+                {
+                    released.insert(package.name.to_string());
+                }
+            }
+        }
+        ChangedAgainst::Registry => {
+            let index = RegistryIndex::resolve(
+                args.registry.as_deref(),
+                args.index.as_deref(),
+                metadata.workspace_root.as_std_path(),
+            )?;
+            let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+            for package in packages {
+                logger.status("Checking", &format!("{} in registry", package.name));
+                let versions = rt.block_on(index.lookup(&package.name))?;
+                if versions.contains(&package.version.to_string()) {
+                    released.insert(package.name.to_string());
+                }
+            }
+        }
+    }
+
+    Ok(released)
+}
+
+/// Sort publishable workspace members so dependencies come first.
+///
+/// Uses Kahn's algorithm over the dependencies between publishable members
+/// (skipping path-only dev-dependencies), always taking the alphabetically
+/// first crate whose dependencies are done so the order is stable.
+fn publish_order(metadata: &Metadata) -> Result<Vec<&Package>> {
+    let packages: BTreeMap<&str, &Package> = metadata
+        .workspace_packages()
+        .into_iter()
+        .filter(|package| {
+            package
+                .publish
+                .as_ref()
+                .is_none_or(|registries| !registries.is_empty())
+        })
+        .map(|package| (package.name.as_str(), package))
+        .collect();
+
+    // For each crate, the workspace crates it still waits for
+    let mut pending: BTreeMap<&str, BTreeSet<&str>> = packages
+        .iter()
+        .map(|(name, package)| {
+            let deps = package
+                .dependencies
+                .iter()
+                .filter(|dep| dep.path.is_some())
+                .filter(|dep| {
+                    dep.kind != DependencyKind::Development || dep.req != VersionReq::STAR
+                })
+                .filter_map(|dep| {
+                    packages
+                        .get_key_value(dep.name.as_str())
+                        .map(|(key, _)| *key)
+                })
+                .filter(|dep_name| dep_name != name)
+                .collect();
+            (*name, deps)
+        })
+        .collect();
+
+    let mut order = Vec::with_capacity(packages.len());
+    while let Some(next) = pending
+        .iter()
+        .find(|(_, deps)| deps.is_empty())
+        .map(|(name, _)| *name)
+    {
+        pending.remove(next);
+        for deps in pending.values_mut() {
+            deps.remove(next);
+        }
+        order.push(packages[next]);
+    }
+
+    if !pending.is_empty() {
+        // Crates that only wait for a cycle are pending too; report the cycles
+        let cycles: Vec<String> = dependency_cycles(&pending)
+            .iter()
+            .flat_map(|cycle| {
+                cycle.iter().map(|name| {
+                    let deps: Vec<&str> = pending[name].intersection(cycle).copied().collect();
+                    format!("{} -> {}", name, deps.join(", "))
+                })
+            })
+            .collect();
+        anyhow::bail!(
+            "Dependency cycle between workspace crates:\n  {}",
+            cycles.join("\n  ")
+        );
+    }
+
+    Ok(order)
+}
+
+/// Strongly connected components of `graph` that form a cycle (more than one
+/// crate, or a crate depending on itself).
+fn dependency_cycles<'a>(graph: &BTreeMap<&'a str, BTreeSet<&'a str>>) -> Vec<BTreeSet<&'a str>> {
+    let reachable = |start: &'a str| {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![start];
+        while let Some(name) = stack.pop() {
+            for dep in graph.get(name).into_iter().flatten() {
+                if seen.insert(*dep) {
+                    stack.push(dep);
+                }
+            }
+        }
+        seen
+    };
+    let reach: BTreeMap<&str, BTreeSet<&str>> =
+        graph.keys().map(|name| (*name, reachable(name))).collect();
+
+    let mut assigned = BTreeSet::new();
+    let mut cycles = Vec::new();
+    for name in graph.keys() {
+        if assigned.contains(name) || !reach[name].contains(name) {
+            continue;
+        }
+        let component: BTreeSet<&str> = reach[name]
+            .iter()
+            .filter(|other| reach[*other].contains(name))
+            .copied()
+            .collect();
+        assigned.extend(component.iter().copied());
+        cycles.push(component);
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a workspace with members given as `(name, dependencies)`.
+    fn create_workspace(members: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let names: Vec<String> = members
+            .iter()
+            .map(|(name, _)| format!("\"{}\"", name))
+            .collect();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            format!(
+                "[workspace]\nmembers = [{}]\nresolver = \"2\"\n",
+                names.join(", ")
+            ),
+        )
+        .unwrap();
+        for (name, deps) in members {
+            let member = dir.path().join(name);
+            std::fs::create_dir_all(member.join("src")).unwrap();
+            std::fs::write(member.join("src").join("lib.rs"), "").unwrap();
+            std::fs::write(
+                member.join("Cargo.toml"),
+                format!(
+                    "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n{}",
+                    name, deps
+                ),
+            )
+            .unwrap();
+        }
+        dir
+    }
+
+    fn order_names(dir: &tempfile::TempDir) -> Result<Vec<String>> {
+        let metadata = get_metadata(Some(&dir.path().join("Cargo.toml")))?;
+        Ok(publish_order(&metadata)?
+            .into_iter()
+            .map(|package| package.name.to_string())
+            .collect())
+    }
+
+    #[test]
+    fn test_publish_order_dependencies_first() {
+        let dir = create_workspace(&[
+            (
+                "app",
+                "\n[dependencies]\nmy-core = { path = \"../my-core\", version = \"0.1.0\" }\nmacros = { path = \"../macros\", version = \"0.1.0\" }\n",
+            ),
+            ("my-core", ""),
+            (
+                "macros",
+                "\n[dependencies]\nmy-core = { path = \"../my-core\", version = \"0.1.0\" }\n",
+            ),
+        ]);
+
+        assert_eq!(order_names(&dir).unwrap(), vec!["my-core", "macros", "app"]);
+    }
+
+    #[test]
+    fn test_publish_order_skips_unpublishable_and_path_only_dev_deps() {
+        let dir = create_workspace(&[
+            (
+                "alpha",
+                "\n[dev-dependencies]\nbeta = { path = \"../beta\" }\n",
+            ),
+            (
+                "beta",
+                "\n[dependencies]\nalpha = { path = \"../alpha\", version = \"0.1.0\" }\n",
+            ),
+            ("internal", "publish = false\n"),
+        ]);
+
+        assert_eq!(order_names(&dir).unwrap(), vec!["alpha", "beta"]);
+    }
+
+    #[test]
+    fn test_publish_order_reports_cycle() {
+        let dir = create_workspace(&[
+            (
+                "alpha",
+                "\n[dev-dependencies]\nbeta = { path = \"../beta\", version = \"0.1.0\" }\n",
+            ),
+            (
+                "beta",
+                "\n[dependencies]\nalpha = { path = \"../alpha\", version = \"0.1.0\" }\n",
+            ),
+        ]);
+
+        let error = order_names(&dir).unwrap_err();
+
+        assert!(error.to_string().contains("cycle"));
+        assert!(error.to_string().contains("alpha -> beta"));
+    }
+
+    #[test]
+    fn test_publish_order_reports_only_cycle_members() {
+        let dir = create_workspace(&[
+            (
+                "alpha",
+                "\n[dev-dependencies]\nbeta = { path = \"../beta\", version = \"0.1.0\" }\n",
+            ),
+            (
+                "beta",
+                "\n[dependencies]\nalpha = { path = \"../alpha\", version = \"0.1.0\" }\n",
+            ),
+            (
+                "gamma",
+                "\n[dependencies]\nalpha = { path = \"../alpha\", version = \"0.1.0\" }\n",
+            ),
+        ]);
+
+        let error = order_names(&dir).unwrap_err().to_string();
+
+        assert!(error.contains("alpha -> beta"));
+        assert!(error.contains("beta -> alpha"));
+        assert!(!error.contains("gamma"));
+    }
+
+    #[test]
+    fn test_publish_plan_filters_released_by_registry() {
+        let dir = create_workspace(&[
+            ("my-core", ""),
+            (
+                "app",
+                "\n[dependencies]\nmy-core = { path = \"../my-core\", version = \"0.1.0\" }\n",
+            ),
+        ]);
+        let index_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            index_dir.path().join("config.json"),
+            r#"{"dl":"https://example.com/api/v1/crates"}"#,
+        )
+        .unwrap();
+        let my_core_path = index_dir
+            .path()
+            .join(crate::registry::sparse_index_path("my-core"));
+        std::fs::create_dir_all(my_core_path.parent().unwrap()).unwrap();
+        std::fs::write(&my_core_path, r#"{"name":"my-core","vers":"0.1.0"}"#).unwrap();
+
+        let output_file = tempfile::NamedTempFile::new().unwrap();
+        let args = PublishPlanArgs {
+            manifest_path: Some(dir.path().join("Cargo.toml")),
+            repo_path: dir.path().to_path_buf(),
+            against: ChangedAgainst::Registry,
+            tag_format: "v{version}".to_string(),
+            registry: None,
+            index: Some(index_dir.path().display().to_string()),
+            include_released: false,
+            format: "github-actions".to_string(),
+            output: OutputArgs {
+                github_output: Some(output_file.path().to_string_lossy().to_string()),
+                ..Default::default()
+            },
+        };
+        publish_plan(args).unwrap();
+
+        let content = std::fs::read_to_string(output_file.path()).unwrap();
+        assert!(content.contains("crates=[\"app\"]"));
+        assert!(content.contains("count=1"));
+        assert!(content.contains(r#"matrix={"include":[{"name":"app","version":"0.1.0"}]}"#));
+    }
+}
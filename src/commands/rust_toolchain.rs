@@ -24,6 +24,12 @@ use anyhow::{
 };
 use clap::Parser;
 
+use super::output::{
+    OutputArgs,
+    OutputFormat,
+    VersionOutput,
+};
+
 /// Arguments for the `rust-toolchain` command.
 #[derive(Parser, Debug)]
 pub struct RustToolchainArgs {
@@ -37,8 +43,17 @@ pub struct RustToolchainArgs {
     ///
     /// - `version`: Print just the version number (e.g., "1.91.0")
     /// - `json`: Print JSON with version field
+    /// - `github-actions`: Write version to GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print a
+    ///   `VERSION_INFO_VERSION=...` line
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "version")]
     format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
 /// Get the Rust toolchain version from a `.rust-toolchain.toml` file.
@@ -108,13 +123,8 @@ pub fn rust_toolchain(args: RustToolchainArgs) -> Result<()> {
         })
         .with_context(|| format!("No channel found in {}", args.toolchain_file.display()))?;
 
-    match args.format.as_str() {
-        "version" => println!("{}", version),
-        "json" => println!("{{\"version\":\"{}\"}}", version),
-        _ => anyhow::bail!("Invalid format: {}", args.format),
-    }
-
-    Ok(())
+    OutputFormat::parse(&args.format, "version")?
+        .write(&VersionOutput { version: &version }, &args.output)
 }
 
 #[cfg(test)]
@@ -137,6 +147,7 @@ mod tests {
         let args = RustToolchainArgs {
             toolchain_file: toolchain_file.path().to_path_buf(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(rust_toolchain(args).is_ok());
     }
@@ -147,6 +158,7 @@ mod tests {
         let args = RustToolchainArgs {
             toolchain_file: toolchain_file.path().to_path_buf(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(rust_toolchain(args).is_ok());
     }
@@ -157,6 +169,7 @@ mod tests {
         let args = RustToolchainArgs {
             toolchain_file: toolchain_file.path().to_path_buf(),
             format: "json".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(rust_toolchain(args).is_ok());
     }
@@ -167,6 +180,7 @@ mod tests {
         let args = RustToolchainArgs {
             toolchain_file: toolchain_file.path().to_path_buf(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(rust_toolchain(args).is_err());
     }
@@ -176,6 +190,7 @@ mod tests {
         let args = RustToolchainArgs {
             toolchain_file: "/nonexistent/.rust-toolchain.toml".into(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(rust_toolchain(args).is_err());
     }
@@ -186,6 +201,7 @@ mod tests {
         let args = RustToolchainArgs {
             toolchain_file: toolchain_file.path().to_path_buf(),
             format: "invalid".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(rust_toolchain(args).is_err());
     }
@@ -196,6 +212,7 @@ mod tests {
         let args = RustToolchainArgs {
             toolchain_file: toolchain_file.path().to_path_buf(),
             format: "version".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(rust_toolchain(args).is_ok());
     }
@@ -19,6 +19,11 @@
 use anyhow::Result;
 use clap::Parser;
 
+use super::output::{
+    CommandOutput,
+    OutputArgs,
+    OutputFormat,
+};
 use crate::version::{
     format_tag,
     parse_version,
@@ -37,8 +42,17 @@ pub struct TagArgs {
     ///
     /// - `tag`: Print just the tag (e.g., "v0.1.2")
     /// - `json`: Print JSON with tag and version fields
+    /// - `github-actions`: Write tag and version to GITHUB_OUTPUT
+    /// - `dotenv`, `shell-export`, `gitlab-dotenv`: Print
+    ///   `VERSION_INFO_TAG=...` and `VERSION_INFO_VERSION=...` lines
+    /// - `ci`: `github-actions` in GitHub Actions, `gitlab-dotenv` in GitLab
+    ///   CI, `dotenv` elsewhere
     #[arg(long, default_value = "tag")]
     format: String,
+
+    /// Output file and variable name options.
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
 /// Generate a git tag name from a semantic version string.
@@ -84,13 +98,26 @@ pub fn tag(args: TagArgs) -> Result<()> {
     let (major, minor, patch) = parse_version(&args.version)?;
     let tag = format_tag(major, minor, patch);
 
-    match args.format.as_str() {
-        "tag" => println!("{}", tag),
-        "json" => println!("{{\"tag\":\"{}\",\"version\":\"{}\"}}", tag, args.version),
-        _ => anyhow::bail!("Invalid format: {}", args.format),
-    }
+    OutputFormat::parse(&args.format, "tag")?.write(
+        &TagOutput {
+            tag: &tag,
+            version: &args.version,
+        },
+        &args.output,
+    )
+}
 
-    Ok(())
+/// Output of `tag`.
+#[derive(serde::Serialize)]
+struct TagOutput<'a> {
+    tag: &'a str,
+    version: &'a str,
+}
+
+impl CommandOutput for TagOutput<'_> {
+    fn text(&self) -> String {
+        self.tag.to_string()
+    }
 }
 
 #[cfg(test)]
@@ -102,6 +129,7 @@ mod tests {
         let args = TagArgs {
             version: "0.1.2".to_string(),
             format: "tag".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(tag(args).is_ok());
     }
@@ -111,6 +139,7 @@ mod tests {
         let args = TagArgs {
             version: "v0.1.2".to_string(),
             format: "tag".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(tag(args).is_ok());
     }
@@ -120,6 +149,7 @@ mod tests {
         let args = TagArgs {
             version: "1.2.3".to_string(),
             format: "json".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(tag(args).is_ok());
     }
@@ -129,6 +159,7 @@ mod tests {
         let args = TagArgs {
             version: "invalid".to_string(),
             format: "tag".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(tag(args).is_err());
     }
@@ -138,6 +169,7 @@ mod tests {
         let args = TagArgs {
             version: "0.1.2".to_string(),
             format: "invalid".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(tag(args).is_err());
     }
@@ -147,6 +179,7 @@ mod tests {
         let args = TagArgs {
             version: "10.20.30".to_string(),
             format: "tag".to_string(),
+            output: OutputArgs::default(),
         };
         assert!(tag(args).is_ok());
     }
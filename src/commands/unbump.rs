@@ -0,0 +1,515 @@
+//! Undo the last version bump.
+//!
+//! This command undoes a commit created by `cargo version-info bump`. It
+//! recognizes the commit by the `Bumped-by: cargo-version-info` trailer of
+//! its message (the subject can be configured) and refuses to run if other
+//! commits were made after it.
+//!
+//! How the bump is undone depends on whether it was pushed:
+//!
+//! - **Not pushed**: the branch is reset to the bump's parent, as with `git
+//!   reset --keep HEAD~1`.
+//! - **Pushed** (the branch's upstream contains the bump): a revert commit is
+//!   created on top, so the published history stays intact.
+//!
+//! Either way, the version changes are undone in the worktree and in the index
+//! entries of the bumped files. Other uncommitted changes, including ones in
+//! the bumped files and staged changes to other files, are kept; if one
+//! touches a line the bump changed, the command fails before changing
+//! anything.
+//!
+//! # Examples
+//!
+//! ```bash
+//! # Oops, that should have been a minor bump
+//! cargo version-info bump --patch
+//! cargo version-info unbump
+//! cargo version-info bump --minor
+//! ```
+
+use std::path::PathBuf;
+
+use anyhow::{
+    Context,
+    Result,
+};
+use bstr::ByteSlice;
+use clap::Parser;
+
+use super::bump::{
+    commit,
+    diff,
+};
+
+/// Arguments for the `unbump` command.
+#[derive(Parser, Debug)]
+pub struct UnbumpArgs {
+    /// Path to the Cargo.toml manifest file (standard cargo flag).
+    ///
+    /// Used to find the git repository. When running as a cargo subcommand,
+    /// this is automatically handled.
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+}
+
+/// A worktree file to restore once the git changes succeeded.
+enum Restore {
+    /// Write this content.
+    Write(PathBuf, String),
+    /// Remove the file, which the bump added.
+    Remove(PathBuf),
+}
+
+/// Undo the version bump commit at HEAD.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Not in a git repository, or HEAD is detached
+/// - No bump commit is found, or commits were made after it
+/// - Uncommitted changes touch the lines the bump changed
+/// - The revert commit cannot be created or signed
+///
+/// # Examples
+///
+/// ```no_run
+/// use cargo_version_info::commands::{
+///     UnbumpArgs,
+///     unbump,
+/// };
+/// use clap::Parser;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let args = UnbumpArgs::parse_from(&["cargo", "version-info", "unbump"]);
+/// unbump(args)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn unbump(args: UnbumpArgs) -> Result<()> {
+    let mut logger = cargo_plugin_utils::logger::Logger::new();
+
+    let dir = match args.manifest_path.as_deref().and_then(|path| path.parent()) {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let repo = gix::discover(&dir).context("Not in a git repository")?;
+    let head_id = repo
+        .head_id()
+        .context("HEAD does not point to a commit")?
+        .detach();
+
+    logger.status("Checking", "last commit");
+    let (bump_id, commits_after) =
+        find_last_bump(&repo, head_id)?.context("No version bump commit found on this branch")?;
+    let bump = repo
+        .find_commit(bump_id)
+        .context("Failed to read the bump commit")?;
+    let message = bump
+        .message_raw()
+        .context("Failed to read the bump commit message")?
+        .to_str_lossy()
+        .into_owned();
+    let subject = message.lines().next().unwrap_or_default().to_string();
+    if commits_after > 0 {
+        anyhow::bail!(
+            "{} commit{} made after the version bump {} ({}); refusing to undo it",
+            commits_after,
+            if commits_after == 1 { " was" } else { "s were" },
+            short_id(bump_id),
+            subject
+        );
+    }
+    let parent_id = bump
+        .parent_ids()
+        .next()
+        .context("The bump commit has no parent")?
+        .detach();
+    logger.finish();
+
+    // Work out the worktree changes first, so a conflict changes nothing
+    let (restores, bumped_paths) = worktree_restores(&repo, parent_id, bump_id)?;
+    let parent_tree = repo
+        .find_commit(parent_id)
+        .context("Failed to read the parent commit")?
+        .tree_id()
+        .context("Failed to read the parent tree")?
+        .detach();
+
+    if upstream_contains(&repo, bump_id)? {
+        logger.status("Reverting", &subject);
+        let revert_message = format!(
+            "Revert \"{}\"\n\nThis reverts commit {}.\n",
+            subject, bump_id
+        );
+        let head = repo.head_id().context("Failed to read HEAD")?;
+        let revert_id = commit::create_commit(&repo, &parent_tree, head, &revert_message)?;
+        commit::update_head(&repo, revert_id, "unbump: revert")?;
+        commit::reset_index_paths(&repo, parent_tree, &bumped_paths)?;
+        restore_worktree(restores)?;
+        logger.finish();
+        logger.print_message(&format!(
+            "✓ Reverted \"{}\" with {} (the bump was already pushed)",
+            subject,
+            short_id(revert_id)
+        ));
+    } else {
+        logger.status("Resetting", &format!("to {}", short_id(parent_id)));
+        commit::update_head(&repo, parent_id, "unbump: reset")?;
+        commit::reset_index_paths(&repo, parent_tree, &bumped_paths)?;
+        restore_worktree(restores)?;
+        logger.finish();
+        logger.print_message(&format!(
+            "✓ Undid \"{}\" (reset to {})",
+            subject,
+            short_id(parent_id)
+        ));
+    }
+
+    Ok(())
+}
+
+/// The most recent bump commit on the first-parent history of `head_id`,
+/// and the number of commits made after it.
+fn find_last_bump(
+    repo: &gix::Repository,
+    head_id: gix::ObjectId,
+) -> Result<Option<(gix::ObjectId, usize)>> {
+    let walk = repo
+        .rev_walk([head_id])
+        .first_parent_only()
+        .all()
+        .context("Failed to walk commit history")?;
+    for (distance, info) in walk.enumerate() {
+        let info = info.context("Failed to read commit while walking history")?;
+        let commit = repo
+            .find_commit(info.id)
+            .context("Failed to find commit object")?;
+        let message = commit
+            .message_raw()
+            .context("Failed to read raw commit message")?;
+        if commit::is_bump_message(&message.to_str_lossy()) {
+            return Ok(Some((info.id, distance)));
+        }
+    }
+    Ok(None)
+}
+
+/// How to restore each worktree file the bump changed, and the paths of
+/// those files relative to the worktree.
+///
+/// # Errors
+///
+/// Returns an error if an uncommitted change conflicts with the bump.
+fn worktree_restores(
+    repo: &gix::Repository,
+    parent_id: gix::ObjectId,
+    bump_id: gix::ObjectId,
+) -> Result<(Vec<Restore>, Vec<bstr::BString>)> {
+    let workdir = repo
+        .workdir()
+        .context("Cannot undo a bump in a bare repository")?;
+    let tree = |id: gix::ObjectId| -> Result<gix::Tree<'_>> {
+        repo.find_commit(id)
+            .context("Failed to find commit")?
+            .tree()
+            .context("Failed to read commit tree")
+    };
+    let changes = repo
+        .diff_tree_to_tree(
+            &tree(parent_id)?,
+            &tree(bump_id)?,
+            gix::diff::Options::default(),
+        )
+        .context("Failed to diff the bump commit")?;
+
+    let mut restores = Vec::new();
+    let mut paths = Vec::new();
+    for change in changes {
+        if change.entry_mode().is_tree() {
+            continue;
+        }
+        paths.push(change.location().to_owned());
+        let path = workdir.join(change.location().to_str_lossy().as_ref());
+        let working = std::fs::read_to_string(&path).ok();
+        let conflict = || {
+            format!(
+                "{} has uncommitted changes to the lines the bump changed",
+                path.display()
+            )
+        };
+        match change {
+            gix::object::tree::diff::ChangeDetached::Modification {
+                previous_id, id, ..
+            } => {
+                let working = working.with_context(conflict)?;
+                let content = diff::revert_changes(
+                    &working,
+                    &blob_text(repo, id)?,
+                    &blob_text(repo, previous_id)?,
+                )
+                .with_context(conflict)?;
+                restores.push(Restore::Write(path, content));
+            }
+            gix::object::tree::diff::ChangeDetached::Addition { id, .. } => {
+                if working.is_some_and(|working| working != blob_text(repo, id).unwrap_or_default())
+                {
+                    anyhow::bail!(conflict());
+                }
+                restores.push(Restore::Remove(path));
+            }
+            gix::object::tree::diff::ChangeDetached::Deletion { id, .. } => {
+                if working.is_some() {
+                    anyhow::bail!(conflict());
+                }
+                restores.push(Restore::Write(path, blob_text(repo, id)?));
+            }
+            gix::object::tree::diff::ChangeDetached::Rewrite { .. } => {
+                anyhow::bail!("Cannot undo a bump that renamed {}", path.display());
+            }
+        }
+    }
+    Ok((restores, paths))
+}
+
+/// Apply the worktree restores.
+fn restore_worktree(restores: Vec<Restore>) -> Result<()> {
+    for restore in restores {
+        match restore {
+            Restore::Write(path, content) => std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?,
+            Restore::Remove(path) => {
+                if path.exists() {
+                    std::fs::remove_file(&path)
+                        .with_context(|| format!("Failed to remove {}", path.display()))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether the upstream of the current branch contains `commit_id`.
+///
+/// Branches without an upstream, or whose upstream was never fetched, have
+/// not been pushed.
+fn upstream_contains(repo: &gix::Repository, commit_id: gix::ObjectId) -> Result<bool> {
+    let Some(head_name) = repo.head_name().context("Failed to read HEAD")? else {
+        return Ok(false);
+    };
+    let Some(upstream) =
+        repo.branch_remote_tracking_ref_name(head_name.as_ref(), gix::remote::Direction::Fetch)
+    else {
+        return Ok(false);
+    };
+    let upstream = upstream.context("Invalid upstream configuration")?;
+    let Ok(mut reference) = repo.find_reference(upstream.as_ref()) else {
+        return Ok(false);
+    };
+    let upstream_id = reference
+        .peel_to_id()
+        .context("Failed to resolve upstream")?
+        .detach();
+    if upstream_id == commit_id {
+        return Ok(true);
+    }
+    Ok(repo
+        .merge_base(commit_id, upstream_id)
+        .is_ok_and(|base| base.detach() == commit_id))
+}
+
+/// Content of the blob `id` as text.
+fn blob_text(repo: &gix::Repository, id: gix::ObjectId) -> Result<String> {
+    let blob = repo
+        .find_object(id)
+        .context("Failed to find blob")?
+        .try_into_blob()
+        .context("Tree entry is not a blob")?;
+    Ok(blob.data.to_str_lossy().into_owned())
+}
+
+/// Abbreviated commit ID.
+fn short_id(id: gix::ObjectId) -> String {
+    id.to_hex_with_len(7).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    /// A repository with a 1.0.0 commit followed by a bump to 1.0.1.
+    fn create_bumped_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.email", "test@example.com"]);
+        git(dir.path(), &["config", "user.name", "Test User"]);
+        git(dir.path(), &["config", "commit.gpgsign", "false"]);
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test-unbump\"\nversion = \"1.0.0\"\n\n[dependencies]\n",
+        )
+        .unwrap();
+        git(dir.path(), &["add", "-A"]);
+        git(dir.path(), &["commit", "-q", "-m", "Initial commit"]);
+
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test-unbump\"\nversion = \"1.0.1\"\n\n[dependencies]\n",
+        )
+        .unwrap();
+        git(dir.path(), &["add", "-A"]);
+        git(
+            dir.path(),
+            &[
+                "commit",
+                "-q",
+                "-m",
+                &commit::commit_message("1.0.0", "1.0.1"),
+            ],
+        );
+        dir
+    }
+
+    fn args(dir: &tempfile::TempDir) -> UnbumpArgs {
+        UnbumpArgs {
+            manifest_path: Some(dir.path().join("Cargo.toml")),
+        }
+    }
+
+    #[test]
+    fn test_unbump_resets_and_keeps_other_changes() {
+        let dir = create_bumped_repo();
+        let initial = git(dir.path(), &["rev-parse", "HEAD~1"]);
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test-unbump\"\nversion = \"1.0.1\"\n\n[dependencies]\nserde = \"1\"\n",
+        )
+        .unwrap();
+
+        unbump(args(&dir)).unwrap();
+
+        assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), initial);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("Cargo.toml")).unwrap(),
+            "[package]\nname = \"test-unbump\"\nversion = \"1.0.0\"\n\n[dependencies]\nserde = \"1\"\n"
+        );
+        // The unrelated change stays unstaged
+        assert_eq!(
+            git(dir.path(), &["status", "--porcelain"]),
+            " M Cargo.toml\n"
+        );
+    }
+
+    #[test]
+    fn test_unbump_keeps_staged_changes_to_other_files() {
+        let dir = create_bumped_repo();
+        std::fs::write(dir.path().join("notes.txt"), "notes\n").unwrap();
+        git(dir.path(), &["add", "notes.txt"]);
+
+        unbump(args(&dir)).unwrap();
+
+        assert_eq!(
+            git(dir.path(), &["status", "--porcelain"]),
+            "A  notes.txt\n"
+        );
+        assert!(git(dir.path(), &["show", ":Cargo.toml"]).contains("version = \"1.0.0\""));
+    }
+
+    #[test]
+    fn test_unbump_refuses_after_other_commits() {
+        let dir = create_bumped_repo();
+        std::fs::write(dir.path().join("notes.txt"), "notes\n").unwrap();
+        git(dir.path(), &["add", "notes.txt"]);
+        git(dir.path(), &["commit", "-q", "-m", "Add notes"]);
+        let head = git(dir.path(), &["rev-parse", "HEAD"]);
+
+        let err = unbump(args(&dir)).unwrap_err().to_string();
+
+        assert!(
+            err.contains("1 commit was made after the version bump"),
+            "{}",
+            err
+        );
+        assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+    }
+
+    #[test]
+    fn test_unbump_refuses_conflicting_changes() {
+        let dir = create_bumped_repo();
+        let head = git(dir.path(), &["rev-parse", "HEAD"]);
+        let edited = "[package]\nname = \"test-unbump\"\nversion = \"1.0.2\"\n\n[dependencies]\n";
+        std::fs::write(dir.path().join("Cargo.toml"), edited).unwrap();
+
+        let err = unbump(args(&dir)).unwrap_err().to_string();
+
+        assert!(err.contains("has uncommitted changes"), "{}", err);
+        assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("Cargo.toml")).unwrap(),
+            edited
+        );
+    }
+
+    #[test]
+    fn test_unbump_custom_message() {
+        let dir = create_bumped_repo();
+        let initial = git(dir.path(), &["rev-parse", "HEAD~1"]);
+        git(
+            dir.path(),
+            &[
+                "commit",
+                "-q",
+                "--amend",
+                "-m",
+                "release: test-unbump v1.0.1\n\nRelease-As: 1.0.1\nBumped-by: cargo-version-info",
+            ],
+        );
+
+        unbump(args(&dir)).unwrap();
+
+        assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), initial);
+    }
+
+    #[test]
+    fn test_unbump_without_bump_commit() {
+        let dir = create_bumped_repo();
+        git(dir.path(), &["reset", "-q", "--hard", "HEAD~1"]);
+
+        let err = unbump(args(&dir)).unwrap_err().to_string();
+
+        assert!(err.contains("No version bump commit found"), "{}", err);
+    }
+
+    #[test]
+    fn test_unbump_reverts_pushed_bump() {
+        let remote = tempfile::tempdir().unwrap();
+        git(remote.path(), &["init", "-q", "--bare"]);
+        let dir = create_bumped_repo();
+        let bump = git(dir.path(), &["rev-parse", "HEAD"]);
+        git(
+            dir.path(),
+            &["remote", "add", "origin", remote.path().to_str().unwrap()],
+        );
+        git(dir.path(), &["push", "-q", "-u", "origin", "HEAD"]);
+
+        unbump(args(&dir)).unwrap();
+
+        assert_eq!(git(dir.path(), &["rev-parse", "HEAD~1"]), bump);
+        assert_eq!(
+            git(dir.path(), &["rev-parse", "HEAD^{tree}"]),
+            git(dir.path(), &["rev-parse", "HEAD~2^{tree}"])
+        );
+        let message = git(dir.path(), &["log", "-1", "--format=%B"]);
+        assert!(
+            message.starts_with("Revert \"chore(version): bump 1.0.0 -> 1.0.1\""),
+            "{}",
+            message
+        );
+        assert!(message.contains(&format!("This reverts commit {}.", bump.trim())));
+        assert!(git(dir.path(), &["status", "--porcelain"]).is_empty());
+        assert!(
+            std::fs::read_to_string(dir.path().join("Cargo.toml"))
+                .unwrap()
+                .contains("version = \"1.0.0\"")
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! GitHub Actions step summaries and workflow commands.
+//!
+//! Outside GitHub Actions (see [`CiProvider::detect`]) warnings and errors are
+//! printed as plain messages and step summaries are skipped. Inside Actions,
+//! warnings and errors become `::warning::`/`::error::` workflow commands,
+//! which show up as annotations on the run, and [`append_step_summary`]
+//! appends markdown to `$GITHUB_STEP_SUMMARY`.
+//!
+//! Workflow commands are written to stderr so they never mix with a
+//! command's result on stdout.
+
+use std::io::Write;
+
+use anyhow::{
+    Context,
+    Result,
+};
+
+use super::output::CiProvider;
+
+/// Environment variable with the step summary file.
+pub(crate) const STEP_SUMMARY_ENV: &str = "GITHUB_STEP_SUMMARY";
+
+/// Lines of a changelog included in a step summary.
+pub(crate) const SUMMARY_CHANGELOG_LINES: usize = 50;
+
+/// Whether the command runs in GitHub Actions.
+pub(crate) fn in_github_actions() -> bool {
+    CiProvider::detect() == Some(CiProvider::GithubActions)
+}
+
+/// Report a problem that does not stop the command.
+pub(crate) fn warning(message: &str) {
+    if in_github_actions() {
+        eprintln!("::warning::{}", escape_data(message));
+    } else {
+        eprintln!("⚠️  Warning: {}", message);
+    }
+}
+
+/// Report a problem that fails the command (or would, with
+/// `--exit-on-error`).
+pub(crate) fn error(message: &str) {
+    if in_github_actions() {
+        eprintln!("::error::{}", escape_data(message));
+    } else {
+        eprintln!("❌ Error: {}", message);
+    }
+}
+
+/// Append `markdown` to `$GITHUB_STEP_SUMMARY`.
+///
+/// Does nothing outside GitHub Actions or when the variable is not set.
+///
+/// # Errors
+///
+/// Returns an error if the summary file cannot be written.
+#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+pub(crate) fn append_step_summary(markdown: &str) -> Result<()> {
+    if !in_github_actions() {
+        return Ok(());
+    }
+    let Ok(path) = std::env::var(STEP_SUMMARY_ENV) else {
+        return Ok(());
+    };
+    write_summary(&path, markdown)
+}
+
+/// Append `markdown` to the summary file at `path`, followed by a blank line.
+fn write_summary(path: &str, markdown: &str) -> Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path))?;
+    writeln!(file, "{}\n", markdown.trim_end())
+        .with_context(|| format!("Failed to write to {}", path))
+}
+
+/// Markdown section with a heading and a two-column table of `rows`.
+pub(crate) fn summary_table(title: &str, rows: &[(&str, String)]) -> String {
+    let mut markdown = format!("### {}\n\n| | |\n| --- | --- |\n", title);
+    for (name, value) in rows {
+        markdown.push_str(&format!("| {} | {} |\n", name, value.replace('|', "\\|")));
+    }
+    markdown
+}
+
+/// The first `max_lines` lines of `text`, noting how many were left out.
+pub(crate) fn excerpt(text: &str, max_lines: usize) -> String {
+    let total = text.lines().count();
+    let mut excerpt: String = text
+        .lines()
+        .take(max_lines)
+        .map(|line| format!("{}\n", line))
+        .collect();
+    if total > max_lines {
+        let omitted = total - max_lines;
+        excerpt.push_str(&format!(
+            "\n_… {} more line{}_\n",
+            omitted,
+            if omitted == 1 { "" } else { "s" }
+        ));
+    }
+    excerpt
+}
+
+/// Escape the message of a workflow command.
+fn escape_data(message: &str) -> String {
+    message
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_table() {
+        let markdown = summary_table(
+            "Version bump",
+            &[
+                ("Version", "`0.1.0` → `0.2.0`".to_string()),
+                ("Note", "a|b".to_string()),
+            ],
+        );
+
+        assert_eq!(
+            markdown,
+            "### Version bump\n\n| | |\n| --- | --- |\n| Version | `0.1.0` → `0.2.0` |\n| Note | a\\|b |\n"
+        );
+    }
+
+    #[test]
+    fn test_excerpt_and_escape() {
+        assert_eq!(excerpt("a\nb\nc\n", 5), "a\nb\nc\n");
+        assert_eq!(excerpt("a\nb\nc\n", 2), "a\nb\n\n_… 1 more line_\n");
+        assert_eq!(escape_data("50% done\nnext"), "50%25 done%0Anext");
+    }
+
+    #[test]
+    fn test_write_summary_appends() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().to_string_lossy().to_string();
+
+        write_summary(&path, "### One\n").unwrap();
+        write_summary(&path, "### Two").unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(content, "### One\n\n### Two\n\n");
+    }
+}
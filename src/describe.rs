@@ -0,0 +1,372 @@
+//! `git describe`-style versions computed with gix.
+//!
+//! A describe version records how far HEAD is from the last release:
+//!
+//! ```text
+//! 1.2.3-dev.14+g1a2b3c4.dirty
+//! │     │      │        └─ uncommitted changes to tracked files
+//! │     │      └─ short SHA of HEAD
+//! │     └─ commits since the tag
+//! └─ version of the nearest semver tag (0.0.0 without tags)
+//! ```
+//!
+//! When HEAD is exactly at a tag and the working tree is clean, the version
+//! is just the tag version (`1.2.3`).
+//!
+//! # Format String
+//!
+//! The shape is controlled by a format string with these placeholders,
+//! shared by `dev --describe`, `build-version --describe` and
+//! [`compute_version_string`](crate::commands::compute_version_string):
+//!
+//! - `{version}`: Tag version without the `v` prefix (`1.2.3`)
+//! - `{tag}`: Tag name as written (`v1.2.3`), empty without tags
+//! - `{distance}`: Number of commits since the tag
+//! - `{sha}`: Short SHA of HEAD
+//! - `{dirty}`: `.dirty` if the working tree is dirty, empty otherwise
+//!
+//! The default is
+//! [`DEFAULT_DESCRIBE_FORMAT`](crate::describe::DEFAULT_DESCRIBE_FORMAT); it
+//! can be overridden with `--describe-format` or the
+//! [`DESCRIBE_FORMAT_ENV`](crate::describe::DESCRIBE_FORMAT_ENV) environment
+//! variable.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{
+    Context,
+    Result,
+};
+use semver::Version;
+
+use crate::worktree;
+
+/// Default describe format, e.g. `1.2.3-dev.14+g1a2b3c4`.
+pub const DEFAULT_DESCRIBE_FORMAT: &str = "{version}-dev.{distance}+g{sha}{dirty}";
+
+/// Environment variable holding the describe format string.
+///
+/// Setting it also turns on describe mode in
+/// [`compute_version_string`](crate::commands::compute_version_string).
+pub const DESCRIBE_FORMAT_ENV: &str = "VERSION_INFO_DESCRIBE_FORMAT";
+
+/// Position of HEAD relative to the nearest semver tag.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Describe {
+    /// Name of the nearest semver tag, `None` if no tag is reachable.
+    pub tag: Option<String>,
+    /// Version of the nearest tag, "0.0.0" if no tag is reachable.
+    pub version: String,
+    /// Commits reachable from HEAD but not from the tag.
+    pub distance: usize,
+    /// Short SHA of HEAD.
+    pub sha: String,
+    /// Whether tracked files have uncommitted changes.
+    pub dirty: bool,
+}
+
+impl Describe {
+    /// Whether HEAD is exactly at the tag with a clean working tree.
+    pub fn is_exact(&self) -> bool {
+        self.tag.is_some() && self.distance == 0 && !self.dirty
+    }
+
+    /// Render the describe version using a format string.
+    ///
+    /// Returns the plain tag version if [`is_exact`](Self::is_exact).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cargo_version_info::describe::{
+    ///     DEFAULT_DESCRIBE_FORMAT,
+    ///     Describe,
+    /// };
+    ///
+    /// let describe = Describe {
+    ///     tag: Some("v1.2.3".to_string()),
+    ///     version: "1.2.3".to_string(),
+    ///     distance: 14,
+    ///     sha: "1a2b3c4".to_string(),
+    ///     dirty: true,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     describe.format(DEFAULT_DESCRIBE_FORMAT),
+    ///     "1.2.3-dev.14+g1a2b3c4.dirty"
+    /// );
+    /// ```
+    pub fn format(&self, format: &str) -> String {
+        if self.is_exact() {
+            return self.version.clone();
+        }
+
+        format
+            .replace("{version}", &self.version)
+            .replace("{tag}", self.tag.as_deref().unwrap_or(""))
+            .replace("{distance}", &self.distance.to_string())
+            .replace("{sha}", &self.sha)
+            .replace("{dirty}", if self.dirty { ".dirty" } else { "" })
+    }
+}
+
+/// Read the describe format from [`DESCRIBE_FORMAT_ENV`], if set.
+#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+pub fn describe_format_from_env() -> Option<String> {
+    std::env::var(DESCRIBE_FORMAT_ENV)
+        .ok()
+        .filter(|format| !format.trim().is_empty())
+}
+
+/// Describe HEAD of the repository containing `repo_path`.
+///
+/// `include_untracked` decides whether untracked files make the worktree
+/// dirty (see [`worktree::is_dirty`]). Tags are considered if their name (with
+/// an optional `v`/`V` prefix) is a semantic version. The nearest tagged commit
+/// is found by walking the history breadth-first from HEAD; when a commit
+/// carries several such tags the highest version wins.
+///
+/// # Errors
+///
+/// Returns an error if the repository cannot be discovered, HEAD does not
+/// point to a commit, or the history cannot be traversed.
+pub fn describe(repo_path: &Path, include_untracked: bool) -> Result<Describe> {
+    let repo = gix::discover(repo_path).with_context(|| {
+        format!(
+            "Failed to discover git repository at {}",
+            repo_path.display()
+        )
+    })?;
+
+    let head = repo.head_id().context("HEAD does not point to a commit")?;
+    let sha = head
+        .shorten()
+        .context("Failed to shorten commit SHA")?
+        .to_string();
+    let head_id = head.detach();
+    let tags = semver_tags_by_commit(&repo)?;
+
+    let mut nearest = None;
+    for info in repo
+        .rev_walk([head_id])
+        .all()
+        .context("Failed to walk commit history")?
+    {
+        let info = info.context("Failed to read commit while walking history")?;
+        if let Some((tag, version)) = tags.get(&info.id) {
+            nearest = Some((info.id, tag.clone(), version.to_string()));
+            break;
+        }
+    }
+
+    let (tag, version, distance) = match nearest {
+        Some((tag_commit, tag, version)) => {
+            let distance = repo
+                .rev_walk([head_id])
+                .with_hidden([tag_commit])
+                .all()
+                .context("Failed to walk commit history")?
+                .count();
+            (Some(tag), version, distance)
+        }
+        None => {
+            let distance = repo
+                .rev_walk([head_id])
+                .all()
+                .context("Failed to walk commit history")?
+                .count();
+            (None, "0.0.0".to_string(), distance)
+        }
+    };
+
+    // A worktree we cannot inspect (e.g. a bare repository) is not dirty
+    let dirty = worktree::is_dirty(&repo, include_untracked).unwrap_or(false);
+
+    Ok(Describe {
+        tag,
+        version,
+        distance,
+        sha,
+        dirty,
+    })
+}
+
+/// The highest semver tag on `commit_id` or one of its ancestors.
+///
+/// Tags are considered as in [`describe`]. Unlike the highest tag of the
+/// whole repository, tags on other branches are ignored.
+///
+/// # Errors
+///
+/// Returns an error if the tags or the history cannot be read.
+pub fn highest_reachable_tag(
+    repo: &gix::Repository,
+    commit_id: gix::ObjectId,
+) -> Result<Option<(String, Version)>> {
+    let tags = semver_tags_by_commit(repo)?;
+    let mut highest: Option<(String, Version)> = None;
+    for info in repo
+        .rev_walk([commit_id])
+        .all()
+        .context("Failed to walk commit history")?
+    {
+        let info = info.context("Failed to read commit while walking history")?;
+        if let Some((tag, version)) = tags.get(&info.id)
+            && highest.as_ref().is_none_or(|(_, best)| version > best)
+        {
+            highest = Some((tag.clone(), version.clone()));
+        }
+    }
+    Ok(highest)
+}
+
+/// Map tagged commits to their highest semver tag.
+fn semver_tags_by_commit(
+    repo: &gix::Repository,
+) -> Result<HashMap<gix::ObjectId, (String, Version)>> {
+    let mut tags: HashMap<gix::ObjectId, (String, Version)> = HashMap::new();
+    let references = repo.references().context("Failed to read references")?;
+
+    for reference in references
+        .tags()
+        .context("Failed to read tags")?
+        .filter_map(Result::ok)
+    {
+        let mut reference = reference;
+        let name = reference.name().shorten().to_string();
+        let stripped = name
+            .strip_prefix('v')
+            .or_else(|| name.strip_prefix('V'))
+            .unwrap_or(&name);
+        let Ok(version) = Version::parse(stripped) else {
+            continue;
+        };
+        let Ok(commit_id) = reference.peel_to_commit().map(|commit| commit.id) else {
+            continue;
+        };
+
+        match tags.get(&commit_id) {
+            Some((_, existing)) if *existing >= version => {}
+            _ => {
+                tags.insert(commit_id, (name, version));
+            }
+        }
+    }
+
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    fn commit_file(dir: &Path, content: &str) {
+        std::fs::write(dir.join("file.txt"), content).unwrap();
+        git(dir, &["add", "file.txt"]);
+        git(dir, &["commit", "-q", "-m", content]);
+    }
+
+    fn create_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.email", "test@example.com"]);
+        git(dir.path(), &["config", "user.name", "Test User"]);
+        git(dir.path(), &["config", "commit.gpgsign", "false"]);
+        git(dir.path(), &["config", "tag.gpgsign", "false"]);
+        dir
+    }
+
+    #[test]
+    fn test_highest_reachable_tag_ignores_other_branches() {
+        let dir = create_repo();
+        commit_file(dir.path(), "one");
+        git(dir.path(), &["tag", "v1.0.0"]);
+        git(dir.path(), &["branch", "release"]);
+        commit_file(dir.path(), "two");
+        git(dir.path(), &["tag", "v2.0.0"]);
+        let repo = gix::discover(dir.path()).unwrap();
+        let release = repo
+            .find_reference("refs/heads/release")
+            .unwrap()
+            .peel_to_id()
+            .unwrap()
+            .detach();
+
+        let (tag, version) = highest_reachable_tag(&repo, release).unwrap().unwrap();
+
+        assert_eq!(tag, "v1.0.0");
+        assert_eq!(version.to_string(), "1.0.0");
+    }
+
+    #[test]
+    fn test_describe_without_tags() {
+        let dir = create_repo();
+        commit_file(dir.path(), "one");
+        commit_file(dir.path(), "two");
+
+        let describe = describe(dir.path(), false).unwrap();
+
+        assert_eq!(describe.tag, None);
+        assert_eq!(describe.version, "0.0.0");
+        assert_eq!(describe.distance, 2);
+        assert!(!describe.dirty);
+    }
+
+    #[test]
+    fn test_describe_distance_from_tag() {
+        let dir = create_repo();
+        commit_file(dir.path(), "one");
+        git(dir.path(), &["tag", "-a", "v1.2.3", "-m", "v1.2.3"]);
+        commit_file(dir.path(), "two");
+        commit_file(dir.path(), "three");
+
+        let describe = describe(dir.path(), false).unwrap();
+
+        assert_eq!(describe.tag.as_deref(), Some("v1.2.3"));
+        assert_eq!(describe.version, "1.2.3");
+        assert_eq!(describe.distance, 2);
+        assert_eq!(
+            describe.format(DEFAULT_DESCRIBE_FORMAT),
+            format!("1.2.3-dev.2+g{}", describe.sha)
+        );
+    }
+
+    #[test]
+    fn test_describe_exact_tag_and_dirty() {
+        let dir = create_repo();
+        commit_file(dir.path(), "one");
+        git(dir.path(), &["tag", "v0.1.0"]);
+        git(dir.path(), &["tag", "0.2.0"]);
+        git(dir.path(), &["tag", "not-a-version"]);
+
+        let clean = describe(dir.path(), false).unwrap();
+        assert_eq!(clean.tag.as_deref(), Some("0.2.0"));
+        assert_eq!(clean.format(DEFAULT_DESCRIBE_FORMAT), "0.2.0");
+
+        std::fs::write(dir.path().join("file.txt"), "modified").unwrap();
+        let dirty = describe(dir.path(), false).unwrap();
+        assert!(dirty.dirty);
+        assert_eq!(
+            dirty.format(DEFAULT_DESCRIBE_FORMAT),
+            format!("0.2.0-dev.0+g{}.dirty", dirty.sha)
+        );
+    }
+
+    #[test]
+    fn test_format_placeholders() {
+        let describe = Describe {
+            tag: Some("v1.0.0".to_string()),
+            version: "1.0.0".to_string(),
+            distance: 3,
+            sha: "abc1234".to_string(),
+            dirty: false,
+        };
+
+        assert_eq!(
+            describe.format("{tag}-{distance}-g{sha}{dirty}"),
+            "v1.0.0-3-gabc1234"
+        );
+    }
+}
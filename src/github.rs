@@ -1,44 +1,208 @@
 //! GitHub API integration for version queries.
 
 use std::env;
+use std::path::{
+    Path,
+    PathBuf,
+};
 
 use anyhow::{
     Context,
     Result,
 };
+use cargo_plugin_utils::common::{
+    find_package,
+    get_owner_repo,
+};
+#[cfg(feature = "github")]
+use semver::Version;
 
+use crate::registry;
 use crate::version::{
     format_version,
     increment_patch,
     parse_version,
 };
 
+/// Default GitHub REST API base URL (github.com).
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Resolve the GitHub REST API base URL.
+///
+/// Uses the explicit value if given, otherwise the `GITHUB_API_URL`
+/// environment variable (set automatically by GitHub Actions, including on
+/// GitHub Enterprise Server runners), otherwise [`DEFAULT_GITHUB_API_URL`].
+/// Empty values are ignored and a trailing slash is stripped.
+#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+pub fn resolve_api_url(api_url: Option<&str>) -> String {
+    let env_url = env::var("GITHUB_API_URL").ok();
+    api_url
+        .or(env_url.as_deref())
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .unwrap_or(DEFAULT_GITHUB_API_URL)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Build an octocrab client for the given API base URL.
+///
+/// The base URL may contain a path (e.g. `https://ghe.example.com/api/v3`
+/// for GitHub Enterprise Server); octocrab preserves it when joining request
+/// paths.
+#[cfg(feature = "github")]
+fn build_client(token: Option<&str>, api_url: &str) -> Result<octocrab::Octocrab> {
+    let builder = octocrab::OctocrabBuilder::new()
+        .base_uri(api_url)
+        .with_context(|| format!("Invalid GitHub API URL: {}", api_url))?;
+
+    let builder = match token {
+        Some(token) => builder.personal_token(token.to_string()),
+        // For public repos, we can use octocrab without a token
+        None => builder,
+    };
+
+    builder
+        .build()
+        .context("Failed to create GitHub API client")
+}
+
+/// Number of releases requested per page when scanning release lists.
+#[cfg(feature = "github")]
+const RELEASES_PER_PAGE: u8 = 100;
+
+/// Criteria for selecting "the latest" GitHub release.
+///
+/// The default selects the release GitHub lists first (usually the most
+/// recently created one), including drafts and pre-releases, which matches
+/// the historical behavior of `latest`.
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseFilter {
+    /// Skip draft releases.
+    pub exclude_drafts: bool,
+    /// Skip releases marked as pre-release.
+    pub exclude_prereleases: bool,
+    /// Only consider releases whose tag starts with this prefix (e.g.
+    /// `my-crate-v` in a monorepo). The prefix is stripped before parsing
+    /// the version.
+    pub tag_prefix: Option<String>,
+    /// Pick the highest semantic version across all pages instead of the
+    /// first matching release.
+    pub highest_semver: bool,
+}
+
+impl ReleaseFilter {
+    /// Whether this filter can be answered from the first release alone.
+    #[cfg(feature = "github")]
+    fn is_first_release_only(&self) -> bool {
+        !self.exclude_drafts
+            && !self.exclude_prereleases
+            && self.tag_prefix.is_none()
+            && !self.highest_semver
+    }
+
+    /// Extract the version from a release if it passes the filter.
+    #[cfg(feature = "github")]
+    fn version_of(&self, release: &octocrab::models::repos::Release) -> Option<String> {
+        if (self.exclude_drafts && release.draft)
+            || (self.exclude_prereleases && release.prerelease)
+        {
+            return None;
+        }
+
+        let tag_name = match &self.tag_prefix {
+            Some(prefix) => release.tag_name.strip_prefix(prefix.as_str())?,
+            None => release.tag_name.as_str(),
+        };
+        let version = tag_name.strip_prefix('v').unwrap_or(tag_name);
+        let version = version.strip_prefix('V').unwrap_or(version);
+
+        Some(version.to_string())
+    }
+}
+
+/// A GitHub release selected by [`get_latest_release`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ReleaseInfo {
+    /// Version parsed from the tag (prefix and `v` stripped).
+    pub version: String,
+    /// Tag name of the release as published.
+    pub tag_name: String,
+    /// Release title, if set.
+    pub name: Option<String>,
+    /// Web URL of the release page.
+    pub url: String,
+    /// Publication timestamp (RFC 3339), `None` for drafts.
+    pub published_at: Option<String>,
+    /// Whether the release is marked as a pre-release.
+    pub prerelease: bool,
+    /// Whether the release is a draft.
+    pub draft: bool,
+}
+
+#[cfg(feature = "github")]
+impl ReleaseInfo {
+    fn from_release(release: &octocrab::models::repos::Release, version: String) -> Self {
+        Self {
+            version,
+            tag_name: release.tag_name.clone(),
+            name: release.name.clone().filter(|name| !name.is_empty()),
+            url: release.html_url.to_string(),
+            published_at: release.published_at.map(|date| date.to_rfc3339()),
+            prerelease: release.prerelease,
+            draft: release.draft,
+        }
+    }
+}
+
 /// Get the latest published release version from GitHub.
 ///
 /// Uses the GitHub API via octocrab. Works for public repos without a token
 /// (with rate limits). For private repos, a token is required (automatically
 /// detected from GITHUB_TOKEN env var if not provided).
-#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+///
+/// `api_url` selects the REST API base URL (see [`resolve_api_url`]), which
+/// allows querying GitHub Enterprise Server instances.
 pub async fn get_latest_release_version(
     owner: &str,
     repo: &str,
     github_token: Option<&str>,
+    api_url: Option<&str>,
 ) -> Result<Option<String>> {
+    let release = get_latest_release(
+        owner,
+        repo,
+        github_token,
+        api_url,
+        &ReleaseFilter::default(),
+    )
+    .await?;
+    Ok(release.map(|release| release.version))
+}
+
+/// Get the latest GitHub release matching a [`ReleaseFilter`].
+///
+/// Returns `None` if the repository has no (matching) releases. Token and
+/// API URL are resolved as in [`get_latest_release_version`].
+#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+pub async fn get_latest_release(
+    owner: &str,
+    repo: &str,
+    github_token: Option<&str>,
+    api_url: Option<&str>,
+    filter: &ReleaseFilter,
+) -> Result<Option<ReleaseInfo>> {
     // Auto-detect token from environment if not provided
     let env_token = env::var("GITHUB_TOKEN").ok();
     let token = github_token.or(env_token.as_deref());
+    let api_url = resolve_api_url(api_url);
 
-    // Try with token first (required for private repos, better rate limits for
-    // public)
-    let result = if let Some(token) = token {
-        get_latest_release_via_api(owner, repo, Some(token)).await
-    } else {
-        // Try without token (public repos only)
-        get_latest_release_via_api(owner, repo, None).await
-    };
+    // Uses the token when available (required for private repos, better rate
+    // limits for public); otherwise works for public repos only
+    let result = get_latest_release_via_api(owner, repo, token, &api_url, filter).await;
 
     match result {
-        Ok(version) => Ok(Some(version)),
+        Ok(release) => Ok(Some(release)),
         Err(e) => {
             let error_msg = e.to_string();
             // If no releases found, return None instead of error
@@ -73,39 +237,85 @@ pub async fn get_latest_release_version(
 ///
 /// Works for public repositories even without a token (with rate limits).
 /// If a token is provided, uses it for authentication (higher rate limits).
+///
+/// With the default filter only the first release is requested. Otherwise
+/// release pages are scanned until a match is found, or to the end when
+/// `highest_semver` is set.
+#[cfg(feature = "github")]
 async fn get_latest_release_via_api(
     owner: &str,
     repo: &str,
     token: Option<&str>,
-) -> Result<String> {
-    let octocrab = if let Some(token) = token {
-        octocrab::OctocrabBuilder::new()
-            .personal_token(token.to_string())
-            .build()
-            .context("Failed to create GitHub API client")?
-    } else {
-        // For public repos, we can use octocrab without a token
-        octocrab::Octocrab::builder()
-            .build()
-            .context("Failed to create GitHub API client")?
-    };
+    api_url: &str,
+    filter: &ReleaseFilter,
+) -> Result<ReleaseInfo> {
+    let octocrab = build_client(token, api_url)?;
+    let repo_handler = octocrab.repos(owner, repo);
+    let releases_handler = repo_handler.releases();
+
+    if filter.is_first_release_only() {
+        let releases = releases_handler
+            .list()
+            .per_page(1)
+            .send()
+            .await
+            .context("Failed to query GitHub releases")?;
+
+        let release = releases.items.first().context("No releases found")?;
+        let version = filter.version_of(release).context("No releases found")?;
+        return Ok(ReleaseInfo::from_release(release, version));
+    }
 
-    let releases = octocrab
-        .repos(owner, repo)
-        .releases()
-        .list()
-        .per_page(1)
-        .send()
-        .await
-        .context("Failed to query GitHub releases")?;
+    let mut best: Option<(Version, ReleaseInfo)> = None;
+    let mut page_number: u32 = 1;
+    loop {
+        let page = releases_handler
+            .list()
+            .per_page(RELEASES_PER_PAGE)
+            .page(page_number)
+            .send()
+            .await
+            .context("Failed to query GitHub releases")?;
+
+        for release in &page.items {
+            let Some(version) = filter.version_of(release) else {
+                continue;
+            };
+            if !filter.highest_semver {
+                return Ok(ReleaseInfo::from_release(release, version));
+            }
+            let Ok(semver) = Version::parse(&version) else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|(best_semver, _)| semver > *best_semver)
+            {
+                best = Some((semver, ReleaseInfo::from_release(release, version)));
+            }
+        }
 
-    let release = releases.items.first().context("No releases found")?;
+        if page.items.len() < usize::from(RELEASES_PER_PAGE) {
+            break;
+        }
+        page_number += 1;
+    }
 
-    let tag_name = release.tag_name.as_str();
-    let version = tag_name.strip_prefix('v').unwrap_or(tag_name);
-    let version = version.strip_prefix('V').unwrap_or(version);
+    best.map(|(_, release)| release)
+        .context("No releases found")
+}
 
-    Ok(version.to_string())
+/// Get latest release via GitHub API (unavailable without the `github`
+/// feature).
+#[cfg(not(feature = "github"))]
+async fn get_latest_release_via_api(
+    _owner: &str,
+    _repo: &str,
+    _token: Option<&str>,
+    _api_url: &str,
+    _filter: &ReleaseFilter,
+) -> Result<ReleaseInfo> {
+    anyhow::bail!("GitHub releases require the `github` feature of cargo-version-info")
 }
 
 /// Get the latest version from git tags.
@@ -113,25 +323,46 @@ async fn get_latest_release_via_api(
 /// Queries git tags in the current repository to find the latest semantic
 /// version tag. Returns None if no version tags exist.
 fn get_latest_git_tag_version() -> Result<Option<String>> {
+    Ok(get_latest_git_tag(None)?.map(|(_, version)| version))
+}
+
+/// Get the name and version of the latest semantic version tag.
+///
+/// With `tag_prefix`, only tags starting with the prefix are considered and
+/// the prefix is stripped before the version is parsed (e.g. `app-v` turns
+/// `app-v1.2.0-rc.1` into `1.2.0-rc.1`). Returns None if no version tags
+/// exist.
+///
+/// # Errors
+///
+/// Returns an error if the repository or its tags cannot be read.
+pub fn get_latest_git_tag(tag_prefix: Option<&str>) -> Result<Option<(String, String)>> {
     let cwd = std::env::current_dir().context("Failed to get current directory")?;
     let repo = gix::discover(cwd)
         .context("Failed to discover git repository. Ensure you're in a git repository.")?;
 
-    let mut version_tags: Vec<(String, (u32, u32, u32))> = repo
+    let mut version_tags: Vec<_> = repo
         .references()?
         .prefixed("refs/tags/")?
         .filter_map(|r: Result<gix::Reference<'_>, _>| r.ok())
         .filter_map(|r| {
             let name_full = r.name().as_bstr().to_string();
             let name = name_full.strip_prefix("refs/tags/").unwrap_or(&name_full);
-            let version_str = name
+            let unprefixed = match tag_prefix {
+                Some(prefix) => name.strip_prefix(prefix)?,
+                None => name,
+            };
+            let version_str = unprefixed
                 .strip_prefix('v')
-                .or_else(|| name.strip_prefix('V'))
-                .unwrap_or(name);
+                .or_else(|| unprefixed.strip_prefix('V'))
+                .unwrap_or(unprefixed);
 
             // Try to parse as semantic version
             if let Ok((major, minor, patch)) = parse_version(version_str) {
-                Some((name.to_string(), (major, minor, patch)))
+                Some((
+                    (name.to_string(), version_str.to_string()),
+                    (major, minor, patch),
+                ))
             } else {
                 None
             }
@@ -141,32 +372,137 @@ fn get_latest_git_tag_version() -> Result<Option<String>> {
     // Sort tags by semantic version (major, minor, patch)
     version_tags.sort_by_key(|a| a.1);
 
-    Ok(version_tags
-        .last()
-        .map(|(tag_name, _): &(String, (u32, u32, u32))| {
-            tag_name
-                .strip_prefix('v')
-                .or_else(|| tag_name.strip_prefix('V'))
-                .unwrap_or(tag_name)
-                .to_string()
-        }))
+    Ok(version_tags.pop().map(|(tag, _)| tag))
 }
 
-/// Calculate next patch version from latest git tag.
+/// Where the latest released version is read from.
 ///
-/// Queries git tags in the current repository (not GitHub releases) to find
-/// the latest version. If no tags exist, returns "0.0.0" as latest and
-/// "0.0.1" as next.
+/// Used by `next` and `bump --auto` to decide which version the next patch
+/// version is computed from, and by `latest` to pick what to report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionSource {
+    /// Highest semver git tag in the local repository (default).
+    ///
+    /// Requires tags to be present locally; shallow CI clones should use
+    /// `fetch-depth: 0` or another source.
+    #[default]
+    Tags,
+    /// Latest release published on GitHub (via the REST API).
+    Releases,
+    /// Current version in Cargo.toml.
+    CargoToml,
+    /// Latest non-yanked version published to crates.io.
+    Registry,
+}
+
+impl VersionSource {
+    /// Name of the source as used on the command line and in JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VersionSource::Tags => "tags",
+            VersionSource::Releases => "releases",
+            VersionSource::CargoToml => "cargo-toml",
+            VersionSource::Registry => "registry",
+        }
+    }
+}
+
+impl std::fmt::Display for VersionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Inputs for looking up the latest version from a [`VersionSource`].
+///
+/// Only the fields relevant to the selected source are consulted: `owner`,
+/// `repo`, `github_token` and `github_api_url` for
+/// [`VersionSource::Releases`], `manifest_path` for
+/// [`VersionSource::CargoToml`] and [`VersionSource::Registry`], and
+/// `registry`/`index` for [`VersionSource::Registry`].
+#[derive(Debug, Clone, Default)]
+pub struct SourceContext {
+    /// GitHub repository owner (auto-detected when `None`).
+    pub owner: Option<String>,
+    /// GitHub repository name (auto-detected when `None`).
+    pub repo: Option<String>,
+    /// GitHub token (falls back to `GITHUB_TOKEN`).
+    pub github_token: Option<String>,
+    /// GitHub REST API base URL (see [`resolve_api_url`]).
+    pub github_api_url: Option<String>,
+    /// Path to Cargo.toml (defaults to the current package).
+    pub manifest_path: Option<PathBuf>,
+    /// Name of an alternative registry declared in `.cargo/config.toml`.
+    pub registry: Option<String>,
+    /// Registry index URL (overrides `registry`).
+    pub index: Option<String>,
+}
+
+impl SourceContext {
+    /// Resolve the registry index for [`VersionSource::Registry`].
+    ///
+    /// Named registries are looked up in cargo configuration starting at
+    /// the directory of `manifest_dir`.
+    pub fn registry_index(&self, manifest_dir: &Path) -> Result<registry::RegistryIndex> {
+        registry::RegistryIndex::resolve(
+            self.registry.as_deref(),
+            self.index.as_deref(),
+            manifest_dir,
+        )
+    }
+}
+
+/// Get the latest released version from the given source.
+///
+/// Returns `None` if the source has no version yet (no tags, no GitHub
+/// releases, or the crate has never been published).
+pub async fn get_latest_version(
+    source: VersionSource,
+    context: &SourceContext,
+) -> Result<Option<String>> {
+    match source {
+        VersionSource::Tags => get_latest_git_tag_version(),
+        VersionSource::Releases => {
+            let (owner, repo) = get_owner_repo(context.owner.clone(), context.repo.clone())?;
+            get_latest_release_version(
+                &owner,
+                &repo,
+                context.github_token.as_deref(),
+                context.github_api_url.as_deref(),
+            )
+            .await
+        }
+        VersionSource::CargoToml => {
+            let package = find_package(context.manifest_path.as_deref())?;
+            Ok(Some(package.version.to_string()))
+        }
+        VersionSource::Registry => {
+            let package = find_package(context.manifest_path.as_deref())?;
+            let manifest_dir = package
+                .manifest_path
+                .parent()
+                .map(|dir| dir.as_std_path().to_path_buf())
+                .unwrap_or_default();
+            let index = context.registry_index(&manifest_dir)?;
+            registry::get_latest_published_version(&index, &package.name).await
+        }
+    }
+}
+
+/// Calculate next patch version from the latest version of a source.
+///
+/// Looks up the latest version via [`get_latest_version`] and increments
+/// the patch number. If the source has no version yet, returns "0.0.0" as
+/// latest and "0.0.1" as next.
 pub async fn calculate_next_version(
-    _owner: &str,
-    _repo: &str,
-    _github_token: Option<&str>,
+    source: VersionSource,
+    context: &SourceContext,
 ) -> Result<(String, String)> {
-    // Get latest version from git tags (not GitHub releases)
-    let latest_version_str = match get_latest_git_tag_version()? {
+    let latest_version_str = match get_latest_version(source, context).await? {
         Some(v) => v,
         None => {
-            // No tags yet, start at 0.0.1
+            // Nothing released yet, start at 0.0.1
             return Ok(("0.0.0".to_string(), "0.0.1".to_string()));
         }
     };
@@ -180,14 +516,123 @@ pub async fn calculate_next_version(
     Ok((latest_version_str, next_version))
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "github"))]
 mod tests {
+    use std::io::{
+        BufRead,
+        BufReader,
+        Write,
+    };
+    use std::net::{
+        TcpListener,
+        TcpStream,
+    };
     use std::process::Command;
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
 
     use tempfile::TempDir;
 
     use super::*;
 
+    /// Minimal HTTP stub standing in for the GitHub REST API.
+    ///
+    /// Answers every request with the `(status, body)` returned by the
+    /// handler for the request target, and records each request line (e.g.
+    /// `GET /api/v3/repos/o/r/releases?per_page=1 HTTP/1.1`).
+    struct StubServer {
+        url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StubServer {
+        fn start(handler: impl Fn(&str) -> (&'static str, String) + Send + 'static) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let recorded = Arc::clone(&requests);
+
+            std::thread::spawn(move || {
+                for stream in listener.incoming().map_while(Result::ok) {
+                    handle_stub_request(stream, &handler, &recorded);
+                }
+            });
+
+            Self { url, requests }
+        }
+
+        fn with_body(status: &'static str, body: String) -> Self {
+            Self::start(move |_| (status, body.clone()))
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn handle_stub_request(
+        mut stream: TcpStream,
+        handler: &impl Fn(&str) -> (&'static str, String),
+        recorded: &Mutex<Vec<String>>,
+    ) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).is_err() {
+            return;
+        }
+
+        // Drain headers up to the blank line
+        let mut header = String::new();
+        while reader.read_line(&mut header).is_ok() && !header.trim().is_empty() {
+            header.clear();
+        }
+
+        let request_line = request_line.trim().to_string();
+        let target = request_line.split(' ').nth(1).unwrap_or("/").to_string();
+        recorded.lock().unwrap().push(request_line);
+
+        let (status, body) = handler(&target);
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes());
+    }
+
+    fn release_json(tag_name: &str) -> String {
+        release_json_with(tag_name, false, false)
+    }
+
+    fn release_json_with(tag_name: &str, draft: bool, prerelease: bool) -> String {
+        format!(
+            r#"{{
+                "url": "https://ghe.example.com/api/v3/repos/o/r/releases/1",
+                "html_url": "https://ghe.example.com/o/r/releases/tag/{tag_name}",
+                "assets_url": "https://ghe.example.com/api/v3/repos/o/r/releases/1/assets",
+                "upload_url": "https://ghe.example.com/api/uploads/repos/o/r/releases/1/assets",
+                "tarball_url": null,
+                "zipball_url": null,
+                "id": 1,
+                "node_id": "RE_1",
+                "tag_name": "{tag_name}",
+                "target_commitish": "main",
+                "name": "{tag_name}",
+                "body": null,
+                "draft": {draft},
+                "prerelease": {prerelease},
+                "created_at": "2025-01-01T00:00:00Z",
+                "published_at": "2025-01-01T00:00:00Z",
+                "author": null,
+                "assets": []
+            }}"#
+        )
+    }
+
     fn create_test_git_repo_with_tags(tags: &[&str]) -> TempDir {
         let dir = tempfile::tempdir().unwrap();
 
@@ -289,6 +734,23 @@ mod tests {
         assert_eq!(result, Some("0.3.0".to_string()));
     }
 
+    #[test]
+    fn test_get_latest_git_tag_with_prefixed_prerelease() {
+        let _dir = create_test_git_repo_with_tags(&["app-v1.1.0", "app-v1.2.0-rc.1", "v9.0.0"]);
+        let dir_path = _dir.path().to_path_buf();
+        let original_dir = std::env::current_dir().unwrap();
+
+        std::env::set_current_dir(&dir_path).unwrap();
+        let result = get_latest_git_tag(Some("app-v")).unwrap();
+        std::env::set_current_dir(original_dir).unwrap();
+
+        // The tag name is reported as is, with prefix and pre-release suffix
+        assert_eq!(
+            result,
+            Some(("app-v1.2.0-rc.1".to_string(), "1.2.0-rc.1".to_string()))
+        );
+    }
+
     #[tokio::test]
     async fn test_calculate_next_version_no_tags() {
         let _dir = create_test_git_repo_with_tags(&[]);
@@ -296,7 +758,9 @@ mod tests {
         let original_dir = std::env::current_dir().unwrap();
 
         std::env::set_current_dir(&dir_path).unwrap();
-        let (latest, next) = calculate_next_version("test", "repo", None).await.unwrap();
+        let (latest, next) = calculate_next_version(VersionSource::Tags, &SourceContext::default())
+            .await
+            .unwrap();
         std::env::set_current_dir(original_dir).unwrap();
 
         assert_eq!(latest, "0.0.0");
@@ -310,19 +774,236 @@ mod tests {
         let original_dir = std::env::current_dir().unwrap();
 
         std::env::set_current_dir(&dir_path).unwrap();
-        let (latest, next) = calculate_next_version("test", "repo", None).await.unwrap();
+        let (latest, next) = calculate_next_version(VersionSource::Tags, &SourceContext::default())
+            .await
+            .unwrap();
         std::env::set_current_dir(original_dir).unwrap();
 
         assert_eq!(latest, "0.1.2");
         assert_eq!(next, "0.1.3");
     }
 
+    #[test]
+    fn test_resolve_api_url_explicit() {
+        assert_eq!(
+            resolve_api_url(Some("https://ghe.example.com/api/v3/")),
+            "https://ghe.example.com/api/v3"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_version_custom_api_url() {
+        let server = StubServer::with_body("200 OK", format!("[{}]", release_json("v1.4.2")));
+        let api_url = format!("{}/api/v3", server.url);
+
+        let version = get_latest_release_version("o", "r", Some("token"), Some(&api_url))
+            .await
+            .unwrap();
+
+        assert_eq!(version, Some("1.4.2".to_string()));
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(
+            requests[0].starts_with("GET /api/v3/repos/o/r/releases"),
+            "unexpected request: {}",
+            requests[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_version_custom_api_url_no_releases() {
+        let server = StubServer::with_body("200 OK", "[]".to_string());
+
+        let version = get_latest_release_version("o", "r", Some("token"), Some(&server.url))
+            .await
+            .unwrap();
+
+        assert_eq!(version, None);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_version_custom_api_url_server_error() {
+        let server = StubServer::with_body(
+            "500 Internal Server Error",
+            r#"{"message":"boom"}"#.to_string(),
+        );
+
+        let result = get_latest_release_version("o", "r", Some("token"), Some(&server.url)).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_excludes_drafts_and_prereleases() {
+        let releases = [
+            release_json_with("v2.0.0", true, false),
+            release_json_with("v2.0.0-rc.1", false, true),
+            release_json("v1.9.0"),
+        ];
+        let server = StubServer::with_body("200 OK", format!("[{}]", releases.join(",")));
+        let filter = ReleaseFilter {
+            exclude_drafts: true,
+            exclude_prereleases: true,
+            ..Default::default()
+        };
+
+        let release = get_latest_release("o", "r", Some("token"), Some(&server.url), &filter)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(release.version, "1.9.0");
+        assert!(!release.prerelease);
+        assert_eq!(
+            release.url,
+            "https://ghe.example.com/o/r/releases/tag/v1.9.0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_default_filter_takes_first() {
+        let releases = [
+            release_json_with("v2.0.0-rc.1", false, true),
+            release_json("v1.9.0"),
+        ];
+        let server = StubServer::with_body("200 OK", format!("[{}]", releases.join(",")));
+
+        let release = get_latest_release(
+            "o",
+            "r",
+            Some("token"),
+            Some(&server.url),
+            &ReleaseFilter::default(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(release.version, "2.0.0-rc.1");
+        assert!(release.prerelease);
+        assert!(server.requests()[0].contains("per_page=1"));
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_tag_prefix() {
+        let releases = [
+            release_json("other-v3.0.0"),
+            release_json("my-crate-v0.4.0"),
+            release_json("my-crate-v0.3.0"),
+        ];
+        let server = StubServer::with_body("200 OK", format!("[{}]", releases.join(",")));
+        let filter = ReleaseFilter {
+            tag_prefix: Some("my-crate-v".to_string()),
+            ..Default::default()
+        };
+
+        let release = get_latest_release("o", "r", Some("token"), Some(&server.url), &filter)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(release.version, "0.4.0");
+        assert_eq!(release.tag_name, "my-crate-v0.4.0");
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_highest_semver_across_pages() {
+        // Page 1 is full (forcing a second request); the highest version is
+        // only on page 2.
+        let first_page: Vec<String> = (0..usize::from(RELEASES_PER_PAGE))
+            .map(|patch| release_json(&format!("v1.0.{}", patch)))
+            .collect();
+        let first_page = format!("[{}]", first_page.join(","));
+        let second_page = format!("[{},{}]", release_json("v0.9.0"), release_json("v1.2.0"));
+        let server = StubServer::start(move |target| {
+            if target.contains("page=2") {
+                ("200 OK", second_page.clone())
+            } else {
+                ("200 OK", first_page.clone())
+            }
+        });
+        let filter = ReleaseFilter {
+            highest_semver: true,
+            ..Default::default()
+        };
+
+        let release = get_latest_release("o", "r", Some("token"), Some(&server.url), &filter)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(release.version, "1.2.0");
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_no_match() {
+        let server = StubServer::with_body(
+            "200 OK",
+            format!("[{}]", release_json_with("v1.0.0", true, false)),
+        );
+        let filter = ReleaseFilter {
+            exclude_drafts: true,
+            ..Default::default()
+        };
+
+        let release = get_latest_release("o", "r", Some("token"), Some(&server.url), &filter)
+            .await
+            .unwrap();
+
+        assert_eq!(release, None);
+    }
+
+    #[tokio::test]
+    async fn test_calculate_next_version_from_releases() {
+        let server = StubServer::with_body("200 OK", format!("[{}]", release_json("v2.3.4")));
+        let context = SourceContext {
+            owner: Some("o".to_string()),
+            repo: Some("r".to_string()),
+            github_token: Some("token".to_string()),
+            github_api_url: Some(server.url.clone()),
+            ..Default::default()
+        };
+
+        let (latest, next) = calculate_next_version(VersionSource::Releases, &context)
+            .await
+            .unwrap();
+
+        assert_eq!(latest, "2.3.4");
+        assert_eq!(next, "2.3.5");
+    }
+
+    #[tokio::test]
+    async fn test_calculate_next_version_from_cargo_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test\"\nversion = \"0.4.7\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("lib.rs"), "").unwrap();
+        let context = SourceContext {
+            manifest_path: Some(dir.path().join("Cargo.toml")),
+            ..Default::default()
+        };
+
+        let (latest, next) = calculate_next_version(VersionSource::CargoToml, &context)
+            .await
+            .unwrap();
+
+        assert_eq!(latest, "0.4.7");
+        assert_eq!(next, "0.4.8");
+    }
+
     #[tokio::test]
     #[ignore] // Requires network access
     async fn test_get_latest_release_via_api() {
         // This test requires network access
         // Only run manually
-        if let Ok(Some(version)) = get_latest_release_version("rust-lang", "rust", None).await {
+        if let Ok(Some(version)) = get_latest_release_version("rust-lang", "rust", None, None).await
+        {
             println!("Latest rust release: {}", version);
         }
     }
@@ -1,37 +1,89 @@
 #![doc = include_str!("../README.md")]
 
+/// Build metadata emission for `build.rs`.
+pub mod build_script;
 /// Command implementations and argument types.
 ///
+/// Each command function prints its result. To use the results in-process,
+/// call the typed functions instead; the commands are formatters on top of
+/// them, and all returned structs implement `serde::Serialize`:
+///
+/// - [`commands::compute_build_version`] → [`commands::BuildVersion`]
+/// - [`commands::current_version`] → [`commands::CurrentVersion`]
+/// - [`commands::next_version`] → [`commands::NextVersion`]
+/// - [`commands::changed_status`] → [`commands::ChangedStatus`]
+///
 /// # Example: Using in `build.rs` to set `CARGO_PKG_VERSION`
 ///
 /// Add `cargo-version-info` as a build dependency in your `Cargo.toml`:
 ///
 /// ```toml
 /// [build-dependencies]
-/// cargo-version-info = { version = "0.0.1", default-features = false }
+/// cargo-version-info = { version = "0.0.17", default-features = false }
 /// ```
 ///
+/// Without default features only the build version computation,
+/// [`build_script`], [`describe`], [`priority`], [`version`] and [`worktree`]
+/// are compiled, with no async runtime, HTTP client or CLI dependencies. This
+/// also skips the GitHub API step below; enable `github` to keep it.
+///
 /// Then in your `build.rs`:
 ///
 /// ```no_run
-/// use cargo_version_info::commands::compute_version_string;
+/// use cargo_version_info::build_script::BuildMetadata;
 ///
 /// fn main() {
-///     if let Ok(version) = compute_version_string(".") {
-///         println!("cargo:rustc-env=CARGO_PKG_VERSION={}", version);
-///         println!("cargo:rerun-if-changed=.git/HEAD");
-///         println!("cargo:rerun-if-changed=.git/refs");
+///     if let Ok(metadata) = BuildMetadata::collect(".") {
+///         println!("cargo:rustc-env=CARGO_PKG_VERSION={}", metadata.version);
+///         // Also emits VERSION_INFO_* variables and rerun-if-changed lines
+///         metadata.emit();
 ///     }
 /// }
 /// ```
 ///
-/// This will override `CARGO_PKG_VERSION` with the computed version based on:
+/// This will override `CARGO_PKG_VERSION` with the version from
+/// [`commands::compute_version_string`], based on:
 /// 1. `BUILD_VERSION` env var (highest priority, set by CI)
-/// 2. GitHub API (in GitHub Actions)
+/// 2. GitHub API (in GitHub Actions, with the `github` feature)
 /// 3. Cargo.toml version + git SHA
 /// 4. Git SHA fallback (`0.0.0-dev-<sha>`)
+///
+/// Set `VERSION_INFO_DESCRIBE_FORMAT` (e.g. to
+/// `{version}-dev.{distance}+g{sha}{dirty}`) to replace steps 3 and 4 with a
+/// `git describe`-style version.
+///
+/// Set `VERSION_INFO_DIRTY_SUFFIX` (e.g. to `-dirty`) to mark versions built
+/// from a working tree with uncommitted changes, or expose the dirty state
+/// itself with [`commands::compute_dirty`]:
+///
+/// ```no_run
+/// use cargo_version_info::commands::compute_dirty;
+///
+/// fn main() {
+///     if let Ok(dirty) = compute_dirty(".") {
+///         println!("cargo:rustc-env=VERSION_INFO_DIRTY={}", dirty);
+///         println!("cargo:rerun-if-changed=.git/index");
+///     }
+/// }
+/// ```
+///
+/// For the full set of build metadata (SHA, branch, timestamps, compiler,
+/// target and accurate rebuild triggers), see [`build_script`].
 pub mod commands;
+/// `git describe`-style versions.
+pub mod describe;
 /// GitHub helpers.
+#[cfg(feature = "cli")]
 pub mod github;
+/// Build version priority chain shared with `build.rs`.
+pub mod priority;
+/// Crate registry helpers.
+#[cfg(feature = "cli")]
+pub mod registry;
+/// Helpers shared by the unit tests.
+#[cfg(test)]
+mod test_util;
 /// Version helpers.
 pub mod version;
+/// Working tree status helpers.
+pub mod worktree;
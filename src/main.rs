@@ -1,7 +1,7 @@
 //! Cargo subcommand for unified version management.
 //!
 //! This tool provides a single source of truth for version operations:
-//! - Calculate next version from GitHub releases
+//! - Calculate next version from git tags, GitHub releases, or the registry
 //! - Read current version from Cargo.toml
 //! - Compare versions
 //! - Generate dev versions from git SHA
@@ -15,12 +15,19 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use cargo_version_info::commands;
+#[cfg(feature = "badges")]
 use cargo_version_info::commands::{
     BadgeArgs,
+    ReleasePageArgs,
+    UpdateReadmeArgs,
+};
+use cargo_version_info::commands::{
     BuildVersionArgs,
     BumpArgs,
     ChangedArgs,
     ChangelogArgs,
+    CheckDocsArgs,
+    CheckPublishArgs,
     CompareArgs,
     CurrentArgs,
     DevArgs,
@@ -30,10 +37,10 @@ use cargo_version_info::commands::{
     PostBumpHookArgs,
     PrLogArgs,
     PreBumpHookArgs,
-    ReleasePageArgs,
+    PublishPlanArgs,
     RustToolchainArgs,
     TagArgs,
-    UpdateReadmeArgs,
+    UnbumpArgs,
 };
 use clap::{
     ArgAction,
@@ -85,7 +92,8 @@ struct VersionInfoCli {
 
 #[derive(Parser, Debug)]
 enum VersionInfoCommand {
-    /// Calculate next patch version from latest GitHub release
+    /// Calculate next patch version from the latest release (git tags by
+    /// default)
     #[command(name = "next")]
     Next(NextArgs),
     /// Get current version from Cargo.toml
@@ -115,9 +123,21 @@ enum VersionInfoCommand {
     /// Check if Cargo.toml version changed since last git tag
     #[command(name = "changed")]
     Changed(ChangedArgs),
+    /// Check that html_root_url and doc comment versions match Cargo.toml
+    #[command(name = "check-docs")]
+    CheckDocs(CheckDocsArgs),
+    /// Check that the package is ready to be published
+    #[command(name = "check-publish")]
+    CheckPublish(CheckPublishArgs),
+    /// Print the order in which workspace crates must be published
+    #[command(name = "publish-plan")]
+    PublishPlan(PublishPlanArgs),
     /// Bump version in Cargo.toml and commit changes (does not create tags)
     #[command(name = "bump")]
     Bump(BumpArgs),
+    /// Undo the last version bump commit (reset, or revert if pushed)
+    #[command(name = "unbump")]
+    Unbump(UnbumpArgs),
     /// Pre-bump hook for cog integration (verifies state before bumping)
     #[command(name = "pre-bump-hook")]
     PreBumpHook(PreBumpHookArgs),
@@ -131,12 +151,15 @@ enum VersionInfoCommand {
     #[command(name = "pr-log")]
     PrLog(PrLogArgs),
     /// Generate complete release page with badges, PR log, and changelog
+    #[cfg(feature = "badges")]
     #[command(name = "release-page")]
     ReleasePage(ReleasePageArgs),
     /// Generate badges for quality metrics
+    #[cfg(feature = "badges")]
     #[command(name = "badge")]
     Badge(BadgeArgs),
     /// Update README with badges
+    #[cfg(feature = "badges")]
     #[command(name = "update-readme")]
     UpdateReadme(UpdateReadmeArgs),
     /// Compute effective version (same as --version)
@@ -208,13 +231,20 @@ fn main() -> Result<()> {
                 VersionInfoCommand::Dioxus(args) => commands::dioxus(args),
                 VersionInfoCommand::BuildVersion(args) => commands::build_version(args),
                 VersionInfoCommand::Changed(args) => commands::changed(args),
+                VersionInfoCommand::CheckDocs(args) => commands::check_docs(args),
+                VersionInfoCommand::CheckPublish(args) => commands::check_publish(args),
+                VersionInfoCommand::PublishPlan(args) => commands::publish_plan(args),
                 VersionInfoCommand::Bump(args) => commands::bump(args),
+                VersionInfoCommand::Unbump(args) => commands::unbump(args),
                 VersionInfoCommand::PreBumpHook(args) => commands::pre_bump_hook(args),
                 VersionInfoCommand::PostBumpHook(args) => commands::post_bump_hook(args),
                 VersionInfoCommand::Changelog(args) => commands::changelog(args),
                 VersionInfoCommand::PrLog(args) => commands::pr_log(args),
+                #[cfg(feature = "badges")]
                 VersionInfoCommand::ReleasePage(args) => commands::release_page(args),
+                #[cfg(feature = "badges")]
                 VersionInfoCommand::Badge(args) => commands::badge(args),
+                #[cfg(feature = "badges")]
                 VersionInfoCommand::UpdateReadme(args) => commands::update_readme(args),
                 VersionInfoCommand::Version => commands::build_version_default(),
             };
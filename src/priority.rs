@@ -0,0 +1,185 @@
+//! Dependency-light steps of the build version priority chain.
+//!
+//! This module only uses `std`, `anyhow`, `gix` and `toml`, so the crate's own
+//! `build.rs` can include it with `#[path = "src/priority.rs"]`. It must not
+//! refer to other modules of the crate.
+//!
+//! `build.rs` only runs the local steps ([`local_version`]), so its version
+//! matches [`compute_version_string`](crate::commands::compute_version_string)
+//! without the GitHub API step, `VERSION_INFO_DESCRIBE_FORMAT` and
+//! `VERSION_INFO_DIRTY_SUFFIX`.
+//!
+//! The steps, in order:
+//!
+//! 1. `BUILD_VERSION`, then `CARGO_PKG_VERSION_OVERRIDE` ([`env_override`])
+//! 2. GitHub API (only in the library, with the `github` feature)
+//! 3. Cargo.toml version + short SHA ([`manifest_version_with_sha`])
+//! 4. `0.0.0-dev-<short-sha>` ([`dev_version`])
+//!
+//! [`local_version`] chains steps 1, 3 and 4.
+//!
+//! [`env_override`]: crate::priority::env_override
+//! [`manifest_version_with_sha`]: crate::priority::manifest_version_with_sha
+//! [`dev_version`]: crate::priority::dev_version
+//! [`local_version`]: crate::priority::local_version
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+
+/// Environment variables that override the computed version, in priority
+/// order.
+pub const OVERRIDE_ENV_VARS: [&str; 2] = ["BUILD_VERSION", "CARGO_PKG_VERSION_OVERRIDE"];
+
+/// Explicit version override from [`OVERRIDE_ENV_VARS`], if set and not empty.
+#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+pub fn env_override() -> Option<String> {
+    OVERRIDE_ENV_VARS
+        .into_iter()
+        .find_map(|key| std::env::var(key).ok())
+        .filter(|v| !v.trim().is_empty())
+}
+
+/// Version from `[package]` in the manifest, ignoring empty and `0.0.0`.
+pub fn read_manifest_version(manifest: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(manifest).ok()?;
+    let value: toml::Value = toml::from_str(&contents).ok()?;
+    value
+        .get("package")
+        .and_then(|pkg| pkg.get("version"))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|version| !version.is_empty() && *version != "0.0.0")
+        .map(ToString::to_string)
+}
+
+/// Short SHA of HEAD, `None` outside a git repository.
+pub fn short_sha(repo_path: &Path) -> Option<String> {
+    let repo = gix::discover(repo_path).ok()?;
+    let head = repo.head().ok()?;
+    let commit_id = head.id()?;
+    let short = commit_id.shorten().ok()?;
+    Some(short.to_string())
+}
+
+/// Manifest version with the short SHA and `dirty_suffix` appended.
+///
+/// Returns the bare manifest version outside a git repository, and `None` if
+/// the manifest has no usable version (see [`read_manifest_version`]).
+pub fn manifest_version_with_sha(
+    manifest: &Path,
+    repo_path: &Path,
+    dirty_suffix: &str,
+) -> Option<String> {
+    let version = read_manifest_version(manifest)?;
+    Some(
+        short_sha(repo_path)
+            .map(|sha| format!("{version}-{sha}{dirty_suffix}"))
+            .unwrap_or(version),
+    )
+}
+
+/// Final fallback `0.0.0-dev-<short-sha>` with `dirty_suffix` appended.
+///
+/// Returns the version and the short SHA.
+///
+/// # Errors
+///
+/// Returns an error if the repository cannot be discovered or HEAD does not
+/// point to a commit.
+pub fn dev_version(repo_path: &Path, dirty_suffix: &str) -> Result<(String, String)> {
+    let repo = gix::discover(repo_path).with_context(|| {
+        format!(
+            "Failed to discover git repository at {}",
+            repo_path.display()
+        )
+    })?;
+
+    let head = repo.head().context("Failed to read HEAD")?;
+    let commit_id = head.id().context("HEAD does not point to a commit")?;
+    let short_sha = commit_id
+        .shorten()
+        .context("Failed to shorten commit SHA")?
+        .to_string();
+
+    Ok((
+        format!("0.0.0-dev-{}{}", short_sha, dirty_suffix),
+        short_sha,
+    ))
+}
+
+/// Version from the local steps of the chain, without GitHub or describe.
+///
+/// # Errors
+///
+/// Returns an error if no override or manifest version is available and the
+/// git fallback fails (see [`dev_version`]).
+pub fn local_version(repo_root: &Path) -> Result<String> {
+    if let Some(version) = env_override() {
+        return Ok(version);
+    }
+    if let Some(version) = manifest_version_with_sha(&repo_root.join("Cargo.toml"), repo_root, "") {
+        return Ok(version);
+    }
+    dev_version(repo_root, "").map(|(version, _)| version)
+}
+
+/// Existing git paths whose changes move HEAD, for `cargo:rerun-if-changed`.
+///
+/// Covers linked worktrees (whose `HEAD` lives outside the common git dir) and
+/// packed refs. A packed branch has no loose ref file until its next update,
+/// so the nearest existing directory above it is watched instead; creating
+/// the loose ref then changes that directory.
+pub fn head_rerun_paths(repo: &gix::Repository) -> Vec<PathBuf> {
+    let common_dir = repo.common_dir();
+
+    let mut paths = vec![repo.git_dir().join("HEAD")];
+    if let Ok(Some(name)) = repo.head_name() {
+        let loose = common_dir.join(name.as_bstr().to_string());
+        paths.extend(
+            loose
+                .ancestors()
+                .find(|path| path.exists())
+                .map(Path::to_path_buf),
+        );
+    }
+    paths.push(common_dir.join("packed-refs"));
+
+    // Drops missing paths and normalizes the common dir of linked worktrees
+    // (`.git/worktrees/<name>/../..`)
+    paths
+        .into_iter()
+        .filter_map(|path| path.canonicalize().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_manifest_version_ignores_placeholder() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+
+        std::fs::write(&manifest, "[package]\nname = \"a\"\nversion = \"0.0.0\"\n").unwrap();
+        assert_eq!(read_manifest_version(&manifest), None);
+
+        std::fs::write(
+            &manifest,
+            "[package]\nname = \"a\"\nversion = \" 1.2.3 \"\n",
+        )
+        .unwrap();
+        assert_eq!(read_manifest_version(&manifest).as_deref(), Some("1.2.3"));
+        assert_eq!(
+            manifest_version_with_sha(&manifest, dir.path(), ".dirty").as_deref(),
+            Some("1.2.3")
+        );
+    }
+}
@@ -0,0 +1,547 @@
+//! Crate registry queries via the sparse index protocol.
+//!
+//! Cargo's sparse index serves a `config.json` at the index root and one
+//! file per crate containing line-delimited JSON, one line per published
+//! version:
+//!
+//! ```text
+//! {"name":"my-crate","vers":"0.1.0","deps":[],"cksum":"...","features":{},"yanked":false}
+//! {"name":"my-crate","vers":"0.1.1","deps":[],"cksum":"...","features":{},"yanked":true}
+//! ```
+//!
+//! The file lives at a path derived from the lowercased crate name (see
+//! [`sparse_index_path`](crate::registry::sparse_index_path)).
+//!
+//! # Index Locations
+//!
+//! A [`RegistryIndex`](crate::registry::RegistryIndex) can point at:
+//!
+//! - crates.io (`https://index.crates.io`, the default)
+//! - any sparse index URL (`sparse+https://...`; a URL without `sparse+` is a
+//!   git index, which is not supported)
+//! - an alternative registry declared in `.cargo/config.toml`:
+//!
+//!   ```toml
+//!   [registries.my-registry]
+//!   index = "sparse+https://registry.example.com/index/"
+//!   ```
+//!
+//! - a local directory laid out like a sparse index (`file://...` or a plain
+//!   path), which is handy for testing and air-gapped mirrors
+
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use cargo_metadata::semver::Version;
+use serde::Deserialize;
+
+/// Sparse index URL of crates.io.
+pub const CRATES_IO_SPARSE_INDEX: &str = "https://index.crates.io";
+
+/// One line of a sparse index file (only the fields we need).
+#[derive(Debug, Deserialize)]
+struct IndexEntry {
+    vers: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+/// Contents of a sparse index's `config.json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexConfig {
+    /// Download URL template for crate files.
+    pub dl: String,
+    /// Web API base URL, if the registry has one.
+    #[serde(default)]
+    pub api: Option<String>,
+    /// Whether the registry requires authentication for all requests.
+    #[serde(default, rename = "auth-required")]
+    pub auth_required: bool,
+}
+
+/// Versions of a crate found in a registry index.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct RegistryVersions {
+    /// Non-yanked versions, sorted ascending by semver.
+    pub versions: Vec<String>,
+    /// Yanked versions, sorted ascending by semver.
+    pub yanked: Vec<String>,
+}
+
+impl RegistryVersions {
+    /// Highest non-yanked version, if any.
+    pub fn latest(&self) -> Option<&str> {
+        self.versions.last().map(String::as_str)
+    }
+
+    /// Whether `version` was ever published (yanked or not).
+    pub fn contains(&self, version: &str) -> bool {
+        self.versions.iter().any(|v| v == version) || self.yanked.iter().any(|v| v == version)
+    }
+
+    /// Whether `version` is published but yanked.
+    pub fn is_yanked(&self, version: &str) -> bool {
+        self.yanked.iter().any(|v| v == version)
+    }
+}
+
+/// Location of a sparse registry index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryIndex {
+    /// Sparse index served over HTTP(S); the URL has no trailing slash.
+    Http(String),
+    /// Sparse index laid out in a local directory.
+    Directory(PathBuf),
+}
+
+impl Default for RegistryIndex {
+    fn default() -> Self {
+        Self::crates_io()
+    }
+}
+
+impl std::fmt::Display for RegistryIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryIndex::Http(url) => write!(f, "{}", url),
+            RegistryIndex::Directory(path) => write!(f, "file://{}", path.display()),
+        }
+    }
+}
+
+impl RegistryIndex {
+    /// The crates.io sparse index.
+    pub fn crates_io() -> Self {
+        Self::Http(CRATES_IO_SPARSE_INDEX.to_string())
+    }
+
+    /// Parse an index URL.
+    ///
+    /// Accepts `sparse+https://...`, `sparse+http://...`, `file://...` and
+    /// plain directory paths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for git index URLs (`https://...` or `http://...`
+    /// without the `sparse+` prefix).
+    pub fn from_url(url: &str) -> Result<Self> {
+        let url = url.trim();
+
+        if let Some(sparse) = url.strip_prefix("sparse+") {
+            return Ok(Self::Http(sparse.trim_end_matches('/').to_string()));
+        }
+        if url.starts_with("http://") || url.starts_with("https://") || url.contains("+http") {
+            anyhow::bail!(
+                "Registry index {} is a git index, which is not supported. Use the registry's \
+                 sparse index instead (`sparse+https://...`)",
+                url
+            );
+        }
+
+        let path = url.strip_prefix("file://").unwrap_or(url);
+        Ok(Self::Directory(PathBuf::from(path)))
+    }
+
+    /// Resolve the index to query from command-line options.
+    ///
+    /// An explicit `index_url` wins, then a named `registry` looked up via
+    /// [`find_registry_index_url`] starting at `start_dir`, then crates.io.
+    pub fn resolve(
+        registry: Option<&str>,
+        index_url: Option<&str>,
+        start_dir: &Path,
+    ) -> Result<Self> {
+        if let Some(url) = index_url {
+            return Self::from_url(url);
+        }
+
+        match registry {
+            Some("crates-io") | None => Ok(Self::crates_io()),
+            Some(name) => {
+                let url = find_registry_index_url(name, start_dir)?.with_context(|| {
+                    format!(
+                        "Registry `{}` is not defined. Add it to .cargo/config.toml:\n\n\
+                         [registries.{}]\nindex = \"sparse+https://...\"",
+                        name, name
+                    )
+                })?;
+                Self::from_url(&url)
+            }
+        }
+    }
+
+    /// Fetch and parse the index's `config.json`.
+    pub async fn fetch_config(&self) -> Result<IndexConfig> {
+        let content = self.fetch("config.json").await?.with_context(|| {
+            format!(
+                "No config.json found at {} (only sparse indexes are supported)",
+                self
+            )
+        })?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid config.json in registry index {}", self))
+    }
+
+    /// Look up all published versions of a crate.
+    ///
+    /// Validates the index via its `config.json` first. A crate that was
+    /// never published yields empty [`RegistryVersions`].
+    pub async fn lookup(&self, crate_name: &str) -> Result<RegistryVersions> {
+        let config = self.fetch_config().await?;
+        let content = match self.fetch(&sparse_index_path(crate_name)).await {
+            Ok(content) => content,
+            Err(e) if config.auth_required => {
+                return Err(e.context(format!(
+                    "Registry index {} requires authentication, which is not supported",
+                    self
+                )));
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(content
+            .map(|content| parse_index_file(&content))
+            .unwrap_or_default())
+    }
+
+    /// Fetch a file relative to the index root, `None` if it doesn't exist.
+    async fn fetch(&self, relative_path: &str) -> Result<Option<String>> {
+        match self {
+            RegistryIndex::Directory(root) => {
+                let path = root.join(relative_path);
+                if !path.exists() {
+                    return Ok(None);
+                }
+                let content = tokio::fs::read_to_string(&path)
+                    .await
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                Ok(Some(content))
+            }
+            RegistryIndex::Http(base) => {
+                let url = format!("{}/{}", base, relative_path);
+                let client = reqwest::Client::builder()
+                    .timeout(std::time::Duration::from_secs(10))
+                    .build()
+                    .context("Failed to create HTTP client")?;
+
+                let response = client
+                    .get(&url)
+                    .header("User-Agent", "cargo-version-info")
+                    .send()
+                    .await
+                    .with_context(|| format!("Failed to query registry index at {}", url))?;
+
+                // Sparse indexes may answer 404, 410 or 451 for missing crates
+                if matches!(response.status().as_u16(), 404 | 410 | 451) {
+                    return Ok(None);
+                }
+
+                let content = response
+                    .error_for_status()
+                    .with_context(|| format!("Registry index request failed for {}", url))?
+                    .text()
+                    .await
+                    .context("Failed to read registry index response")?;
+                Ok(Some(content))
+            }
+        }
+    }
+}
+
+/// Find the index URL of a named registry in cargo configuration.
+///
+/// Checks the `CARGO_REGISTRIES_<NAME>_INDEX` environment variable, then
+/// `[registries.<name>] index` in `.cargo/config.toml` (or `.cargo/config`)
+/// files from `start_dir` upwards, then `$CARGO_HOME/config.toml`. Returns
+/// `None` if the registry is not declared anywhere.
+#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+pub fn find_registry_index_url(name: &str, start_dir: &Path) -> Result<Option<String>> {
+    let env_key = format!(
+        "CARGO_REGISTRIES_{}_INDEX",
+        name.to_uppercase().replace('-', "_")
+    );
+    if let Ok(url) = std::env::var(&env_key)
+        && !url.trim().is_empty()
+    {
+        return Ok(Some(url));
+    }
+
+    let start_dir = start_dir
+        .canonicalize()
+        .unwrap_or_else(|_| start_dir.to_path_buf());
+    let mut candidates: Vec<PathBuf> = start_dir
+        .ancestors()
+        .flat_map(|dir| {
+            [
+                dir.join(".cargo").join("config.toml"),
+                dir.join(".cargo").join("config"),
+            ]
+        })
+        .collect();
+
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")));
+    if let Some(cargo_home) = cargo_home {
+        candidates.push(cargo_home.join("config.toml"));
+        candidates.push(cargo_home.join("config"));
+    }
+
+    for path in candidates.iter().filter(|path| path.is_file()) {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let config: toml::Value = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        if let Some(url) = config
+            .get("registries")
+            .and_then(|registries| registries.get(name))
+            .and_then(|registry| registry.get("index"))
+            .and_then(|index| index.as_str())
+        {
+            return Ok(Some(url.to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Compute the sparse index path for a crate name.
+///
+/// Follows cargo's layout: `1/{name}`, `2/{name}`, `3/{c}/{name}` and
+/// `{c1c2}/{c3c4}/{name}` for longer names, all lowercased.
+///
+/// # Examples
+///
+/// ```
+/// use cargo_version_info::registry::sparse_index_path;
+///
+/// assert_eq!(sparse_index_path("a"), "1/a");
+/// assert_eq!(sparse_index_path("ab"), "2/ab");
+/// assert_eq!(sparse_index_path("abc"), "3/a/abc");
+/// assert_eq!(sparse_index_path("My-Crate"), "my/-c/my-crate");
+/// ```
+pub fn sparse_index_path(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+/// Parse a sparse index file into sorted published and yanked versions.
+///
+/// Lines that fail to parse (or carry an invalid semver) are skipped.
+pub fn parse_index_file(content: &str) -> RegistryVersions {
+    let mut versions: Vec<Version> = Vec::new();
+    let mut yanked: Vec<Version> = Vec::new();
+
+    for entry in content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<IndexEntry>(line).ok())
+    {
+        let Ok(version) = Version::parse(&entry.vers) else {
+            continue;
+        };
+        if entry.yanked {
+            yanked.push(version);
+        } else {
+            versions.push(version);
+        }
+    }
+
+    versions.sort();
+    yanked.sort();
+
+    RegistryVersions {
+        versions: versions.iter().map(ToString::to_string).collect(),
+        yanked: yanked.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// Parse a sparse index file and return the highest non-yanked version.
+pub fn latest_version_from_index(content: &str) -> Option<String> {
+    parse_index_file(content).latest().map(ToString::to_string)
+}
+
+/// Get the latest published (non-yanked) version of a crate.
+///
+/// Returns `None` if the crate has never been published to `index`.
+pub async fn get_latest_published_version(
+    index: &RegistryIndex,
+    crate_name: &str,
+) -> Result<Option<String>> {
+    let versions = index.lookup(crate_name).await?;
+    Ok(versions.latest().map(ToString::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a directory-backed sparse index containing one crate file.
+    fn create_local_index(crate_name: &str, lines: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("config.json"),
+            r#"{"dl":"https://example.com/api/v1/crates"}"#,
+        )
+        .unwrap();
+        let crate_path = dir.path().join(sparse_index_path(crate_name));
+        std::fs::create_dir_all(crate_path.parent().unwrap()).unwrap();
+        std::fs::write(&crate_path, lines.join("\n")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_sparse_index_path_long_name() {
+        assert_eq!(
+            sparse_index_path("cargo-version-info"),
+            "ca/rg/cargo-version-info"
+        );
+    }
+
+    #[test]
+    fn test_latest_version_from_index_skips_yanked() {
+        let content = r#"{"name":"x","vers":"0.1.0","yanked":false}
+{"name":"x","vers":"0.3.0","yanked":true}
+{"name":"x","vers":"0.2.0","yanked":false}
+"#;
+
+        assert_eq!(
+            latest_version_from_index(content),
+            Some("0.2.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_latest_version_from_index_semver_order() {
+        let content = r#"{"name":"x","vers":"0.10.0"}
+{"name":"x","vers":"0.9.0"}
+"#;
+
+        assert_eq!(
+            latest_version_from_index(content),
+            Some("0.10.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_latest_version_from_index_empty() {
+        assert_eq!(latest_version_from_index(""), None);
+    }
+
+    #[test]
+    fn test_parse_index_file_reports_yanked_separately() {
+        let content = r#"{"name":"x","vers":"0.2.0","yanked":true}
+{"name":"x","vers":"0.1.0","yanked":false}
+"#;
+
+        let versions = parse_index_file(content);
+
+        assert_eq!(versions.versions, vec!["0.1.0"]);
+        assert_eq!(versions.yanked, vec!["0.2.0"]);
+        assert!(versions.contains("0.2.0"));
+        assert!(versions.is_yanked("0.2.0"));
+        assert!(!versions.is_yanked("0.1.0"));
+    }
+
+    #[test]
+    fn test_registry_index_from_url() {
+        assert_eq!(
+            RegistryIndex::from_url("sparse+https://registry.example.com/index/").unwrap(),
+            RegistryIndex::Http("https://registry.example.com/index".to_string())
+        );
+        assert_eq!(
+            RegistryIndex::from_url("file:///srv/index").unwrap(),
+            RegistryIndex::Directory(PathBuf::from("/srv/index"))
+        );
+    }
+
+    #[test]
+    fn test_registry_index_from_url_rejects_git_index() {
+        let error = RegistryIndex::from_url("https://github.com/rust-lang/crates.io-index")
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("is a git index"));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_local_directory_index() {
+        let dir = create_local_index(
+            "my-crate",
+            &[
+                r#"{"name":"my-crate","vers":"0.1.0","yanked":false}"#,
+                r#"{"name":"my-crate","vers":"0.1.1","yanked":true}"#,
+            ],
+        );
+        let index = RegistryIndex::from_url(&format!("file://{}", dir.path().display())).unwrap();
+
+        let versions = index.lookup("my-crate").await.unwrap();
+
+        assert_eq!(versions.latest(), Some("0.1.0"));
+        assert_eq!(versions.yanked, vec!["0.1.1"]);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_local_directory_index_unpublished_crate() {
+        let dir = create_local_index("my-crate", &[]);
+        let index = RegistryIndex::Directory(dir.path().to_path_buf());
+
+        let versions = index.lookup("other-crate").await.unwrap();
+
+        assert_eq!(versions, RegistryVersions::default());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_missing_config_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = RegistryIndex::Directory(dir.path().to_path_buf());
+
+        let result = index.lookup("my-crate").await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_find_registry_index_url_from_cargo_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".cargo")).unwrap();
+        std::fs::write(
+            dir.path().join(".cargo").join("config.toml"),
+            "[registries.my-registry]\nindex = \"sparse+https://registry.example.com/index/\"\n",
+        )
+        .unwrap();
+        let nested = dir.path().join("crates").join("member");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let url = find_registry_index_url("my-registry", &nested).unwrap();
+
+        assert_eq!(
+            url.as_deref(),
+            Some("sparse+https://registry.example.com/index/")
+        );
+        assert_eq!(
+            RegistryIndex::resolve(Some("my-registry"), None, &nested).unwrap(),
+            RegistryIndex::Http("https://registry.example.com/index".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_unknown_registry_fails() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let result = RegistryIndex::resolve(Some("no-such-registry-xyz"), None, dir.path());
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,19 @@
+//! Helpers shared by the unit tests.
+
+use std::path::Path;
+
+/// Run git in `dir`, assert that it succeeded and return its stdout.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
@@ -0,0 +1,307 @@
+//! Working tree status of a git repository.
+//!
+//! Only the `index` and `blob-diff` features of gix are enabled, so instead
+//! of a full `git status` this compares three states directly:
+//!
+//! ```text
+//! HEAD tree  ←→  index  ←→  files on disk
+//!   (staged changes)   (unstaged changes)
+//! ```
+//!
+//! Like git, files whose stat data (size, timestamps, inode) still matches the
+//! index are taken as unchanged. Other files are run through the repository's
+//! filters (`core.autocrlf`, `.gitattributes` `eol`/`filter`, e.g. git LFS)
+//! and hashed into a blob id, so timestamps touched without a content change
+//! don't count as modified.
+//!
+//! Untracked files are ignored by default. When they should count (see
+//! [`INCLUDE_UNTRACKED_ENV`](crate::worktree::INCLUDE_UNTRACKED_ENV)), the
+//! worktree is walked and every file that is neither in the index nor excluded
+//! by `.gitignore` is reported.
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{
+    Context,
+    Result,
+};
+
+/// Environment variable that makes untracked files count as dirty.
+///
+/// Read by [`compute_version_string`](crate::commands::compute_version_string)
+/// and [`compute_dirty`](crate::commands::compute_dirty); accepts `1` or
+/// `true`.
+pub const INCLUDE_UNTRACKED_ENV: &str = "VERSION_INFO_INCLUDE_UNTRACKED";
+
+/// Whether the working tree has uncommitted changes.
+///
+/// Staged and unstaged changes to tracked files always count; untracked
+/// (and not ignored) files only count if `include_untracked` is set.
+pub fn is_dirty(repo: &gix::Repository, include_untracked: bool) -> Result<bool> {
+    if !modified_paths(repo)?.is_empty() {
+        return Ok(true);
+    }
+    Ok(include_untracked && !untracked_paths(repo)?.is_empty())
+}
+
+/// Whether [`INCLUDE_UNTRACKED_ENV`] is set to `1` or `true`.
+#[allow(clippy::disallowed_methods)] // CLI tool needs direct env access
+pub fn include_untracked_from_env() -> bool {
+    std::env::var(INCLUDE_UNTRACKED_ENV)
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true"))
+        .unwrap_or(false)
+}
+
+/// Find files in the worktree that are neither tracked nor ignored.
+///
+/// Ignored directories are skipped entirely, and a directory holding another
+/// git repository is reported as a whole (with a trailing `/`), like
+/// `git status` does.
+pub fn untracked_paths(repo: &gix::Repository) -> Result<Vec<String>> {
+    let workdir = repo
+        .workdir()
+        .context("Cannot check a bare repository for untracked files")?;
+    let index = repo.index_or_empty().context("Failed to read git index")?;
+    let tracked: HashSet<String> = index
+        .entries()
+        .iter()
+        .map(|entry| entry.path(&index).to_string())
+        .collect();
+    let mut excludes = repo
+        .excludes(&index, None, Default::default())
+        .context("Failed to read .gitignore files")?;
+
+    let mut untracked = Vec::new();
+    let mut pending = vec![String::new()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(workdir.join(&dir)).with_context(|| {
+            format!("Failed to read directory {}", workdir.join(&dir).display())
+        })?;
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if dir.is_empty() && name == ".git" {
+                continue;
+            }
+            let relative = format!("{}{}", dir, name);
+            let is_dir = entry.file_type().is_ok_and(|kind| kind.is_dir());
+            let mode = is_dir.then_some(gix::index::entry::Mode::DIR);
+            let excluded = excludes
+                .at_path(&relative, mode)
+                .is_ok_and(|platform| platform.is_excluded());
+
+            if excluded || tracked.contains(&relative) {
+                continue;
+            }
+            if !is_dir {
+                untracked.push(relative);
+            } else if entry.path().join(".git").exists() {
+                untracked.push(format!("{}/", relative));
+            } else {
+                pending.push(format!("{}/", relative));
+            }
+        }
+    }
+    untracked.sort();
+
+    Ok(untracked)
+}
+
+/// Find tracked paths that differ between HEAD, the index and the worktree.
+///
+/// Compares the index with the HEAD tree (staged changes) and each indexed
+/// file on disk with its index entry (unstaged changes). Untracked files are
+/// ignored.
+pub fn modified_paths(repo: &gix::Repository) -> Result<Vec<String>> {
+    let workdir = repo
+        .workdir()
+        .context("Cannot check a bare repository for uncommitted changes")?;
+    let head_tree_id = repo
+        .head_commit()
+        .context("Failed to read HEAD commit")?
+        .tree_id()
+        .context("Failed to read HEAD tree")?;
+    let head_index = repo
+        .index_from_tree(&head_tree_id)
+        .context("Failed to build index from HEAD tree")?;
+    let (mut pipeline, index) = repo
+        .filter_pipeline(None)
+        .context("Failed to set up the worktree filters")?;
+    let stat_options = repo
+        .stat_options()
+        .context("Failed to read the stat options from git config")?;
+
+    let mut head_entries: HashMap<&[u8], (gix::ObjectId, gix::index::entry::Mode)> = head_index
+        .entries()
+        .iter()
+        .map(|entry| (entry.path(&head_index).as_ref(), (entry.id, entry.mode)))
+        .collect();
+
+    let mut modified = Vec::new();
+    for entry in index.entries() {
+        let path = entry.path(&index);
+        let key: &[u8] = path.as_ref();
+        let staged = head_entries.remove(key) != Some((entry.id, entry.mode));
+        if staged || is_worktree_modified(&mut pipeline, &index, workdir, entry, stat_options)? {
+            modified.push(path.to_string());
+        }
+    }
+    // Whatever is left in HEAD was deleted from the index
+    modified.extend(
+        head_entries
+            .keys()
+            .map(|path| String::from_utf8_lossy(path).into_owned()),
+    );
+    modified.sort();
+
+    Ok(modified)
+}
+
+/// Whether the worktree file of an index entry differs from its blob.
+///
+/// Entries whose stat data matches (and that were not written in the same
+/// second as the index, see "racy git") are not read at all. Other files are
+/// converted to their git representation through `pipeline` before hashing.
+fn is_worktree_modified(
+    pipeline: &mut gix::filter::Pipeline<'_>,
+    index: &gix::index::State,
+    workdir: &Path,
+    entry: &gix::index::Entry,
+    stat_options: gix::index::entry::stat::Options,
+) -> Result<bool> {
+    use gix::index::entry::{
+        Flags,
+        Mode,
+        Stat,
+    };
+
+    if entry
+        .flags
+        .intersects(Flags::SKIP_WORKTREE | Flags::ASSUME_VALID)
+    {
+        return Ok(false);
+    }
+    if entry.mode != Mode::FILE
+        && entry.mode != Mode::FILE_EXECUTABLE
+        && entry.mode != Mode::SYMLINK
+    {
+        // Submodules and sparse directories are not compared
+        return Ok(false);
+    }
+
+    let path = entry.path(index);
+    let relative = gix::path::from_bstr(path);
+    let file_path = workdir.join(&relative);
+    let Ok(metadata) = gix::index::fs::Metadata::from_path_no_follow(&file_path) else {
+        return Ok(true);
+    };
+    if (entry.mode == Mode::SYMLINK) != metadata.is_symlink() {
+        return Ok(true);
+    }
+    if Stat::from_fs(&metadata).is_ok_and(|stat| {
+        entry.stat.matches(&stat, stat_options)
+            && !entry.stat.is_racy(index.timestamp(), stat_options)
+    }) {
+        return Ok(false);
+    }
+
+    let content = if entry.mode == Mode::SYMLINK {
+        match std::fs::read_link(&file_path) {
+            Ok(target) => gix::path::into_bstr(target).into_owned().into(),
+            Err(_) => return Ok(true),
+        }
+    } else {
+        let Ok(file) = std::fs::File::open(&file_path) else {
+            return Ok(true);
+        };
+        let mut content = Vec::new();
+        pipeline
+            .convert_to_git(file, &relative, index)
+            .with_context(|| format!("Failed to apply the git filters to {}", path))?
+            .read_to_end(&mut content)
+            .with_context(|| format!("Failed to read {}", file_path.display()))?;
+        content
+    };
+
+    let id = gix::objs::compute_hash(entry.id.kind(), gix::object::Kind::Blob, &content)
+        .with_context(|| format!("Failed to hash {}", path))?;
+    Ok(id != entry.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::git;
+
+    #[test]
+    fn test_modified_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.email", "test@example.com"]);
+        git(dir.path(), &["config", "user.name", "Test User"]);
+        git(dir.path(), &["config", "commit.gpgsign", "false"]);
+        std::fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b\n").unwrap();
+        git(dir.path(), &["add", "a.txt", "b.txt"]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        let repo = gix::discover(dir.path()).unwrap();
+        assert!(!is_dirty(&repo, false).unwrap());
+
+        std::fs::write(dir.path().join("a.txt"), "changed\n").unwrap();
+        git(dir.path(), &["rm", "-q", "b.txt"]);
+        std::fs::write(dir.path().join("untracked.txt"), "new\n").unwrap();
+
+        assert_eq!(modified_paths(&repo).unwrap(), vec!["a.txt", "b.txt"]);
+        assert!(is_dirty(&repo, false).unwrap());
+    }
+
+    #[test]
+    fn test_modified_paths_applies_eol_conversion() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.email", "test@example.com"]);
+        git(dir.path(), &["config", "user.name", "Test User"]);
+        git(dir.path(), &["config", "commit.gpgsign", "false"]);
+        std::fs::write(dir.path().join(".gitattributes"), "*.txt text eol=crlf\n").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        git(dir.path(), &["add", ".gitattributes", "a.txt"]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        let repo = gix::discover(dir.path()).unwrap();
+
+        // What a checkout writes with `eol=crlf`; the blob still has LF
+        std::fs::write(dir.path().join("a.txt"), "a\r\n").unwrap();
+        assert!(modified_paths(&repo).unwrap().is_empty());
+
+        std::fs::write(dir.path().join("a.txt"), "b\r\n").unwrap();
+        assert_eq!(modified_paths(&repo).unwrap(), vec!["a.txt"]);
+    }
+
+    #[test]
+    fn test_untracked_paths_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.email", "test@example.com"]);
+        git(dir.path(), &["config", "user.name", "Test User"]);
+        git(dir.path(), &["config", "commit.gpgsign", "false"]);
+        std::fs::write(dir.path().join(".gitignore"), "target/\n*.log\n").unwrap();
+        git(dir.path(), &["add", ".gitignore"]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        let repo = gix::discover(dir.path()).unwrap();
+
+        std::fs::create_dir_all(dir.path().join("target").join("debug")).unwrap();
+        std::fs::write(dir.path().join("target").join("debug").join("app"), "").unwrap();
+        std::fs::write(dir.path().join("build.log"), "").unwrap();
+        assert!(untracked_paths(&repo).unwrap().is_empty());
+        assert!(!is_dirty(&repo, true).unwrap());
+
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("new.rs"), "").unwrap();
+        assert_eq!(untracked_paths(&repo).unwrap(), vec!["src/new.rs"]);
+        assert!(!is_dirty(&repo, false).unwrap());
+        assert!(is_dirty(&repo, true).unwrap());
+    }
+}